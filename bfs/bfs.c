@@ -2,6 +2,9 @@
 #include <stdlib.h>
 #include <time.h>
 #include <stdbool.h>
+#include "bfs_algo.h"
+#include "bench_input.h"
+#include "bench_rng.h"
 
 // Function to dynamically allocate a 2D array for the adjacency matrix
 int** createAdjMatrix(int n) {
@@ -20,11 +23,11 @@ void freeAdjMatrix(int** matrix, int n) {
 }
 
 // Function to generate a random graph
-void generateRandomGraph(int** adjMatrix, int n) {
-    srand(time(NULL));
+void generateRandomGraph(int** adjMatrix, int n, uint64_t seed) {
+    BenchRng rng = {seed};
     for (int i = 0; i < n; i++) {
         for (int j = i + 1; j < n; j++) {
-            if (rand() % 2) { // Randomly connect nodes with a 50% probability
+            if (bench_rng_below(&rng, 2)) { // Randomly connect nodes with a 50% probability
                 adjMatrix[i][j] = 1;
                 adjMatrix[j][i] = 1;
             }
@@ -32,6 +35,19 @@ void generateRandomGraph(int** adjMatrix, int n) {
     }
 }
 
+// Function to load a graph written by the Rust benchmark with --dump-input
+int loadGraph(int** adjMatrix, int n, const char* path) {
+    int* data = bench_input_load(path, n, 0, (long long)n * n);
+    if (!data) {
+        return 1;
+    }
+    for (int i = 0; i < n; i++) {
+        memcpy(adjMatrix[i], data + (long long)i * n, n * sizeof(int));
+    }
+    free(data);
+    return 0;
+}
+
 // Function to print the adjacency matrix
 void printAdjMatrix(int** adjMatrix, int n) {
     printf("Adjacency Matrix:\n");
@@ -43,30 +59,6 @@ void printAdjMatrix(int** adjMatrix, int n) {
     }
 }
 
-void bfs(int** adjMatrix, int n, int start) {
-    bool* visited = (bool*)calloc(n, sizeof(bool));
-    int* queue = (int*)malloc(n * sizeof(int));
-    int front = 0, rear = 0;
-
-    visited[start] = true;
-    queue[rear++] = start;
-
-    while (front < rear) {
-        int current = queue[front++];
-        printf("Visited node %d\n", current);
-
-        for (int i = 0; i < n; i++) {
-            if (adjMatrix[current][i] == 1 && !visited[i]) {
-                visited[i] = true;
-                queue[rear++] = i;
-            }
-        }
-    }
-
-    free(visited);
-    free(queue);
-}
-
 // Function to compute the time difference using timespec struct
 double diff_timespec(struct timespec *time1, struct timespec *time0) {
     return (time1->tv_sec - time0->tv_sec)
@@ -74,8 +66,12 @@ double diff_timespec(struct timespec *time1, struct timespec *time0) {
 }
 
 int main(int argc, char* argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <number_of_nodes>\n", argv[0]);
+    uint64_t seed = BENCH_DEFAULT_SEED;
+    if (bench_take_seed(&argc, argv, &seed) != 0) {
+        return 1;
+    }
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <number_of_nodes> [input_file] [--seed N]\n", argv[0]);
         return 1;
     }
 
@@ -87,20 +83,36 @@ int main(int argc, char* argv[]) {
     }
 
     int** adjMatrix = createAdjMatrix(size);
-    generateRandomGraph(adjMatrix, size);
+    if (argc == 3) {
+        if (loadGraph(adjMatrix, size, argv[2]) != 0) {
+            return 1;
+        }
+    } else {
+        generateRandomGraph(adjMatrix, size, seed);
+    }
+
+    // Visit order, search tree and levels, filled in by the search
+    int* order = (int*)malloc(size * sizeof(int));
+    int* parent = (int*)malloc(size * sizeof(int));
+    int* level = (int*)malloc(size * sizeof(int));
+    memset(parent, -1, size * sizeof(int));
+    memset(level, -1, size * sizeof(int));
 
     // Time BFS execution
     struct timespec start_time, end_time;
     double time_elapsed;
 
     clock_gettime(CLOCK_MONOTONIC, &start_time); // Start time
-    bfs(adjMatrix, size, 0);  // Perform BFS
+    int reached = bfs(adjMatrix, size, 0, order, parent, level);  // Perform BFS
     clock_gettime(CLOCK_MONOTONIC, &end_time); // End time
 
     time_elapsed = diff_timespec(&end_time, &start_time); // Calculate time difference
-    printf("Time taken to search graph of size %d: %f seconds\n", size, time_elapsed);
+    printf("Time taken to search graph of size %d: %f seconds, %d nodes reached\n", size, time_elapsed, reached);
 
     freeAdjMatrix(adjMatrix, size);
+    free(order);
+    free(parent);
+    free(level);
 
     return 0;
 }
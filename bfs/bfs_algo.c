@@ -0,0 +1,27 @@
+#include <stdlib.h>
+#include "bfs_algo.h"
+
+// Breadth-First Search (BFS)
+int bfs(int** adjMatrix, int n, int start, int* order, int* parent, int* level) {
+    // The visit order doubles as the queue: nodes are dequeued in the
+    // order they were reached.
+    int front = 0, rear = 0;
+
+    parent[start] = start;
+    level[start] = 0;
+    order[rear++] = start;
+
+    while (front < rear) {
+        int current = order[front++];
+
+        for (int i = 0; i < n; i++) {
+            if (adjMatrix[current][i] == 1 && parent[i] == -1) {
+                parent[i] = current;
+                level[i] = level[current] + 1;
+                order[rear++] = i;
+            }
+        }
+    }
+
+    return rear;
+}
@@ -0,0 +1,10 @@
+#ifndef BFS_ALGO_H
+#define BFS_ALGO_H
+
+// Breadth-First Search (BFS) over an n x n adjacency matrix.
+// parent and level must hold n entries set to -1; order must hold n entries.
+// Fills in the visit order, the BFS tree and each node's distance from
+// start, and returns the number of nodes reached.
+int bfs(int** adjMatrix, int n, int start, int* order, int* parent, int* level);
+
+#endif
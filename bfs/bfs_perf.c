@@ -2,10 +2,10 @@
 #include <stdlib.h>
 #include <time.h>
 #include <stdbool.h>
-#include <unistd.h>
-#include <sys/types.h>
-#include <sys/wait.h>
-#include <signal.h>
+#include "bfs_algo.h"
+#include "bench_input.h"
+#include "bench_rng.h"
+#include "bench_counters.h"
 
 // Function to dynamically allocate a 2D array for the adjacency matrix
 int** createAdjMatrix(int n) {
@@ -24,11 +24,11 @@ void freeAdjMatrix(int** matrix, int n) {
 }
 
 // Function to generate a random graph
-void generateRandomGraph(int** adjMatrix, int n) {
-    srand(time(NULL));
+void generateRandomGraph(int** adjMatrix, int n, uint64_t seed) {
+    BenchRng rng = {seed};
     for (int i = 0; i < n; i++) {
         for (int j = i + 1; j < n; j++) {
-            if (rand() % 2) { // Randomly connect nodes with a 50% probability
+            if (bench_rng_below(&rng, 2)) { // Randomly connect nodes with a 50% probability
                 adjMatrix[i][j] = 1;
                 adjMatrix[j][i] = 1;
             }
@@ -36,34 +36,26 @@ void generateRandomGraph(int** adjMatrix, int n) {
     }
 }
 
-// Breadth-First Search (BFS)
-void bfs(int** adjMatrix, int n, int start) {
-    bool* visited = (bool*)calloc(n, sizeof(bool));
-    int* queue = (int*)malloc(n * sizeof(int));
-    int front = 0, rear = 0;
-
-    visited[start] = true;
-    queue[rear++] = start;
-
-    while (front < rear) {
-        int current = queue[front++];
-        // printf("Visited node %d\n", current);
-
-        for (int i = 0; i < n; i++) {
-            if (adjMatrix[current][i] == 1 && !visited[i]) {
-                visited[i] = true;
-                queue[rear++] = i;
-            }
-        }
+// Function to load a graph written by the Rust benchmark with --dump-input
+int loadGraph(int** adjMatrix, int n, const char* path) {
+    int* data = bench_input_load(path, n, 0, (long long)n * n);
+    if (!data) {
+        return 1;
     }
-
-    free(visited);
-    free(queue);
+    for (int i = 0; i < n; i++) {
+        memcpy(adjMatrix[i], data + (long long)i * n, n * sizeof(int));
+    }
+    free(data);
+    return 0;
 }
 
 int main(int argc, char* argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <number_of_nodes>\n", argv[0]);
+    uint64_t seed = BENCH_DEFAULT_SEED;
+    if (bench_take_seed(&argc, argv, &seed) != 0) {
+        return 1;
+    }
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <number_of_nodes> [input_file] [--seed N]\n", argv[0]);
         return 1;
     }
 
@@ -74,48 +66,41 @@ int main(int argc, char* argv[]) {
     }
 
     int** adjMatrix = createAdjMatrix(size);
-    generateRandomGraph(adjMatrix, size);
-
-    int pid = getpid();
-    int cpid = fork(); 
-
-    if (cpid == 0) { 
-        // Child Process: Run `perf stat` and redirect output to log
-        char buf[300];
-        snprintf(buf, sizeof(buf), "perf stat -e cycles,instructions,cache-references,cache-misses -p %d > bfs_c_perf_output.log 2>&1", pid);
-        execl("/bin/sh", "sh", "-c", buf, NULL);
-        perror("execl failed");
-        exit(1);
-    } else { 
-        // Parent Process: Set process group and run BFS
-        setpgid(cpid, 0); 
-        sleep(1);  
-
-        clock_t start_time = clock();
-        bfs(adjMatrix, size, 0);
-        clock_t end_time = clock();
-
-        kill(-cpid, SIGINT);
-        waitpid(cpid, NULL, 0); 
-
-        // Read and print perf output
-        FILE *perf_file = fopen("bfs_c_perf_output.log", "r");
-        if (perf_file) {
-            char line[256];
-            printf("\n[ Perf Stat Output ]\n");
-            while (fgets(line, sizeof(line), perf_file)) {
-                printf("%s", line);
-            }
-            fclose(perf_file);
-        } else {
-            perror("Failed to read perf stat log");
+    if (argc == 3) {
+        if (loadGraph(adjMatrix, size, argv[2]) != 0) {
+            return 1;
         }
-
-        double time_elapsed = ((double)(end_time - start_time)) / CLOCKS_PER_SEC;
-        printf("\nTime taken to search graph of size %d: %f seconds\n", size, time_elapsed);
-
-        freeAdjMatrix(adjMatrix, size);
+    } else {
+        generateRandomGraph(adjMatrix, size, seed);
     }
 
+    // Visit order, search tree and levels, filled in by the search
+    int* order = (int*)malloc(size * sizeof(int));
+    int* parent = (int*)malloc(size * sizeof(int));
+    int* level = (int*)malloc(size * sizeof(int));
+    memset(parent, -1, size * sizeof(int));
+    memset(level, -1, size * sizeof(int));
+
+    // Counters opened in-process and enabled only around the search
+    BenchCounters counters;
+    bench_counters_open(&counters);
+
+    bench_counters_start(&counters);
+    double start_time = bench_now();
+    int reached = bfs(adjMatrix, size, 0, order, parent, level);
+    double end_time = bench_now();
+    bench_counters_stop(&counters);
+
+    bench_counters_print(&counters, argv[0]);
+    bench_counters_close(&counters);
+
+    double time_elapsed = end_time - start_time;
+    printf("\nTime taken to search graph of size %d: %f seconds, %d nodes reached\n", size, time_elapsed, reached);
+
+    freeAdjMatrix(adjMatrix, size);
+    free(order);
+    free(parent);
+    free(level);
+
     return 0;
 }
@@ -0,0 +1,10 @@
+// Compiles the C implementation shared with the Makefile so the benchmarks
+// can call it through FFI.
+fn main() {
+    println!("cargo:rerun-if-changed=bfs_algo.c");
+    println!("cargo:rerun-if-changed=bfs_algo.h");
+    cc::Build::new()
+        .file("bfs_algo.c")
+        .opt_level(3)
+        .compile("bfs_algo");
+}
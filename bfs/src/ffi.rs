@@ -0,0 +1,44 @@
+//! Bindings to the C implementation in `bfs_algo.c`.
+
+use std::os::raw::c_int;
+
+use graph::Traversal;
+
+extern "C" {
+    #[link_name = "bfs"]
+    fn c_bfs(
+        adj_matrix: *const *const c_int,
+        n: c_int,
+        start: c_int,
+        order: *mut c_int,
+        parent: *mut c_int,
+        level: *mut c_int,
+    ) -> c_int;
+}
+
+/// Runs the C `bfs` over the same adjacency matrix, filling in `search`
+/// from its root. It takes an `int**`, so a table of row pointers is built
+/// first.
+pub fn bfs(adj_matrix: &[Vec<i32>], search: &mut Traversal) {
+    let n = adj_matrix.len();
+    assert!(search.root < n && search.parent.len() == n && search.level.len() == n);
+    assert!(search.reached() == 0 && search.order.capacity() >= n);
+    assert!(adj_matrix.iter().all(|row| row.len() == n));
+    let rows: Vec<*const c_int> = adj_matrix.iter().map(|row| row.as_ptr()).collect();
+    let n = c_int::try_from(n).expect("graph too large for the C implementation");
+    // SAFETY: the matrix is n x n and the C code only reads it. `parent`
+    // and `level` hold n entries, unreached ones are UNREACHED, which is -1
+    // as an int, and `order` has room for n. The C code writes `reached`
+    // entries of `order`, and never more than n.
+    unsafe {
+        let reached = c_bfs(
+            rows.as_ptr(),
+            n,
+            search.root as c_int,
+            search.order.as_mut_ptr().cast(),
+            search.parent.as_mut_ptr().cast(),
+            search.level.as_mut_ptr().cast(),
+        );
+        search.order.set_len(reached as usize);
+    }
+}
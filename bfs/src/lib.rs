@@ -0,0 +1,552 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+use graph::traversal::UNREACHED;
+use graph::{with_graph, AnyGraph, Graph, GraphOptions, Representation, Traversal};
+use harness::{Args, Benchmark, Language};
+
+pub mod ffi;
+pub mod parallel;
+
+pub use parallel::{bfs_parallel, bfs_rayon};
+
+/// Breadth-first search from `search.root`, recording the visit order, the
+/// BFS tree and every node's distance in `search`.
+pub fn bfs<G: Graph>(graph: &G, search: &mut Traversal) {
+    let mut queue = VecDeque::new();
+
+    search.start();
+    queue.push_back(search.root);
+
+    while let Some(current) = queue.pop_front() {
+        for i in graph.neighbors(current) {
+            if !search.is_reached(i) {
+                search.reach(i, current);
+                queue.push_back(i);
+            }
+        }
+    }
+}
+
+/// Level-synchronous BFS: the whole current level is expanded into the
+/// next one before moving on, with the two frontiers in separate vectors.
+pub fn bfs_frontier<G: Graph>(graph: &G, search: &mut Traversal) {
+    let mut frontier = vec![search.root];
+    let mut next = Vec::new();
+    search.start();
+
+    while !frontier.is_empty() {
+        for &current in &frontier {
+            for i in graph.neighbors(current) {
+                if !search.is_reached(i) {
+                    search.reach(i, current);
+                    next.push(i);
+                }
+            }
+        }
+        std::mem::swap(&mut frontier, &mut next);
+        next.clear();
+    }
+}
+
+/// One bit per node.
+struct Bitmap(Vec<u64>);
+
+impl Bitmap {
+    fn new(n: usize) -> Bitmap {
+        Bitmap(vec![0; n.div_ceil(64)])
+    }
+
+    #[inline]
+    fn contains(&self, i: usize) -> bool {
+        self.0[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    #[inline]
+    fn insert(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn clear(&mut self) {
+        self.0.fill(0);
+    }
+}
+
+/// Queue BFS that checks a visited bitmap, 1/32 the size of the parent
+/// array, so it stays in cache longer on large graphs.
+pub fn bfs_bitmap<G: Graph>(graph: &G, search: &mut Traversal) {
+    let mut visited = Bitmap::new(graph.node_count());
+    let mut queue = VecDeque::new();
+
+    search.start();
+    visited.insert(search.root);
+    queue.push_back(search.root);
+
+    while let Some(current) = queue.pop_front() {
+        for i in graph.neighbors(current) {
+            if !visited.contains(i) {
+                visited.insert(i);
+                search.reach(i, current);
+                queue.push_back(i);
+            }
+        }
+    }
+}
+
+/// Switch to bottom-up once the frontier's edges exceed 1/ALPHA of the
+/// unexplored ones, and back to top-down once the frontier holds fewer than
+/// 1/BETA of the nodes. The values from Beamer et al.
+const ALPHA: usize = 14;
+const BETA: usize = 24;
+
+/// Beamer's direction-optimizing BFS. Small frontiers are expanded
+/// top-down; large ones bottom-up, where every unreached node looks for a
+/// parent in the frontier and stops at the first one it finds. The levels
+/// match a plain BFS, but within a level nodes may be reached in another
+/// order and through other parents.
+pub fn bfs_direction<G: Graph>(graph: &G, search: &mut Traversal) {
+    let n = graph.node_count();
+    let mut frontier = vec![search.root];
+    let mut next = Vec::new();
+    let mut in_frontier = Bitmap::new(n);
+    // Adjacency entries of the frontier and of the unreached nodes.
+    let mut frontier_edges = graph.degree(search.root);
+    let mut unexplored_edges = graph.edge_count() - frontier_edges;
+    let mut bottom_up = false;
+    search.start();
+
+    while !frontier.is_empty() {
+        bottom_up = if bottom_up {
+            frontier.len() >= n / BETA
+        } else {
+            frontier_edges > unexplored_edges / ALPHA
+        };
+
+        let mut next_edges = 0;
+        if bottom_up {
+            in_frontier.clear();
+            for &node in &frontier {
+                in_frontier.insert(node);
+            }
+            for node in 0..n {
+                if search.is_reached(node) {
+                    continue;
+                }
+                if let Some(parent) = graph.neighbors(node).find(|&i| in_frontier.contains(i)) {
+                    search.reach(node, parent);
+                    next.push(node);
+                    next_edges += graph.degree(node);
+                }
+            }
+        } else {
+            for &current in &frontier {
+                for i in graph.neighbors(current) {
+                    if !search.is_reached(i) {
+                        search.reach(i, current);
+                        next.push(i);
+                        next_edges += graph.degree(i);
+                    }
+                }
+            }
+        }
+        unexplored_edges -= next_edges;
+        frontier_edges = next_edges;
+        std::mem::swap(&mut frontier, &mut next);
+        next.clear();
+    }
+}
+
+/// [`bfs`] without bounds checks: the visit order doubles as the queue, as
+/// in the C version, and the arrays are accessed through raw pointers.
+pub fn bfs_unchecked<G: Graph>(graph: &G, search: &mut Traversal) {
+    let n = graph.node_count();
+    let root = search.root;
+    assert!(root < n && search.parent.len() == n && search.level.len() == n);
+    assert!(search.reached() == 0 && search.order.capacity() >= n);
+
+    let order = search.order.as_mut_ptr();
+    let parent = search.parent.as_mut_ptr();
+    let level = search.level.as_mut_ptr();
+    // SAFETY: `parent` and `level` hold n entries and `order` has room for
+    // n. Every index is a node id below n, as `Graph::neighbors` promises,
+    // and a node is appended to `order` only the first time it is reached,
+    // so at most n are.
+    unsafe {
+        *parent.add(root) = root as u32;
+        *level.add(root) = 0;
+        *order = root as u32;
+        let (mut front, mut rear) = (0, 1);
+
+        while front < rear {
+            let current = *order.add(front) as usize;
+            front += 1;
+            let next_level = *level.add(current) + 1;
+
+            for i in graph.neighbors(current) {
+                if *parent.add(i) == UNREACHED {
+                    *parent.add(i) = current as u32;
+                    *level.add(i) = next_level;
+                    *order.add(rear) = i as u32;
+                    rear += 1;
+                }
+            }
+        }
+        search.order.set_len(rear);
+    }
+}
+
+/// Which BFS to run, from `--style`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Style {
+    /// [`bfs`], the textbook `VecDeque` queue.
+    #[default]
+    Queue,
+    /// [`bfs_frontier`].
+    Frontier,
+    /// [`bfs_bitmap`].
+    Bitmap,
+    /// [`bfs_direction`].
+    Direction,
+    /// [`bfs_unchecked`].
+    Unchecked,
+    /// [`bfs_parallel`], on scoped threads.
+    Parallel,
+    /// [`bfs_rayon`].
+    Rayon,
+}
+
+impl Style {
+    pub fn is_parallel(self) -> bool {
+        matches!(self, Style::Parallel | Style::Rayon)
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Style::Queue => write!(f, "queue"),
+            Style::Frontier => write!(f, "frontier"),
+            Style::Bitmap => write!(f, "bitmap"),
+            Style::Direction => write!(f, "direction"),
+            Style::Unchecked => write!(f, "unchecked"),
+            Style::Parallel => write!(f, "parallel"),
+            Style::Rayon => write!(f, "rayon"),
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queue" => Ok(Style::Queue),
+            "frontier" => Ok(Style::Frontier),
+            "bitmap" => Ok(Style::Bitmap),
+            "direction" => Ok(Style::Direction),
+            "unchecked" => Ok(Style::Unchecked),
+            "parallel" => Ok(Style::Parallel),
+            "rayon" => Ok(Style::Rayon),
+            _ => Err(format!(
+                "unknown style: {s} (expected queue, frontier, bitmap, direction, unchecked, parallel or rayon)"
+            )),
+        }
+    }
+}
+
+pub struct BfsInput {
+    pub graph: AnyGraph,
+    pub search: Traversal,
+    /// Result of the Rust search at setup, which every run must reproduce.
+    pub expected: Traversal,
+}
+
+#[derive(Default)]
+pub struct Bfs {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+    style: Style,
+    /// Threads of a parallel style; `None` runs the sequential baseline,
+    /// the queue BFS, instead.
+    threads: Option<usize>,
+    /// Rayon pools by thread count, built once for the whole sweep.
+    pools: HashMap<usize, rayon::ThreadPool>,
+    /// Checksum of the expected result, which every run has matched.
+    checksum: u64,
+}
+
+impl Bfs {
+    /// The style that actually runs: a parallel one only with threads.
+    fn running_style(&self) -> Style {
+        match self.threads {
+            None if self.style.is_parallel() => Style::Queue,
+            _ => self.style,
+        }
+    }
+}
+
+impl Benchmark for Bfs {
+    type Input = BfsInput;
+    /// Number of nodes reached.
+    type Output = usize;
+
+    fn name(&self) -> &str {
+        "bfs"
+    }
+
+    fn variant(&self) -> String {
+        format!("{}/{}", self.graph.variant(), self.running_style())
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The C version is the queue BFS over the dense matrix.
+    fn set_language(&mut self, language: Language) -> bool {
+        if language == Language::C && (self.graph.representation != Representation::Matrix || self.style != Style::Queue) {
+            return false;
+        }
+        self.language = language;
+        true
+    }
+
+    fn threads(&self) -> Option<usize> {
+        self.threads
+    }
+
+    /// Only the parallel styles take a thread count. Rust only, so the C
+    /// version never gets here.
+    fn set_threads(&mut self, threads: Option<usize>) -> bool {
+        if !self.style.is_parallel() {
+            return threads.is_none();
+        }
+        if let (Style::Rayon, Some(n)) = (self.style, threads) {
+            if let Entry::Vacant(entry) = self.pools.entry(n) {
+                match rayon::ThreadPoolBuilder::new().num_threads(n).build() {
+                    Ok(pool) => {
+                        entry.insert(pool);
+                    }
+                    Err(_) => return false,
+                }
+            }
+        }
+        self.threads = threads;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE] \
+         [--style queue|frontier|bitmap|direction|unchecked|parallel|rayon]"
+    }
+
+    /// Without `--threads`, a parallel style uses every core.
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.style = args.parse("--style")?.unwrap_or_default();
+        if self.style.is_parallel() {
+            let cores = thread::available_parallelism().map_or(1, |n| n.get());
+            if !self.set_threads(Some(cores)) {
+                return Err(format!("failed to start {} threads", cores));
+            }
+        }
+        self.graph.configure(args)
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let graph = self.graph.build(size, self.seed);
+        let mut expected = Traversal::new(size, 0);
+        with_graph!(&graph, g => bfs(g, &mut expected));
+        self.checksum = expected.checksum();
+        BfsInput {
+            graph,
+            search: Traversal::new(size, 0),
+            expected,
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> usize {
+        let search = &mut input.search;
+        match (self.language, &input.graph) {
+            (Language::C, AnyGraph::Matrix(g)) => ffi::bfs(g, search),
+            (Language::C, _) => unreachable!("the C version needs the dense matrix"),
+            (Language::Rust, graph) => with_graph!(graph, g => match (self.running_style(), self.threads) {
+                (Style::Queue, _) => bfs(g, search),
+                (Style::Frontier, _) => bfs_frontier(g, search),
+                (Style::Bitmap, _) => bfs_bitmap(g, search),
+                (Style::Direction, _) => bfs_direction(g, search),
+                (Style::Unchecked, _) => bfs_unchecked(g, search),
+                (Style::Parallel, Some(n)) => bfs_parallel(g, search, n),
+                (Style::Rayon, Some(n)) => self.pools[&n].install(|| bfs_rayon(g, search)),
+                (_, None) => unreachable!("parallel styles run with threads"),
+            }),
+        }
+        search.reached()
+    }
+
+    /// The adjacency matrix, row by row.
+    fn input_arrays(&self, input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        match &input.graph {
+            AnyGraph::Matrix(adj_matrix) => Some(vec![adj_matrix.concat()]),
+            _ => None,
+        }
+    }
+
+    /// The graph is only read, so only the result needs clearing.
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.search.clear();
+    }
+
+    /// Validates the BFS tree and levels against the graph, then compares
+    /// the result with the one from setup: all of it, or for the
+    /// direction-optimizing and parallel BFS, which may pick other parents,
+    /// the levels.
+    fn verify(&self, input: &Self::Input, _reached: &usize) -> Result<(), String> {
+        with_graph!(&input.graph, g => input.search.validate_bfs(g))?;
+        match self.running_style() {
+            Style::Direction | Style::Parallel | Style::Rayon => input.search.compare_levels(&input.expected),
+            _ => input.search.compare(&input.expected),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to search graph of size {}", size)
+    }
+
+    fn summary(&self, reached: &usize) -> Option<String> {
+        Some(format!(
+            "{} nodes reached (checksum {:016x}), {}",
+            reached,
+            self.checksum,
+            self.graph.summary()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::Generator;
+    use harness::input::Rng;
+    use rayon::{ThreadPool, ThreadPoolBuilder};
+
+    const THREADS: [usize; 3] = [1, 2, 4];
+
+    const REPRESENTATIONS: [Representation; 4] =
+        [Representation::Matrix, Representation::Csr, Representation::List, Representation::BitMatrix];
+
+    fn generate(representation: Representation, spec: &str, n: usize) -> AnyGraph {
+        let generator: Generator = spec.parse().unwrap();
+        AnyGraph::generate(representation, &generator, n, &mut Rng::new(7), None)
+    }
+
+    fn pools() -> Vec<ThreadPool> {
+        THREADS.iter().map(|&n| ThreadPoolBuilder::new().num_threads(n).build().unwrap()).collect()
+    }
+
+    /// Runs every style from `root` and checks it against [`bfs`], as
+    /// `verify` does, with 1, 2 and 4 threads for the parallel ones.
+    fn check_styles<G: Graph + Sync>(graph: &G, root: usize, pools: &[ThreadPool], what: &str) {
+        let n = graph.node_count();
+        let mut expected = Traversal::new(n, root);
+        bfs(graph, &mut expected);
+        expected.validate_bfs(graph).unwrap();
+
+        let mut search = Traversal::new(n, root);
+        for style in [Style::Frontier, Style::Bitmap, Style::Direction, Style::Unchecked] {
+            search.clear();
+            match style {
+                Style::Frontier => bfs_frontier(graph, &mut search),
+                Style::Bitmap => bfs_bitmap(graph, &mut search),
+                Style::Direction => bfs_direction(graph, &mut search),
+                Style::Unchecked => bfs_unchecked(graph, &mut search),
+                _ => unreachable!(),
+            }
+            let context = |e| format!("{style} BFS from {root} of {what}: {e}");
+            search.validate_bfs(graph).map_err(context).unwrap();
+            match style {
+                Style::Direction => search.compare_levels(&expected),
+                _ => search.compare(&expected),
+            }
+            .map_err(context)
+            .unwrap();
+        }
+        for (threads, pool) in THREADS.iter().zip(pools) {
+            for style in [Style::Parallel, Style::Rayon] {
+                search.clear();
+                match style {
+                    Style::Parallel => bfs_parallel(graph, &mut search, *threads),
+                    _ => pool.install(|| bfs_rayon(graph, &mut search)),
+                }
+                let context = |e| format!("{style} BFS from {root} of {what} on {threads} threads: {e}");
+                search.validate_bfs(graph).map_err(context).unwrap();
+                search.compare_levels(&expected).map_err(context).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn styles_agree_with_the_queue() {
+        let pools = pools();
+        for (spec, n) in [
+            ("er:p=0.5", 200),
+            ("er:p=0.02", 300),
+            ("ba:m=2", 300),
+            ("rmat:edge_factor=8", 512),
+            ("grid2d", 400),
+            ("grid3d", 343),
+            ("ws:k=4,beta=0.2", 300),
+        ] {
+            for representation in REPRESENTATIONS {
+                let graph = generate(representation, spec, n);
+                let what = format!("{spec} as {representation}");
+                for root in [0, n / 2, n - 1] {
+                    with_graph!(&graph, g => check_styles(g, root, &pools, &what));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn styles_agree_on_a_disconnected_graph() {
+        // Average degree 1.6: a large component, small ones and lone nodes.
+        let n = 1000;
+        let pools = pools();
+        for representation in [Representation::Csr, Representation::BitMatrix] {
+            let graph = generate(representation, "er:p=0.0016", n);
+            let reached = |root| {
+                let mut search = Traversal::new(n, root);
+                with_graph!(&graph, g => bfs(g, &mut search));
+                search.reached()
+            };
+            let sizes: Vec<_> = (0..n).map(reached).collect();
+            let roots = [
+                sizes.iter().position(|&r| r == 1).expect("no isolated node"),
+                sizes.iter().position(|&r| (2..n / 4).contains(&r)).expect("no small component"),
+                sizes.iter().position(|&r| (n / 4..n).contains(&r)).expect("no large component"),
+            ];
+            let what = format!("a sparse graph as {representation}");
+            for root in roots {
+                with_graph!(&graph, g => check_styles(g, root, &pools, &what));
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Parallel BFS. Both versions are level-synchronous: every node of a level
+//! is expanded before the next level starts, and a node is claimed by
+//! setting its bit in a shared visited bitmap, so exactly one thread records
+//! its parent and level. Which thread wins a node is down to timing, so the
+//! parents and the order within a level vary from run to run; the levels do
+//! not.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering::Relaxed};
+use std::sync::{Barrier, Mutex, RwLock};
+use std::thread;
+
+use graph::traversal::as_atomic;
+use graph::{Graph, Traversal};
+use rayon::prelude::*;
+
+/// Frontier nodes a thread claims at a time.
+const CHUNK: usize = 64;
+
+/// One bit per node, set concurrently.
+struct AtomicBitmap(Vec<AtomicU64>);
+
+impl AtomicBitmap {
+    fn new(n: usize) -> AtomicBitmap {
+        AtomicBitmap((0..n.div_ceil(64)).map(|_| AtomicU64::new(0)).collect())
+    }
+
+    /// Sets the bit of `i`, returning whether this call was the one to set
+    /// it. The plain load first skips the read-modify-write for nodes that
+    /// are long visited, which is most of them.
+    #[inline]
+    fn claim(&self, i: usize) -> bool {
+        let word = &self.0[i / 64];
+        let bit = 1 << (i % 64);
+        word.load(Relaxed) & bit == 0 && word.fetch_or(bit, Relaxed) & bit == 0
+    }
+}
+
+/// Records `node` as reached from `current`, at `depth`.
+#[inline]
+fn reach(parent: &[AtomicU32], level: &[AtomicU32], node: usize, current: usize, depth: u32) {
+    parent[node].store(current as u32, Relaxed);
+    level[node].store(depth, Relaxed);
+}
+
+/// Level-synchronous BFS on `threads` scoped threads. The threads claim
+/// chunks of the frontier from a shared cursor and collect what they reach
+/// locally; at the barrier closing each level, one of them joins those
+/// into the next frontier.
+pub fn bfs_parallel<G: Graph + Sync>(graph: &G, search: &mut Traversal, threads: usize) {
+    let visited = AtomicBitmap::new(graph.node_count());
+    search.start();
+    visited.claim(search.root);
+
+    let frontier = RwLock::new(vec![search.root as u32]);
+    let next = Mutex::new(Vec::new());
+    let order = Mutex::new(&mut search.order);
+    let parent = as_atomic(&mut search.parent);
+    let level = as_atomic(&mut search.level);
+    let cursor = AtomicUsize::new(0);
+    let barrier = Barrier::new(threads);
+
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                let mut reached = Vec::new();
+                loop {
+                    {
+                        let frontier = frontier.read().unwrap();
+                        if frontier.is_empty() {
+                            return;
+                        }
+                        loop {
+                            let start = cursor.fetch_add(CHUNK, Relaxed);
+                            if start >= frontier.len() {
+                                break;
+                            }
+                            for &current in &frontier[start..(start + CHUNK).min(frontier.len())] {
+                                let current = current as usize;
+                                let depth = level[current].load(Relaxed) + 1;
+                                for i in graph.neighbors(current) {
+                                    if visited.claim(i) {
+                                        reach(parent, level, i, current, depth);
+                                        reached.push(i as u32);
+                                    }
+                                }
+                            }
+                        }
+                        next.lock().unwrap().append(&mut reached);
+                    }
+                    if barrier.wait().is_leader() {
+                        let mut frontier = frontier.write().unwrap();
+                        let mut next = next.lock().unwrap();
+                        std::mem::swap(&mut *frontier, &mut *next);
+                        next.clear();
+                        order.lock().unwrap().extend_from_slice(&frontier);
+                        cursor.store(0, Relaxed);
+                    }
+                    barrier.wait();
+                }
+            });
+        }
+    });
+}
+
+/// Level-synchronous BFS on the current rayon pool, which splits each
+/// frontier between its threads and balances them by work stealing.
+pub fn bfs_rayon<G: Graph + Sync>(graph: &G, search: &mut Traversal) {
+    let visited = AtomicBitmap::new(graph.node_count());
+    search.start();
+    visited.claim(search.root);
+
+    let Traversal { root, order, parent, level } = search;
+    let parent = as_atomic(parent);
+    let level = as_atomic(level);
+    let mut frontier = vec![*root as u32];
+
+    while !frontier.is_empty() {
+        frontier = frontier
+            .par_iter()
+            .flat_map_iter(|&current| {
+                let current = current as usize;
+                let depth = level[current].load(Relaxed) + 1;
+                let visited = &visited;
+                graph.neighbors(current).filter(move |&i| visited.claim(i)).map(move |i| {
+                    reach(parent, level, i, current, depth);
+                    i as u32
+                })
+            })
+            .collect();
+        order.extend_from_slice(&frontier);
+    }
+}
@@ -1,22 +1,9 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <time.h>
-
-int binarySearch(int arr[], int low, int high, int x) {
-    while (low <= high) {
-        int mid = low + (high - low) / 2;
-        if (arr[mid] == x)
-            return mid;
-
-        if (arr[mid] < x)
-            low = mid + 1;
-
-        else
-            high = mid - 1;
-    }
-
-    return -1;
-}
+#include "binary_search_algo.h"
+#include "bench_input.h"
+#include "bench_rng.h"
 
 double diff_timespec(struct timespec *time1, struct timespec *time0) {
     return (time1->tv_sec - time0->tv_sec)
@@ -27,8 +14,12 @@ double diff_timespec(struct timespec *time1, struct timespec *time0) {
 
 
 int main(int argc, char *argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <array_size>\n", argv[0]);
+    uint64_t seed = BENCH_DEFAULT_SEED;
+    if (bench_take_seed(&argc, argv, &seed) != 0) {
+        return 1;
+    }
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <array_size> [input_file] [--seed N]\n", argv[0]);
         return 1;
     }
 
@@ -41,11 +32,21 @@ int main(int argc, char *argv[]) {
     struct timespec start_time, end_time;
     int *arr = (int *)malloc(size * sizeof(int));
 
-    srand(time(NULL));
+    BenchRng rng = {seed};
+
+    // Targets written by the Rust benchmark with --dump-input, if given
+    int *targets = NULL;
+    if (argc == 3) {
+        targets = bench_input_load(argv[2], size, 0, size);
+        if (!targets) {
+            return 1;
+        }
+    }
 
-    // Fill the array with values from 1 to the size +1
+    // Fill the array with the even values 2 to 2 * size, like the Rust
+    // benchmark, so odd targets are absent
     for (int i = 0; i < size; i++) {
-        arr[i] = i + 1; 
+        arr[i] = 2 * (i + 1);
     }
 
 
@@ -55,7 +56,7 @@ int main(int argc, char *argv[]) {
     // Search every possible element in the array, get total time
 
     for (int i = 0; i < size; i++){
-        int target = arr[rand() % size];
+        int target = targets ? targets[i] : arr[bench_rng_below(&rng, size)];
 
         clock_gettime(CLOCK_MONOTONIC, &start_time);
         int dummy = binarySearch(arr, 0, size - 1, target);
@@ -66,7 +67,7 @@ int main(int argc, char *argv[]) {
     printf("Total dummy = %ld\n", total_dummy);
     
     /*
-            int target = arr[rand() % size];
+            int target = arr[bench_rng_below(&rng, size)];
         start_time = clock();
         int dummy = binarySearch(arr, 0, size - 1, target);
         end_time = clock();
@@ -81,5 +82,6 @@ int main(int argc, char *argv[]) {
     printf("Time taken to search array of size %d: %f seconds\n", size, time_elapsed);
 
     free(arr);
+    free(targets);
     return 0;
 }
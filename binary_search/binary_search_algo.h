@@ -0,0 +1,7 @@
+#ifndef BINARY_SEARCH_ALGO_H
+#define BINARY_SEARCH_ALGO_H
+
+// Index of x in the sorted range arr[low..high], or -1 if it is absent
+int binarySearch(int arr[], int low, int high, int x);
+
+#endif
@@ -1,30 +1,13 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <time.h>
-#include <unistd.h>
-#include <sys/types.h>
-#include <sys/wait.h>
-#include <signal.h>
-
-int binarySearch(int arr[], int low, int high, int x) {
-    while (low <= high) {
-        int mid = low + (high - low) / 2;
-
-        if (arr[mid] == x)
-            return mid;
-
-        if (arr[mid] < x)
-            low = mid + 1;
-        else
-            high = mid - 1;
-    }
-
-    return -1;
-}
+#include "binary_search_algo.h"
+#include "bench_input.h"
+#include "bench_counters.h"
 
 int main(int argc, char *argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <array_size>\n", argv[0]);
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <array_size> [input_file]\n", argv[0]);
         return 1;
     }
 
@@ -40,60 +23,44 @@ int main(int argc, char *argv[]) {
         return 1;
     }
 
-    // Fill the array with values from 1 to size
+    // Fill the array with the even values 2 to 2 * size, like the Rust
+    // benchmark, so odd targets are absent
     for (int i = 0; i < size; i++) {
-        arr[i] = i + 1;
+        arr[i] = 2 * (i + 1);
     }
 
-    int pid = getpid();
-    int cpid = fork();
-
-    if (cpid == 0) { 
-        // Child Process: Run `perf stat` and redirect output to log
-        char buf[300];
-        snprintf(buf, sizeof(buf), "perf stat -e cycles,instructions,cache-references,cache-misses -p %d > binary_search_c_perf_output.log 2>&1", pid);
-        execl("/bin/sh", "sh", "-c", buf, NULL);
-        perror("execl failed");
-        exit(1);
-    } else { 
-        // Parent Process: Set process group and run Binary Search
-        setpgid(cpid, 0);
-        sleep(1);  
-
-        clock_t start_time, end_time;
-        double time_elapsed = 0;
-
-        // Search every possible element in the array, get total time
-        for (int i = 0; i < size; i++) {
-            int target = arr[i];
-            start_time = clock();
-            binarySearch(arr, 0, size - 1, target);
-            end_time = clock();
-            time_elapsed += ((double)(end_time - start_time)) / CLOCKS_PER_SEC;
+    // Targets written by the Rust benchmark with --dump-input, if given
+    int *targets = NULL;
+    if (argc == 3) {
+        targets = bench_input_load(argv[2], size, 0, size);
+        if (!targets) {
+            return 1;
         }
+    }
 
-        kill(-cpid, SIGINT);
-        sleep(1); 
+    // Counters opened in-process and enabled only around the searches
+    BenchCounters counters;
+    bench_counters_open(&counters);
 
-        waitpid(cpid, NULL, 0);
+    // Search every target once, timing the whole loop like the Rust benchmark
+    long total = 0;
+    bench_counters_start(&counters);
+    double start_time = bench_now();
+    for (int i = 0; i < size; i++) {
+        int target = targets ? targets[i] : arr[i];
+        total += binarySearch(arr, 0, size - 1, target);
+    }
+    double end_time = bench_now();
+    bench_counters_stop(&counters);
 
-        // Read and print perf output
-        FILE *perf_file = fopen("binary_search_c_perf_output.log", "r");
-        if (perf_file) {
-            char line[256];
-            printf("\n[ Perf Stat Output ]\n");
-            while (fgets(line, sizeof(line), perf_file)) {
-                printf("%s", line);
-            }
-            fclose(perf_file);
-        } else {
-            perror("Failed to read perf stat log");
-        }
+    bench_counters_print(&counters, argv[0]);
+    bench_counters_close(&counters);
 
-        printf("\nTime taken to search array of size %d: %f seconds\n", size, time_elapsed);
+    double time_elapsed = end_time - start_time;
+    printf("\nTime taken to search array of size %d: %f seconds (index sum %ld)\n", size, time_elapsed, total);
 
-        free(arr);
-    }
+    free(arr);
+    free(targets);
 
     return 0;
 }
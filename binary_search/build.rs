@@ -0,0 +1,13 @@
+// Compiles the C implementations, binary_search_algo.c shared with the
+// Makefile, so the benchmarks can call them through FFI.
+fn main() {
+    println!("cargo:rerun-if-changed=binary_search_algo.c");
+    println!("cargo:rerun-if-changed=binary_search_algo.h");
+    println!("cargo:rerun-if-changed=search_variants_algo.c");
+    println!("cargo:rerun-if-changed=search_variants_algo.h");
+    cc::Build::new()
+        .file("binary_search_algo.c")
+        .file("search_variants_algo.c")
+        .opt_level(3)
+        .compile("binary_search_algo");
+}
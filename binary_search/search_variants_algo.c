@@ -0,0 +1,117 @@
+#include <stdint.h>
+#include <stdlib.h>
+#include "search_variants_algo.h"
+
+// Lookahead of the Eytzinger prefetch: the 16 nodes 4 levels down share a
+// cache line.
+#define PREFETCH_STRIDE 16
+
+int branchless_search(const int* arr, int n, int x) {
+    if (n == 0)
+        return -1;
+    const int* base = arr;
+    while (n > 1) {
+        int half = n / 2;
+        base = base[half] <= x ? base + half : base;
+        n -= half;
+    }
+    return *base == x ? (int)(base - arr) : -1;
+}
+
+static int lowerBound(const int* arr, int n, int x) {
+    int lo = 0, hi = n;
+    while (lo < hi) {
+        int mid = lo + (hi - lo) / 2;
+        if (arr[mid] < x)
+            lo = mid + 1;
+        else
+            hi = mid;
+    }
+    return lo;
+}
+
+static int upperBound(const int* arr, int n, int x) {
+    int lo = 0, hi = n;
+    while (lo < hi) {
+        int mid = lo + (hi - lo) / 2;
+        if (arr[mid] <= x)
+            lo = mid + 1;
+        else
+            hi = mid;
+    }
+    return lo;
+}
+
+int lower_bound_search(const int* arr, int n, int x) {
+    int i = lowerBound(arr, n, x);
+    return i < n && arr[i] == x ? i : -1;
+}
+
+int upper_bound_search(const int* arr, int n, int x) {
+    int i = upperBound(arr, n, x);
+    return i > 0 && arr[i - 1] == x ? i - 1 : -1;
+}
+
+int interpolation_search(const int* arr, int n, int x) {
+    if (n == 0)
+        return -1;
+    int lo = 0, hi = n - 1;
+    while (lo <= hi && x >= arr[lo] && x <= arr[hi]) {
+        if (arr[hi] == arr[lo])
+            break;
+        int pos = lo + (int)(((int64_t)x - arr[lo]) * (hi - lo) / ((int64_t)arr[hi] - arr[lo]));
+        if (arr[pos] == x)
+            return pos;
+        if (arr[pos] < x)
+            lo = pos + 1;
+        else
+            hi = pos - 1;
+    }
+    return lo < n && arr[lo] == x ? lo : -1;
+}
+
+int exponential_search(const int* arr, int n, int x) {
+    int bound = 1;
+    while (bound < n && arr[bound] < x)
+        bound *= 2;
+    int start = bound / 2;
+    int end = bound + 1 < n ? bound + 1 : n;
+    int i = start + lowerBound(arr + start, end - start, x);
+    return i < n && arr[i] == x ? i : -1;
+}
+
+static int compareInts(const void* a, const void* b) {
+    int x = *(const int*)a, y = *(const int*)b;
+    return (x > y) - (x < y);
+}
+
+int std_search(const int* arr, int n, int x) {
+    const int* found = bsearch(&x, arr, n, sizeof(int), compareInts);
+    return found ? (int)(found - arr) : -1;
+}
+
+int eytzinger_search(const int* keys, const int* index, int n, int x) {
+    unsigned k = 1;
+    while (k <= (unsigned)n) {
+        __builtin_prefetch(keys + PREFETCH_STRIDE * k);
+        k = 2 * k + (keys[k] < x);
+    }
+    // Undo the right turns after the last left one
+    k >>= __builtin_ffs(~k);
+    return k != 0 && keys[k] == x ? index[k] : -1;
+}
+
+int s_tree_search(const int* keys, const int* index, int nodes, int x) {
+    int node = 0;
+    int candidate = -1;
+    while (node < nodes) {
+        const int* keysOfNode = keys + node * S_TREE_KEYS;
+        int i = 0;
+        for (int j = 0; j < S_TREE_KEYS; j++)
+            i += keysOfNode[j] < x;
+        if (i < S_TREE_KEYS)
+            candidate = node * S_TREE_KEYS + i;
+        node = node * (S_TREE_KEYS + 1) + i + 1;
+    }
+    return candidate != -1 && keys[candidate] == x ? index[candidate] : -1;
+}
@@ -0,0 +1,22 @@
+#ifndef SEARCH_VARIANTS_ALGO_H
+#define SEARCH_VARIANTS_ALGO_H
+
+// The search variants of src/variants.rs. Like binarySearch, each returns
+// the index of x in the n ascending keys of arr, or -1 if it is absent.
+
+int branchless_search(const int* arr, int n, int x);
+int lower_bound_search(const int* arr, int n, int x);
+int upper_bound_search(const int* arr, int n, int x);
+int interpolation_search(const int* arr, int n, int x);
+int exponential_search(const int* arr, int n, int x);
+// Through the C library's bsearch
+int std_search(const int* arr, int n, int x);
+
+// The cache-friendly layouts are built on the Rust side: keys[1..=n] in
+// Eytzinger order, and nodes of S_TREE_KEYS keys padded with INT_MAX. The
+// index arrays map every slot back to its position in the sorted array.
+#define S_TREE_KEYS 16
+int eytzinger_search(const int* keys, const int* index, int n, int x);
+int s_tree_search(const int* keys, const int* index, int nodes, int x);
+
+#endif
@@ -0,0 +1,52 @@
+//! Runs the binary search benchmark once per level of the memory
+//! hierarchy, with an array sized to live in it. Every option but
+//! `--levels` is passed on to each run.
+
+use std::process::exit;
+
+use binary_search::{cache, BinarySearch, Queries};
+use harness::args::Args;
+use harness::Runner;
+
+fn main() {
+    let mut args = Args::from_env();
+    let levels = match args.value("--levels") {
+        Ok(levels) => levels,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+    };
+    let sweep = cache::sweep();
+    let selected: Vec<&cache::Level> = match &levels {
+        Some(names) => {
+            let mut selected = Vec::new();
+            for name in names.split(',') {
+                match sweep.iter().find(|level| level.name == name) {
+                    Some(level) => selected.push(level),
+                    None => {
+                        let known: Vec<&str> = sweep.iter().map(|level| level.name.as_str()).collect();
+                        eprintln!("Error: unknown level: {} (expected {})", name, known.join(", "));
+                        exit(1);
+                    }
+                }
+            }
+            selected
+        }
+        None => sweep.iter().collect(),
+    };
+
+    for level in selected {
+        // On stderr, so that stdout stays machine-readable with --format.
+        eprintln!(
+            "\n=== {}: {} keys, {} KiB ===",
+            level.name,
+            level.size,
+            level.size * std::mem::size_of::<i32>() / 1024
+        );
+        let mut rest = vec![level.size.to_string()];
+        rest.extend_from_slice(args.remaining());
+        let args = Args::new(args.program().to_string(), rest);
+        Runner::new().run_with_args(BinarySearch::new(Queries::Random), args);
+    }
+}
@@ -0,0 +1,78 @@
+//! Cache sizes of the host, for sweeping the array across the L1, L2, L3
+//! and DRAM boundaries.
+
+use std::fs;
+
+/// Sizes assumed where sysfs does not say: L1d, L2 and L3.
+const FALLBACK: [(u32, usize); 3] = [(1, 32 << 10), (2, 1 << 20), (3, 32 << 20)];
+
+/// Main memory gets an array this many times the size of the last cache.
+const DRAM_FACTOR: usize = 4;
+
+/// One step of the sweep: the level the array is meant to live in, e.g.
+/// `l2` or `dram`, and its number of keys.
+pub struct Level {
+    pub name: String,
+    pub size: usize,
+}
+
+/// Data and unified caches of CPU 0 as (level, bytes), from sysfs, or
+/// [`FALLBACK`] if they cannot be read.
+pub fn data_caches() -> Vec<(u32, usize)> {
+    let mut caches = Vec::new();
+    for index in 0.. {
+        let dir = format!("/sys/devices/system/cpu/cpu0/cache/index{}", index);
+        let read = |file: &str| fs::read_to_string(format!("{}/{}", dir, file)).ok();
+        let (Some(level), Some(kind), Some(size)) = (read("level"), read("type"), read("size")) else {
+            break;
+        };
+        if kind.trim() == "Instruction" {
+            continue;
+        }
+        if let (Ok(level), Some(bytes)) = (level.trim().parse(), parse_size(size.trim())) {
+            caches.push((level, bytes));
+        }
+    }
+    if caches.is_empty() {
+        caches = FALLBACK.to_vec();
+    }
+    caches.sort_unstable();
+    caches
+}
+
+/// Parses sysfs sizes such as `48K` or `32M`.
+fn parse_size(s: &str) -> Option<usize> {
+    let (digits, unit) = match s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => s.split_at(i),
+        None => (s, ""),
+    };
+    let shift = match unit {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        _ => return None,
+    };
+    digits.parse::<usize>().ok().map(|n| n << shift)
+}
+
+/// The sweep: for every cache level an array of `i32` keys filling half
+/// of it, so the level holds the array along with whatever else runs,
+/// then one [`DRAM_FACTOR`] times the size of the last level.
+pub fn sweep() -> Vec<Level> {
+    let keys = |bytes: usize| bytes / std::mem::size_of::<i32>();
+    let caches = data_caches();
+    let mut levels: Vec<Level> = caches
+        .iter()
+        .map(|&(level, bytes)| Level {
+            name: format!("l{}", level),
+            size: keys(bytes / 2),
+        })
+        .collect();
+    let last = caches.last().map_or(0, |&(_, bytes)| bytes);
+    levels.push(Level {
+        name: "dram".to_string(),
+        size: keys(DRAM_FACTOR * last),
+    });
+    levels
+}
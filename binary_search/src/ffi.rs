@@ -0,0 +1,72 @@
+//! Bindings to the C implementations in `binary_search_algo.c` and
+//! `search_variants_algo.c`.
+
+use std::os::raw::c_int;
+
+use crate::variants::{Eytzinger, STree, NODE_KEYS};
+
+extern "C" {
+    #[link_name = "binarySearch"]
+    fn c_binary_search(arr: *const c_int, low: c_int, high: c_int, x: c_int) -> c_int;
+}
+
+/// Looks `k` up with the C `binarySearch`; same contract as the Rust
+/// `binary_search`. Each call crosses the FFI boundary, so it cannot be
+/// inlined into the caller's loop.
+pub fn binary_search(k: i32, items: &[i32]) -> i32 {
+    let high = c_int::try_from(items.len()).expect("array too large for the C implementation") - 1;
+    // SAFETY: `items` holds high + 1 ints and is only read.
+    unsafe { c_binary_search(items.as_ptr(), 0, high, k) }
+}
+
+/// One of the searches over a sorted array in `search_variants_algo.c`.
+pub type CSearch = unsafe extern "C" fn(arr: *const c_int, n: c_int, x: c_int) -> c_int;
+
+extern "C" {
+    #[link_name = "branchless_search"]
+    fn c_branchless_search(arr: *const c_int, n: c_int, x: c_int) -> c_int;
+    #[link_name = "lower_bound_search"]
+    fn c_lower_bound_search(arr: *const c_int, n: c_int, x: c_int) -> c_int;
+    #[link_name = "upper_bound_search"]
+    fn c_upper_bound_search(arr: *const c_int, n: c_int, x: c_int) -> c_int;
+    #[link_name = "interpolation_search"]
+    fn c_interpolation_search(arr: *const c_int, n: c_int, x: c_int) -> c_int;
+    #[link_name = "exponential_search"]
+    fn c_exponential_search(arr: *const c_int, n: c_int, x: c_int) -> c_int;
+    #[link_name = "std_search"]
+    fn c_std_search(arr: *const c_int, n: c_int, x: c_int) -> c_int;
+    #[link_name = "eytzinger_search"]
+    fn c_eytzinger_search(keys: *const c_int, index: *const c_int, n: c_int, x: c_int) -> c_int;
+    #[link_name = "s_tree_search"]
+    fn c_s_tree_search(keys: *const c_int, index: *const c_int, nodes: c_int, x: c_int) -> c_int;
+}
+
+pub const BRANCHLESS_SEARCH: CSearch = c_branchless_search;
+pub const LOWER_BOUND_SEARCH: CSearch = c_lower_bound_search;
+pub const UPPER_BOUND_SEARCH: CSearch = c_upper_bound_search;
+pub const INTERPOLATION_SEARCH: CSearch = c_interpolation_search;
+pub const EXPONENTIAL_SEARCH: CSearch = c_exponential_search;
+/// libc `bsearch`, with a comparison callback per step.
+pub const STD_SEARCH: CSearch = c_std_search;
+
+/// Looks `k` up in `items` with `search`.
+pub fn search(search: CSearch, k: i32, items: &[i32]) -> i32 {
+    let n = c_int::try_from(items.len()).expect("array too large for the C implementation");
+    // SAFETY: `items` holds n ints and is only read.
+    unsafe { search(items.as_ptr(), n, k) }
+}
+
+/// Looks `k` up in `layout` with the C `eytzinger_search`.
+pub fn eytzinger_search(layout: &Eytzinger, k: i32) -> i32 {
+    let n = c_int::try_from(layout.keys.len() - 1).expect("array too large for the C implementation");
+    // SAFETY: both arrays hold n + 1 ints and are only read.
+    unsafe { c_eytzinger_search(layout.keys.as_ptr(), layout.index.as_ptr(), n, k) }
+}
+
+/// Looks `k` up in `tree` with the C `s_tree_search`.
+pub fn s_tree_search(tree: &STree, k: i32) -> i32 {
+    let nodes = c_int::try_from(tree.keys.len() / NODE_KEYS).expect("array too large for the C implementation");
+    // SAFETY: both arrays hold `nodes` nodes of NODE_KEYS ints and are only
+    // read.
+    unsafe { c_s_tree_search(tree.keys.as_ptr(), tree.index.as_ptr(), nodes, k) }
+}
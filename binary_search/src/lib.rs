@@ -0,0 +1,246 @@
+use std::process::exit;
+
+use harness::args::Args;
+use harness::input::DEFAULT_SEED;
+use harness::{Benchmark, Language};
+
+use variants::{Algorithm, Eytzinger, STree};
+
+pub mod cache;
+pub mod ffi;
+pub mod queries;
+pub mod variants;
+
+pub use queries::Queries;
+
+/// Largest array whose keys `2, 4, ..., 2 * size`, and the absent
+/// `2 * size + 1` above them, fit in an `i32`.
+const MAX_SIZE: usize = (i32::MAX / 2) as usize;
+
+/// Keys every search is checked on against [`binary_search`] after a run,
+/// besides the absent ones.
+const CHECKED_TARGETS: usize = 1000;
+
+pub fn binary_search(k: i32, items: &[i32]) -> i32 {
+    if items.is_empty() {
+        return -1;
+    }
+
+    let mut lo: isize = 0;
+    let mut hi: isize = items.len() as isize - 1;
+
+    while lo <= hi {
+        let mid = lo + (hi - lo) / 2;
+        let mid_val = items[mid as usize];
+
+        if mid_val == k {
+            return mid as i32;
+        }
+        if mid_val > k {
+            hi = mid - 1;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    -1
+}
+
+pub struct BinarySearchInput {
+    pub arr: Vec<i32>,
+    pub targets: Vec<i32>,
+    /// Built in `setup` when `--algo eytzinger` is run.
+    pub eytzinger: Option<Eytzinger>,
+    /// Built in `setup` when `--algo s-tree` is run.
+    pub s_tree: Option<STree>,
+}
+
+/// Sum of the indices `search` returns for every target. Generic so each
+/// search function is inlined into its own loop.
+fn search_all(targets: &[i32], search: impl Fn(i32) -> i32) -> i64 {
+    let mut total = 0;
+    for &target in targets {
+        total += search(target) as i64;
+    }
+    total
+}
+
+pub struct BinarySearch {
+    pub queries: Queries,
+    pub algorithm: Algorithm,
+    language: Language,
+    seed: u64,
+}
+
+impl BinarySearch {
+    pub fn new(queries: Queries) -> Self {
+        BinarySearch {
+            queries,
+            algorithm: Algorithm::default(),
+            language: Language::Rust,
+            seed: DEFAULT_SEED,
+        }
+    }
+
+    /// Looks `k` up with the selected algorithm and language, one call at
+    /// a time, for the checks in `verify`.
+    fn search(&self, input: &BinarySearchInput, k: i32) -> i32 {
+        let arr = &input.arr;
+        match (self.algorithm, self.language) {
+            (Algorithm::Eytzinger, Language::Rust) => input.eytzinger.as_ref().unwrap().search(k),
+            (Algorithm::Eytzinger, Language::C) => ffi::eytzinger_search(input.eytzinger.as_ref().unwrap(), k),
+            (Algorithm::STree, Language::Rust) => input.s_tree.as_ref().unwrap().search(k),
+            (Algorithm::STree, Language::C) => ffi::s_tree_search(input.s_tree.as_ref().unwrap(), k),
+            (algorithm, Language::Rust) => algorithm.on_sorted().unwrap()(k, arr),
+            (algorithm, Language::C) => match c_search(algorithm) {
+                Some(search) => ffi::search(search, k, arr),
+                None => ffi::binary_search(k, arr),
+            },
+        }
+    }
+}
+
+/// The C twin of a search over the sorted array, or `None` for the
+/// classic `binarySearch`, which has its own signature.
+fn c_search(algorithm: Algorithm) -> Option<ffi::CSearch> {
+    match algorithm {
+        Algorithm::Branchless => Some(ffi::BRANCHLESS_SEARCH),
+        Algorithm::LowerBound => Some(ffi::LOWER_BOUND_SEARCH),
+        Algorithm::UpperBound => Some(ffi::UPPER_BOUND_SEARCH),
+        Algorithm::Interpolation => Some(ffi::INTERPOLATION_SEARCH),
+        Algorithm::Exponential => Some(ffi::EXPONENTIAL_SEARCH),
+        Algorithm::Std => Some(ffi::STD_SEARCH),
+        Algorithm::Classic | Algorithm::Eytzinger | Algorithm::STree => None,
+    }
+}
+
+impl Benchmark for BinarySearch {
+    type Input = BinarySearchInput;
+    /// Sum of the returned indices.
+    type Output = i64;
+
+    fn name(&self) -> &str {
+        "binary_search"
+    }
+
+    fn variant(&self) -> String {
+        format!("{}/{}", self.queries, self.algorithm)
+    }
+
+    fn size_name(&self) -> &str {
+        "array_size"
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.queries.is_random().then_some(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--queries NAME[:param=value,...]] \
+         [--algo classic|branchless|lower-bound|upper-bound|eytzinger|s-tree|interpolation|exponential|std]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.queries = args.parse("--queries")?.unwrap_or(self.queries);
+        self.algorithm = args.parse("--algo")?.unwrap_or(self.algorithm);
+        Ok(())
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        if size > MAX_SIZE {
+            eprintln!("Error: array size must be at most {}", MAX_SIZE);
+            exit(1);
+        }
+        let arr: Vec<i32> = (0..size).map(queries::key).collect();
+        let targets = self.queries.generate(size, size, self.seed);
+        let eytzinger = (self.algorithm == Algorithm::Eytzinger).then(|| Eytzinger::new(&arr));
+        let s_tree = (self.algorithm == Algorithm::STree).then(|| STree::new(&arr));
+        BinarySearchInput {
+            arr,
+            targets,
+            eytzinger,
+            s_tree,
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> i64 {
+        let (arr, targets) = (&input.arr[..], &input.targets[..]);
+        match self.language {
+            Language::Rust => match self.algorithm {
+                Algorithm::Classic => search_all(targets, |k| binary_search(k, arr)),
+                Algorithm::Branchless => search_all(targets, |k| variants::branchless_search(k, arr)),
+                Algorithm::LowerBound => search_all(targets, |k| variants::lower_bound_search(k, arr)),
+                Algorithm::UpperBound => search_all(targets, |k| variants::upper_bound_search(k, arr)),
+                Algorithm::Interpolation => search_all(targets, |k| variants::interpolation_search(k, arr)),
+                Algorithm::Exponential => search_all(targets, |k| variants::exponential_search(k, arr)),
+                Algorithm::Std => search_all(targets, |k| variants::std_search(k, arr)),
+                Algorithm::Eytzinger => {
+                    let layout = input.eytzinger.as_ref().unwrap();
+                    search_all(targets, |k| layout.search(k))
+                }
+                Algorithm::STree => {
+                    let tree = input.s_tree.as_ref().unwrap();
+                    search_all(targets, |k| tree.search(k))
+                }
+            },
+            Language::C => match self.algorithm {
+                Algorithm::Eytzinger => {
+                    let layout = input.eytzinger.as_ref().unwrap();
+                    search_all(targets, |k| ffi::eytzinger_search(layout, k))
+                }
+                Algorithm::STree => {
+                    let tree = input.s_tree.as_ref().unwrap();
+                    search_all(targets, |k| ffi::s_tree_search(tree, k))
+                }
+                algorithm => match c_search(algorithm) {
+                    Some(search) => search_all(targets, |k| ffi::search(search, k, arr)),
+                    None => search_all(targets, |k| ffi::binary_search(k, arr)),
+                },
+            },
+        }
+    }
+
+    /// The targets; the array itself is always `2, 4, ..., 2 * size`.
+    fn input_arrays(&self, input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        Some(vec![input.targets.clone()])
+    }
+
+    fn reset(&mut self, _input: &mut Self::Input, _size: usize) {}
+
+    /// Checks the index sum against the classic search, then the answers
+    /// for single keys, among them absent ones.
+    fn verify(&self, input: &Self::Input, total: &i64) -> Result<(), String> {
+        let expected = search_all(&input.targets, |k| binary_search(k, &input.arr));
+        if *total != expected {
+            return Err(format!("index sum {} does not match expected {}", total, expected));
+        }
+        let size = input.arr.len() as i32;
+        let absent = [0, 1, 3, 2 * size + 1, i32::MIN, i32::MAX];
+        for &k in input.targets.iter().take(CHECKED_TARGETS).chain(&absent) {
+            let (found, expected) = (self.search(input, k), binary_search(k, &input.arr));
+            if found != expected {
+                return Err(format!(
+                    "{} search for {} returned {}, expected {}",
+                    self.algorithm, k, found, expected
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to search array of size {}", size)
+    }
+}
@@ -0,0 +1,202 @@
+//! Query streams for the binary search benchmarks.
+//!
+//! A stream is written as `NAME[:param=value,...]` like the sorting
+//! distributions, e.g. `random`, `hits:ratio=0.9` or `zipf:s=1.2`, and its
+//! spec is recorded with the results. The array searched holds the even
+//! keys `2, 4, ..., 2 * size`, so the odd ones are absent and fall between
+//! its keys or just past either end.
+
+use std::fmt;
+use std::str::FromStr;
+
+use harness::args::Params;
+use harness::distribution::Distribution;
+use harness::input::Rng;
+
+/// Zipf streams draw their hot keys from this many of the most popular ones
+/// at most, as the rank table would otherwise grow with the array.
+const ZIPF_MAX_KEYS: usize = 1 << 24;
+
+/// Which keys are looked up, one search per array element.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Queries {
+    /// Uniformly random keys of the array, drawn with replacement.
+    #[default]
+    Random,
+    /// Every key in ascending order, which the caches and the branch
+    /// predictor handle best.
+    InOrder,
+    /// Uniformly random keys that are not in the array.
+    Absent,
+    /// Random keys, of which a fraction `ratio` is in the array and the
+    /// rest is absent.
+    Hits { ratio: f64 },
+    /// Keys of the array where the key of popularity rank `r` is drawn
+    /// with probability proportional to `1 / (r + 1)^s`. The ranks are
+    /// spread over the array, so the hot keys are not neighbours.
+    Zipf { s: f64 },
+    /// Random keys of the array, sorted within consecutive batches of
+    /// `batch`, as when lookups are buffered and sorted first.
+    SortedBatches { batch: usize },
+}
+
+/// Key at `index` of the searched array.
+#[inline]
+pub fn key(index: usize) -> i32 {
+    2 * (index as i32 + 1)
+}
+
+impl Queries {
+    /// `count` keys to look up in the array of `size` keys, random ones
+    /// drawn from a generator seeded with `seed`.
+    pub fn generate(&self, size: usize, count: usize, seed: u64) -> Vec<i32> {
+        let mut rng = Rng::new(seed);
+        let present = |rng: &mut Rng| key(rng.below(size as u64) as usize);
+        // 1, 3, ..., 2 * size + 1: below, between and above the keys.
+        let absent = |rng: &mut Rng| 2 * rng.below(size as u64 + 1) as i32 + 1;
+        match *self {
+            Queries::Random => (0..count).map(|_| present(&mut rng)).collect(),
+            Queries::InOrder => (0..count).map(|i| key(i % size)).collect(),
+            Queries::Absent => (0..count).map(|_| absent(&mut rng)).collect(),
+            Queries::Hits { ratio } => (0..count)
+                .map(|_| {
+                    if rng.next_f64() < ratio {
+                        present(&mut rng)
+                    } else {
+                        absent(&mut rng)
+                    }
+                })
+                .collect(),
+            Queries::Zipf { s } => {
+                // Rank r goes to index (a * r + b) mod size, a bijection
+                // since a and size are coprime.
+                let mut a = rng.below(size as u64) | 1;
+                while gcd(a, size as u64) != 1 {
+                    a += 2;
+                }
+                let b = rng.below(size as u64);
+                let ranks = Distribution::Zipf {
+                    s,
+                    k: size.min(ZIPF_MAX_KEYS),
+                };
+                ranks
+                    .generate(count, rng.next_u64())
+                    .into_iter()
+                    .map(|r| key(((a as u128 * r as u128 + b as u128) % size as u128) as usize))
+                    .collect()
+            }
+            Queries::SortedBatches { batch } => {
+                let mut targets: Vec<i32> = (0..count).map(|_| present(&mut rng)).collect();
+                for chunk in targets.chunks_mut(batch) {
+                    chunk.sort_unstable();
+                }
+                targets
+            }
+        }
+    }
+
+    /// Whether the stream depends on the seed.
+    pub fn is_random(&self) -> bool {
+        *self != Queries::InOrder
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl fmt::Display for Queries {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Queries::Random => write!(f, "random"),
+            Queries::InOrder => write!(f, "in-order"),
+            Queries::Absent => write!(f, "absent"),
+            Queries::Hits { ratio } => write!(f, "hits:ratio={}", ratio),
+            Queries::Zipf { s } => write!(f, "zipf:s={}", s),
+            Queries::SortedBatches { batch } => write!(f, "sorted-batches:batch={}", batch),
+        }
+    }
+}
+
+impl FromStr for Queries {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let mut params = Params::parse(params)?;
+        let queries = match name {
+            "random" => Queries::Random,
+            "in-order" => Queries::InOrder,
+            "absent" => Queries::Absent,
+            "hits" => {
+                let ratio = params.get("ratio")?.unwrap_or(0.5);
+                if !(0.0..=1.0).contains(&ratio) {
+                    return Err(format!("ratio must be between 0 and 1, got {}", ratio));
+                }
+                Queries::Hits { ratio }
+            }
+            "zipf" => {
+                let s: f64 = params.get("s")?.unwrap_or(1.0);
+                if s.is_nan() || s < 0.0 {
+                    return Err(format!("s must not be negative, got {}", s));
+                }
+                Queries::Zipf { s }
+            }
+            "sorted-batches" => match params.get("batch")?.unwrap_or(1024) {
+                0 => return Err("batch must be at least 1".to_string()),
+                batch => Queries::SortedBatches { batch },
+            },
+            _ => {
+                return Err(format!(
+                    "unknown query stream: {} (expected random, in-order, absent, hits, zipf or sorted-batches)",
+                    name
+                ))
+            }
+        };
+        params.finish(name)?;
+        Ok(queries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_round_trip() {
+        for queries in [
+            Queries::Random,
+            Queries::InOrder,
+            Queries::Absent,
+            Queries::Hits { ratio: 0.9 },
+            Queries::Zipf { s: 1.2 },
+            Queries::SortedBatches { batch: 64 },
+        ] {
+            let spec = queries.to_string();
+            assert_eq!(spec.parse(), Ok(queries), "{spec}");
+        }
+    }
+
+    #[test]
+    fn specs_reject_bad_parameters() {
+        for spec in ["hits:ratio=1.5", "zipf:s=-1", "sorted-batches:batch=0", "random:n=1", "missing"] {
+            assert!(spec.parse::<Queries>().is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn streams_hit_the_keys_they_promise() {
+        let size = 1000;
+        let is_key = |k: i32| k % 2 == 0 && (2..=2 * size as i32).contains(&k);
+        assert_eq!(Queries::InOrder.generate(size, size, 1), (0..size).map(key).collect::<Vec<_>>());
+        assert!(Queries::Random.generate(size, size, 1).into_iter().all(is_key));
+        assert!(Queries::Zipf { s: 1.0 }.generate(size, size, 1).into_iter().all(is_key));
+        let is_absent = |k: i32| k % 2 == 1 && (1..=2 * size as i32 + 1).contains(&k);
+        assert!(Queries::Absent.generate(size, size, 1).into_iter().all(is_absent));
+        let batches = Queries::SortedBatches { batch: 100 }.generate(size, size, 1);
+        assert!(batches.chunks(100).all(|chunk| chunk.windows(2).all(|w| w[0] <= w[1])));
+    }
+}
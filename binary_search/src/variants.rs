@@ -0,0 +1,421 @@
+//! Other ways to search a sorted array, each with a C twin in
+//! `search_variants_algo.c`.
+//!
+//! Every search follows the contract of [`binary_search`]: given keys in
+//! strictly ascending order, it returns the index of `k`, or -1 if `k` is
+//! not there. The Eytzinger and S-tree searches run on a copy of the keys
+//! laid out for the cache, built once from the sorted array, and map the
+//! position they find back to its index there.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::binary_search;
+
+/// Keys per S-tree node: 16 `i32`s fill a 64-byte cache line.
+pub const NODE_KEYS: usize = 16;
+
+/// Lookahead of the Eytzinger prefetch: the node 4 levels down is 16 times
+/// further, and its 16 possible targets share a cache line.
+const PREFETCH_STRIDE: usize = 16;
+
+/// "Monobound" binary search: the remaining length is halved every step
+/// regardless of the comparison, which only picks which half is kept, so
+/// the loop count is fixed and the choice compiles to conditional moves.
+pub fn branchless_search(k: i32, items: &[i32]) -> i32 {
+    if items.is_empty() {
+        return -1;
+    }
+    let mut base = 0;
+    let mut rest = items;
+    while rest.len() > 1 {
+        let half = rest.len() / 2;
+        if rest[half] <= k {
+            base += half;
+            rest = &rest[half..];
+        } else {
+            rest = &rest[..rest.len() - half];
+        }
+    }
+    if items[base] == k {
+        base as i32
+    } else {
+        -1
+    }
+}
+
+/// Index of the first element not below `k`, as C++ `std::lower_bound`.
+pub fn lower_bound(k: i32, items: &[i32]) -> usize {
+    let (mut lo, mut hi) = (0, items.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if items[mid] < k {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Index of the first element above `k`, as C++ `std::upper_bound`.
+pub fn upper_bound(k: i32, items: &[i32]) -> usize {
+    let (mut lo, mut hi) = (0, items.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if items[mid] <= k {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// [`lower_bound`], then a check that it landed on `k`.
+pub fn lower_bound_search(k: i32, items: &[i32]) -> i32 {
+    let i = lower_bound(k, items);
+    if i < items.len() && items[i] == k {
+        i as i32
+    } else {
+        -1
+    }
+}
+
+/// [`upper_bound`], then a check that the element before it is `k`.
+pub fn upper_bound_search(k: i32, items: &[i32]) -> i32 {
+    let i = upper_bound(k, items);
+    if i > 0 && items[i - 1] == k {
+        i as i32 - 1
+    } else {
+        -1
+    }
+}
+
+/// Interpolation search: guesses the position of `k` from the values at
+/// both ends, which takes O(log log n) steps on evenly spread keys.
+pub fn interpolation_search(k: i32, items: &[i32]) -> i32 {
+    if items.is_empty() {
+        return -1;
+    }
+    let (mut lo, mut hi) = (0, items.len() - 1);
+    while lo <= hi && k >= items[lo] && k <= items[hi] {
+        if items[hi] == items[lo] {
+            break;
+        }
+        let offset = (k as i64 - items[lo] as i64) * (hi - lo) as i64 / (items[hi] as i64 - items[lo] as i64);
+        let pos = lo + offset as usize;
+        if items[pos] == k {
+            return pos as i32;
+        }
+        if items[pos] < k {
+            lo = pos + 1;
+        } else if pos == 0 {
+            break;
+        } else {
+            hi = pos - 1;
+        }
+    }
+    if lo < items.len() && items[lo] == k {
+        lo as i32
+    } else {
+        -1
+    }
+}
+
+/// Exponential search: doubles a bound from the start until it passes
+/// `k`, then searches the last doubling with [`lower_bound`]. Cheap for
+/// keys near the front.
+pub fn exponential_search(k: i32, items: &[i32]) -> i32 {
+    let mut bound = 1;
+    while bound < items.len() && items[bound] < k {
+        bound *= 2;
+    }
+    let start = bound / 2;
+    let end = (bound + 1).min(items.len());
+    let i = start + lower_bound(k, &items[start..end]);
+    if i < items.len() && items[i] == k {
+        i as i32
+    } else {
+        -1
+    }
+}
+
+/// `slice::binary_search`.
+pub fn std_search(k: i32, items: &[i32]) -> i32 {
+    match items.binary_search(&k) {
+        Ok(i) => i as i32,
+        Err(_) => -1,
+    }
+}
+
+#[inline(always)]
+fn prefetch(p: *const i32) {
+    // SAFETY: SSE is part of x86_64, and a prefetch never faults, even
+    // past the end of the array.
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        std::arch::x86_64::_mm_prefetch::<{ std::arch::x86_64::_MM_HINT_T0 }>(p as *const i8)
+    };
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = p;
+}
+
+/// The keys in Eytzinger (breadth-first) order: node `i` at `keys[i]` has
+/// its children at `2i` and `2i + 1`. `keys[0]` is unused.
+pub struct Eytzinger {
+    pub keys: Vec<i32>,
+    /// Index in the sorted array of each key.
+    pub index: Vec<i32>,
+}
+
+impl Eytzinger {
+    pub fn new(sorted: &[i32]) -> Self {
+        let n = sorted.len();
+        let mut layout = Eytzinger {
+            keys: vec![0; n + 1],
+            index: vec![-1; n + 1],
+        };
+        layout.fill(sorted, &mut 0, 1);
+        layout
+    }
+
+    /// In-order walk of the tree, handing out the sorted keys.
+    fn fill(&mut self, sorted: &[i32], next: &mut usize, node: usize) {
+        if node < self.keys.len() {
+            self.fill(sorted, next, 2 * node);
+            self.keys[node] = sorted[*next];
+            self.index[node] = *next as i32;
+            *next += 1;
+            self.fill(sorted, next, 2 * node + 1);
+        }
+    }
+
+    /// Descends without branches, prefetching four levels ahead, to the
+    /// node where the lower bound was last seen.
+    pub fn search(&self, k: i32) -> i32 {
+        let keys = &self.keys;
+        let n = keys.len() - 1;
+        let mut i = 1;
+        while i <= n {
+            prefetch(keys.as_ptr().wrapping_add(PREFETCH_STRIDE * i));
+            i = 2 * i + (keys[i] < k) as usize;
+        }
+        // Undo the right turns after the last left one.
+        i >>= (!i).trailing_zeros() + 1;
+        if i != 0 && keys[i] == k {
+            self.index[i]
+        } else {
+            -1
+        }
+    }
+}
+
+/// A static B-tree ("S-tree") of [`NODE_KEYS`] keys per node, stored
+/// without pointers: node `b` has its children at `b * (NODE_KEYS + 1) + i
+/// + 1`. Nodes are padded with `i32::MAX` and index -1.
+pub struct STree {
+    pub keys: Vec<i32>,
+    /// Index in the sorted array of each key.
+    pub index: Vec<i32>,
+}
+
+impl STree {
+    pub fn new(sorted: &[i32]) -> Self {
+        let nodes = sorted.len().div_ceil(NODE_KEYS);
+        let mut tree = STree {
+            keys: vec![i32::MAX; nodes * NODE_KEYS],
+            index: vec![-1; nodes * NODE_KEYS],
+        };
+        tree.fill(sorted, &mut 0, 0);
+        tree
+    }
+
+    fn nodes(&self) -> usize {
+        self.keys.len() / NODE_KEYS
+    }
+
+    fn fill(&mut self, sorted: &[i32], next: &mut usize, node: usize) {
+        if node < self.nodes() {
+            for i in 0..NODE_KEYS {
+                self.fill(sorted, next, node * (NODE_KEYS + 1) + i + 1);
+                if *next < sorted.len() {
+                    self.keys[node * NODE_KEYS + i] = sorted[*next];
+                    self.index[node * NODE_KEYS + i] = *next as i32;
+                    *next += 1;
+                }
+            }
+            self.fill(sorted, next, node * (NODE_KEYS + 1) + NODE_KEYS + 1);
+        }
+    }
+
+    /// In every node, counts the keys below `k` with comparisons that
+    /// vectorize, and remembers the first key not below it.
+    pub fn search(&self, k: i32) -> i32 {
+        let mut node = 0;
+        let mut candidate = usize::MAX;
+        while node < self.nodes() {
+            let keys = &self.keys[node * NODE_KEYS..(node + 1) * NODE_KEYS];
+            let i: usize = keys.iter().map(|&key| (key < k) as usize).sum();
+            if i < NODE_KEYS {
+                candidate = node * NODE_KEYS + i;
+            }
+            node = node * (NODE_KEYS + 1) + i + 1;
+        }
+        if candidate != usize::MAX && self.keys[candidate] == k {
+            self.index[candidate]
+        } else {
+            -1
+        }
+    }
+}
+
+/// `--algo`: which search to run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// [`binary_search`], against the C `binarySearch`.
+    #[default]
+    Classic,
+    Branchless,
+    LowerBound,
+    UpperBound,
+    Eytzinger,
+    STree,
+    Interpolation,
+    Exponential,
+    /// [`std_search`], against libc `bsearch`.
+    Std,
+}
+
+pub const NAMES: [(&str, Algorithm); 9] = [
+    ("classic", Algorithm::Classic),
+    ("branchless", Algorithm::Branchless),
+    ("lower-bound", Algorithm::LowerBound),
+    ("upper-bound", Algorithm::UpperBound),
+    ("eytzinger", Algorithm::Eytzinger),
+    ("s-tree", Algorithm::STree),
+    ("interpolation", Algorithm::Interpolation),
+    ("exponential", Algorithm::Exponential),
+    ("std", Algorithm::Std),
+];
+
+impl Algorithm {
+    /// The search over the sorted array, or `None` for those that need
+    /// their own layout.
+    pub fn on_sorted(self) -> Option<fn(i32, &[i32]) -> i32> {
+        match self {
+            Algorithm::Classic => Some(binary_search),
+            Algorithm::Branchless => Some(branchless_search),
+            Algorithm::LowerBound => Some(lower_bound_search),
+            Algorithm::UpperBound => Some(upper_bound_search),
+            Algorithm::Interpolation => Some(interpolation_search),
+            Algorithm::Exponential => Some(exponential_search),
+            Algorithm::Std => Some(std_search),
+            Algorithm::Eytzinger | Algorithm::STree => None,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, _) = NAMES.iter().find(|(_, algorithm)| algorithm == self).unwrap();
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match NAMES.iter().find(|(name, _)| *name == s) {
+            Some(&(_, algorithm)) => Ok(algorithm),
+            None => {
+                let names: Vec<&str> = NAMES.iter().map(|(name, _)| *name).collect();
+                Err(format!("unknown algorithm: {s} (expected one of {})", names.join(", ")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi;
+
+    /// Keys present, their neighbours and the extremes of `i32`.
+    fn targets(keys: &[i32]) -> Vec<i32> {
+        let mut targets = vec![i32::MIN, -1, 0, 1, i32::MAX];
+        for &key in keys {
+            targets.extend([key.saturating_sub(1), key, key.saturating_add(1)]);
+        }
+        targets
+    }
+
+    /// Every search, in both languages, against the position in `keys`.
+    fn check(keys: &[i32]) {
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        let eytzinger = Eytzinger::new(keys);
+        let tree = STree::new(keys);
+        let c_searches = [
+            ("branchless", ffi::BRANCHLESS_SEARCH),
+            ("lower-bound", ffi::LOWER_BOUND_SEARCH),
+            ("upper-bound", ffi::UPPER_BOUND_SEARCH),
+            ("interpolation", ffi::INTERPOLATION_SEARCH),
+            ("exponential", ffi::EXPONENTIAL_SEARCH),
+            ("std", ffi::STD_SEARCH),
+        ];
+        for k in targets(keys) {
+            let expected = keys.iter().position(|&key| key == k).map_or(-1, |i| i as i32);
+            let context = |name: &str| format!("{name} for {k} in {} keys {:?}", keys.len(), keys);
+            for (name, algorithm) in NAMES {
+                let found = match algorithm.on_sorted() {
+                    Some(search) => search(k, keys),
+                    None if algorithm == Algorithm::Eytzinger => eytzinger.search(k),
+                    None => tree.search(k),
+                };
+                assert_eq!(found, expected, "{}", context(name));
+            }
+            for (name, search) in c_searches {
+                assert_eq!(ffi::search(search, k, keys), expected, "C {}", context(name));
+            }
+            assert_eq!(ffi::binary_search(k, keys), expected, "{}", context("C classic"));
+            assert_eq!(ffi::eytzinger_search(&eytzinger, k), expected, "{}", context("C eytzinger"));
+            assert_eq!(ffi::s_tree_search(&tree, k), expected, "{}", context("C s-tree"));
+        }
+    }
+
+    // Up to 70 keys fills the S-tree root at 16 and four of its children,
+    // and the Eytzinger tree to depths 1 through 7 (full at 2^d - 1).
+    const SIZES: std::ops::RangeInclusive<usize> = 0..=70;
+
+    #[test]
+    fn even_keys() {
+        for n in SIZES {
+            check(&(1..=n as i32).map(|i| 2 * i).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn negative_and_uneven_keys() {
+        for n in SIZES {
+            // Cubes spread the keys unevenly, which interpolation must survive.
+            let keys: Vec<i32> = (0..n as i32).map(|i| (i - n as i32 / 2).pow(3) - 7).collect();
+            check(&keys);
+        }
+    }
+
+    #[test]
+    fn keys_at_the_limits_of_i32() {
+        for n in SIZES {
+            // The S-tree pads its nodes with i32::MAX, which here is a key.
+            let mut keys: Vec<i32> = (0..n as i32).map(|i| i * 1000 - 20_000).collect();
+            if let Some(last) = keys.last_mut() {
+                *last = i32::MAX;
+            }
+            if n > 1 {
+                keys[0] = i32::MIN;
+            }
+            check(&keys);
+        }
+    }
+}
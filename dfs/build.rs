@@ -0,0 +1,10 @@
+// Compiles the C implementation shared with the Makefile so the benchmarks
+// can call it through FFI.
+fn main() {
+    println!("cargo:rerun-if-changed=dfs_algo.c");
+    println!("cargo:rerun-if-changed=dfs_algo.h");
+    cc::Build::new()
+        .file("dfs_algo.c")
+        .opt_level(3)
+        .compile("dfs_algo");
+}
@@ -1,6 +1,9 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <time.h>
+#include "dfs_algo.h"
+#include "bench_input.h"
+#include "bench_rng.h"
 
 int** createAdjMatrix(int n) {
     int** matrix = (int**)malloc(n * sizeof(int*));
@@ -17,11 +20,11 @@ void freeAdjMatrix(int** matrix, int n) {
     free(matrix);
 }
 
-void generateRandomGraph(int** adjMatrix, int n) {
-    srand(time(NULL));
+void generateRandomGraph(int** adjMatrix, int n, uint64_t seed) {
+    BenchRng rng = {seed};
     for (int i = 0; i < n; i++) {
         for (int j = i + 1; j < n; j++) {
-            if (rand() % 2) { // Randomly connect nodes with a 50% probability
+            if (bench_rng_below(&rng, 2)) { // Randomly connect nodes with a 50% probability
                 adjMatrix[i][j] = 1;
                 adjMatrix[j][i] = 1;
             }
@@ -29,13 +32,17 @@ void generateRandomGraph(int** adjMatrix, int n) {
     }
 }
 
-void dfs(int** adjMatrix, int n, int start, int* visited) {
-    visited[start] = 1;
+// Function to load a graph written by the Rust benchmark with --dump-input
+int loadGraph(int** adjMatrix, int n, const char* path) {
+    int* data = bench_input_load(path, n, 0, (long long)n * n);
+    if (!data) {
+        return 1;
+    }
     for (int i = 0; i < n; i++) {
-        if (adjMatrix[start][i] == 1 && !visited[i]) {
-            dfs(adjMatrix, n, i, visited);
-        }
+        memcpy(adjMatrix[i], data + (long long)i * n, n * sizeof(int));
     }
+    free(data);
+    return 0;
 }
 
 double diff_timespec(struct timespec *time1, struct timespec *time0) {
@@ -44,8 +51,12 @@ double diff_timespec(struct timespec *time1, struct timespec *time0) {
 }
 
 int main(int argc, char* argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <number_of_nodes>\n", argv[0]);
+    uint64_t seed = BENCH_DEFAULT_SEED;
+    if (bench_take_seed(&argc, argv, &seed) != 0) {
+        return 1;
+    }
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <number_of_nodes> [input_file] [--seed N]\n", argv[0]);
         return 1;
     }
 
@@ -57,23 +68,36 @@ int main(int argc, char* argv[]) {
     }
 
     int** adjMatrix = createAdjMatrix(size);
-    generateRandomGraph(adjMatrix, size);
+    if (argc == 3) {
+        if (loadGraph(adjMatrix, size, argv[2]) != 0) {
+            return 1;
+        }
+    } else {
+        generateRandomGraph(adjMatrix, size, seed);
+    }
 
-    int* visited = (int*)calloc(size, sizeof(int));
+    // Visit order, search tree and levels, filled in by the search
+    int* order = (int*)malloc(size * sizeof(int));
+    int* parent = (int*)malloc(size * sizeof(int));
+    int* level = (int*)malloc(size * sizeof(int));
+    memset(parent, -1, size * sizeof(int));
+    memset(level, -1, size * sizeof(int));
 
     struct timespec start_time, end_time;
 
     // Measure the time for DFS
     clock_gettime(CLOCK_MONOTONIC, &start_time);
-    dfs(adjMatrix, size, 0, visited);
+    int reached = dfs(adjMatrix, size, 0, order, parent, level);
     clock_gettime(CLOCK_MONOTONIC, &end_time);
 
     double time_elapsed = diff_timespec(&end_time, &start_time);
 
-    printf("Time taken to search graph of size %d: %f seconds\n", size, time_elapsed);
+    printf("Time taken to search graph of size %d: %f seconds, %d nodes reached\n", size, time_elapsed, reached);
 
     freeAdjMatrix(adjMatrix, size);
-    free(visited);
+    free(order);
+    free(parent);
+    free(level);
 
     return 0;
 }
@@ -0,0 +1,63 @@
+#include <stdlib.h>
+#include "dfs_algo.h"
+
+static void dfsVisit(int** adjMatrix, int n, int current, int* order, int* count, int* parent, int* level) {
+    order[(*count)++] = current;
+    for (int i = 0; i < n; i++) {
+        if (adjMatrix[current][i] == 1 && parent[i] == -1) {
+            parent[i] = current;
+            level[i] = level[current] + 1;
+            dfsVisit(adjMatrix, n, i, order, count, parent, level);
+        }
+    }
+}
+
+int dfs(int** adjMatrix, int n, int start, int* order, int* parent, int* level) {
+    int count = 0;
+    parent[start] = start;
+    level[start] = 0;
+    dfsVisit(adjMatrix, n, start, order, &count, parent, level);
+    return count;
+}
+
+int dfs_iterative(int** adjMatrix, int n, int start, int* order, int* parent, int* level) {
+    // Each entry is a node and the next neighbour to look at, just like the
+    // locals of a suspended dfsVisit() call.
+    int* nodes = (int*)malloc(n * sizeof(int));
+    int* next = (int*)malloc(n * sizeof(int));
+    if (!nodes || !next) {
+        free(nodes);
+        free(next);
+        return -1;
+    }
+
+    int count = 0;
+    int top = 0;
+    parent[start] = start;
+    level[start] = 0;
+    order[count++] = start;
+    nodes[0] = start;
+    next[0] = 0;
+    while (top >= 0) {
+        int current = nodes[top];
+        int i = next[top];
+        while (i < n && !(adjMatrix[current][i] == 1 && parent[i] == -1)) {
+            i++;
+        }
+        if (i == n) {
+            top--;
+            continue;
+        }
+        next[top] = i + 1;
+        parent[i] = current;
+        level[i] = level[current] + 1;
+        order[count++] = i;
+        top++;
+        nodes[top] = i;
+        next[top] = 0;
+    }
+
+    free(nodes);
+    free(next);
+    return count;
+}
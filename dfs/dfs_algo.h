@@ -0,0 +1,17 @@
+#ifndef DFS_ALGO_H
+#define DFS_ALGO_H
+
+// Both searches take an n x n adjacency matrix, n-entry parent and level
+// arrays set to -1 and room for n nodes in order. They fill in the visit
+// order, the DFS tree and each node's depth in it, and return the number
+// of nodes reached.
+
+// Recursive Depth-First Search
+int dfs(int** adjMatrix, int n, int start, int* order, int* parent, int* level);
+
+// Depth-First Search with an explicit stack, visiting nodes in the same
+// order as dfs() but without the recursion depth limit. Returns -1 if the
+// stack could not be allocated.
+int dfs_iterative(int** adjMatrix, int n, int start, int* order, int* parent, int* level);
+
+#endif
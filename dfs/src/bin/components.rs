@@ -0,0 +1,6 @@
+use dfs::components::Components;
+use harness::Runner;
+
+fn main() {
+    Runner::new().run(Components::default());
+}
@@ -0,0 +1,306 @@
+//! Connected components by DFS, labelling every node with the smallest node
+//! of its component, sequentially and on several threads.
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering::Relaxed};
+use std::thread;
+
+use graph::traversal::{as_atomic, UNREACHED};
+use graph::{with_graph, AnyGraph, Graph, GraphOptions};
+use harness::{Args, Benchmark};
+
+/// Nodes a thread claims at a time.
+const CHUNK: usize = 1024;
+
+/// Labels the components of `graph` by a DFS from every node not labelled
+/// yet, in ascending order, and returns how many there are. `labels` must
+/// start out all [`UNREACHED`].
+pub fn components<G: Graph>(graph: &G, labels: &mut [u32]) -> usize {
+    let mut count = 0;
+    let mut stack = Vec::new();
+    for root in 0..graph.node_count() {
+        if labels[root] != UNREACHED {
+            continue;
+        }
+        count += 1;
+        labels[root] = root as u32;
+        stack.push(graph.neighbors(root));
+        while let Some(neighbors) = stack.last_mut() {
+            match neighbors.find(|&i| labels[i] == UNREACHED) {
+                Some(next) => {
+                    labels[next] = root as u32;
+                    stack.push(graph.neighbors(next));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+    count
+}
+
+/// [`components`] on `threads` scoped threads, with the same labels.
+///
+/// The threads take seeds from chunks of the nodes and grow a DFS tree from
+/// each seed they manage to claim, claiming nodes by compare-and-swap on
+/// their label. Trees that run into each other belong to the same
+/// component; those meetings are merged with union-find afterwards, and a
+/// last pass relabels every node with the smallest node of its merged
+/// component.
+pub fn components_parallel<G: Graph + Sync>(graph: &G, labels: &mut [u32], threads: usize) -> usize {
+    let n = graph.node_count();
+    let labels = as_atomic(labels);
+
+    let mut meetings: Vec<(u32, u32)> = in_chunks(n, threads, |nodes, meetings: &mut Vec<(u32, u32)>| {
+        let mut stack = Vec::new();
+        for seed in nodes {
+            if claim(labels, seed, seed as u32).is_err() {
+                continue;
+            }
+            stack.push(seed);
+            while let Some(current) = stack.pop() {
+                for i in graph.neighbors(current) {
+                    match claim(labels, i, seed as u32) {
+                        Ok(()) => stack.push(i),
+                        Err(label) if label != seed as u32 => meetings.push((seed as u32, label)),
+                        Err(_) => {}
+                    }
+                }
+            }
+        }
+    })
+    .concat();
+    meetings.sort_unstable();
+    meetings.dedup();
+
+    // Union by the smaller seed, so every seed points to a smaller one and
+    // the root of a set is its smallest seed.
+    let mut root: Vec<u32> = (0..n as u32).collect();
+    for (a, b) in meetings {
+        let (a, b) = (find(&root, a), find(&root, b));
+        root[a.max(b) as usize] = a.min(b);
+    }
+    for node in 0..n {
+        root[node] = root[root[node] as usize];
+    }
+
+    let smallest: Vec<AtomicU32> = (0..n).map(|_| AtomicU32::new(UNREACHED)).collect();
+    in_chunks(n, threads, |nodes, _: &mut ()| {
+        for node in nodes {
+            smallest[root[labels[node].load(Relaxed) as usize] as usize].fetch_min(node as u32, Relaxed);
+        }
+    });
+    in_chunks(n, threads, |nodes, count: &mut usize| {
+        for node in nodes {
+            let label = smallest[root[labels[node].load(Relaxed) as usize] as usize].load(Relaxed);
+            labels[node].store(label, Relaxed);
+            *count += (label == node as u32) as usize;
+        }
+    })
+    .into_iter()
+    .sum()
+}
+
+/// Labels `node` with `seed` unless it has a label already, which is
+/// returned instead.
+#[inline]
+fn claim(labels: &[AtomicU32], node: usize, seed: u32) -> Result<(), u32> {
+    match labels[node].load(Relaxed) {
+        UNREACHED => labels[node].compare_exchange(UNREACHED, seed, Relaxed, Relaxed).map(|_| ()),
+        label => Err(label),
+    }
+}
+
+fn find(root: &[u32], mut node: u32) -> u32 {
+    while root[node as usize] != node {
+        node = root[node as usize];
+    }
+    node
+}
+
+/// Runs `work` on `threads` scoped threads that take chunks of `0..n` until
+/// none are left, and returns what each thread collected.
+fn in_chunks<T, F>(n: usize, threads: usize, work: F) -> Vec<T>
+where
+    T: Default + Send,
+    F: Fn(Range<usize>, &mut T) + Sync,
+{
+    let cursor = AtomicUsize::new(0);
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut collected = T::default();
+                    loop {
+                        let start = cursor.fetch_add(CHUNK, Relaxed);
+                        if start >= n {
+                            return collected;
+                        }
+                        work(start..(start + CHUNK).min(n), &mut collected);
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+pub struct ComponentsInput {
+    pub graph: AnyGraph,
+    pub labels: Vec<u32>,
+    /// Labels from the sequential version at setup.
+    pub expected: Vec<u32>,
+}
+
+#[derive(Default)]
+pub struct Components {
+    seed: u64,
+    graph: GraphOptions,
+    /// `None` runs the sequential version.
+    threads: Option<usize>,
+}
+
+impl Benchmark for Components {
+    type Input = ComponentsInput;
+    /// Number of components.
+    type Output = usize;
+
+    fn name(&self) -> &str {
+        "components"
+    }
+
+    fn variant(&self) -> String {
+        self.graph.variant()
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn threads(&self) -> Option<usize> {
+        self.threads
+    }
+
+    fn set_threads(&mut self, threads: Option<usize>) -> bool {
+        self.threads = threads;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE]"
+    }
+
+    /// Without `--threads`, runs on every core.
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.threads = Some(thread::available_parallelism().map_or(1, |n| n.get()));
+        self.graph.configure(args)
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let graph = self.graph.build(size, self.seed);
+        let mut expected = vec![UNREACHED; size];
+        with_graph!(&graph, g => components(g, &mut expected));
+        ComponentsInput {
+            graph,
+            labels: vec![UNREACHED; size],
+            expected,
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> usize {
+        let labels = &mut input.labels;
+        with_graph!(&input.graph, g => match self.threads {
+            Some(threads) => components_parallel(g, labels, threads),
+            None => components(g, labels),
+        })
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.labels.fill(UNREACHED);
+    }
+
+    /// Every node must carry the label of the sequential version.
+    fn verify(&self, input: &Self::Input, _count: &usize) -> Result<(), String> {
+        match (0..input.labels.len()).find(|&node| input.labels[node] != input.expected[node]) {
+            Some(node) => Err(format!(
+                "node {} is labelled {}, expected {}",
+                node, input.labels[node] as i32, input.expected[node]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to label the components of graph of size {}", size)
+    }
+
+    fn summary(&self, count: &usize) -> Option<String> {
+        Some(format!("{} components, {}", count, self.graph.summary()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{Csr, FromEdges, Generator, Representation};
+    use harness::input::Rng;
+
+    fn labels<G: Graph + Sync>(graph: &G, threads: Option<usize>) -> (usize, Vec<u32>) {
+        let mut labels = vec![UNREACHED; graph.node_count()];
+        let count = match threads {
+            Some(threads) => components_parallel(graph, &mut labels, threads),
+            None => components(graph, &mut labels),
+        };
+        (count, labels)
+    }
+
+    #[test]
+    fn labels_small_graph() {
+        // 0-3-5 and 1-4, with 2 and 6 alone.
+        let graph = Csr::from_edges(7, &[(3, 0), (5, 3), (4, 1)]);
+        let expected = (4, vec![0, 1, 2, 0, 1, 0, 6]);
+        assert_eq!(labels(&graph, None), expected);
+        for threads in 1..=4 {
+            assert_eq!(labels(&graph, Some(threads)), expected, "{threads} threads");
+        }
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        for (spec, n) in [
+            // Average degree 1.6 or below: many components of every size.
+            ("er:p=0.0016", 1000),
+            ("er:m=600", 3000),
+            ("er:p=0.01", 500),
+            ("rmat:edge_factor=1", 4096),
+            ("grid2d", 2500),
+            ("ba:m=1", 2000),
+        ] {
+            let generator: Generator = spec.parse().unwrap();
+            for representation in [Representation::Csr, Representation::List, Representation::BitMatrix] {
+                let graph = AnyGraph::generate(representation, &generator, n, &mut Rng::new(7), None);
+                let expected = with_graph!(&graph, g => labels(g, None));
+                let roots = (0..n).filter(|&node| expected.1[node] == node as u32).count();
+                assert_eq!(expected.0, roots, "{spec} as {representation}");
+                // More threads than chunks too, so that some get none.
+                for threads in [1, 2, 3, 4, 8] {
+                    let parallel = with_graph!(&graph, g => labels(g, Some(threads)));
+                    assert!(parallel == expected, "{spec} as {representation} on {threads} threads");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Bindings to the C implementation in `dfs_algo.c`.
+
+use std::os::raw::c_int;
+
+use graph::Traversal;
+
+type CSearch = unsafe extern "C" fn(
+    adj_matrix: *const *const c_int,
+    n: c_int,
+    start: c_int,
+    order: *mut c_int,
+    parent: *mut c_int,
+    level: *mut c_int,
+) -> c_int;
+
+extern "C" {
+    #[link_name = "dfs"]
+    fn c_dfs(
+        adj_matrix: *const *const c_int,
+        n: c_int,
+        start: c_int,
+        order: *mut c_int,
+        parent: *mut c_int,
+        level: *mut c_int,
+    ) -> c_int;
+    #[link_name = "dfs_iterative"]
+    fn c_dfs_iterative(
+        adj_matrix: *const *const c_int,
+        n: c_int,
+        start: c_int,
+        order: *mut c_int,
+        parent: *mut c_int,
+        level: *mut c_int,
+    ) -> c_int;
+}
+
+/// Calls one of the C searches over the same adjacency matrix, filling in
+/// `search` from its root. They take an `int**`, so a table of row pointers
+/// is built first.
+fn call(search_fn: CSearch, adj_matrix: &[Vec<i32>], search: &mut Traversal) {
+    let n = adj_matrix.len();
+    assert!(search.root < n && search.parent.len() == n && search.level.len() == n);
+    assert!(search.reached() == 0 && search.order.capacity() >= n);
+    assert!(adj_matrix.iter().all(|row| row.len() == n));
+    let rows: Vec<*const c_int> = adj_matrix.iter().map(|row| row.as_ptr()).collect();
+    let n = c_int::try_from(n).expect("graph too large for the C implementation");
+    // SAFETY: the matrix is n x n and only read. `parent` and `level` hold
+    // n entries, unreached ones are UNREACHED, which is -1 as an int, and
+    // `order` has room for n. The C code writes `reached` entries of
+    // `order`, and never more than n.
+    unsafe {
+        let reached = search_fn(
+            rows.as_ptr(),
+            n,
+            search.root as c_int,
+            search.order.as_mut_ptr().cast(),
+            search.parent.as_mut_ptr().cast(),
+            search.level.as_mut_ptr().cast(),
+        );
+        assert!(reached >= 0, "the C search could not allocate its stack");
+        search.order.set_len(reached as usize);
+    }
+}
+
+/// Runs the recursive C `dfs`.
+pub fn dfs(adj_matrix: &[Vec<i32>], search: &mut Traversal) {
+    call(c_dfs, adj_matrix, search)
+}
+
+/// Runs the C `dfs_iterative`, which keeps its own stack on the heap.
+pub fn dfs_iterative(adj_matrix: &[Vec<i32>], search: &mut Traversal) {
+    call(c_dfs_iterative, adj_matrix, search)
+}
@@ -0,0 +1,363 @@
+use std::fmt;
+use std::mem;
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use graph::{with_graph, AnyGraph, Graph, GraphOptions, Representation, Traversal};
+use harness::{Args, Benchmark, Language};
+
+pub mod components;
+pub mod ffi;
+
+/// Recursive DFS from `search.root`, recording the visit order, the DFS
+/// tree and every node's depth in `search`. Uses a stack frame per level,
+/// so a deep graph can overflow the thread's stack; see
+/// [`Style::Recursive`].
+pub fn dfs<G: Graph>(graph: &G, search: &mut Traversal) {
+    fn visit<G: Graph>(graph: &G, search: &mut Traversal, current: usize) {
+        for i in graph.neighbors(current) {
+            if !search.is_reached(i) {
+                search.reach(i, current);
+                visit(graph, search, i);
+            }
+        }
+    }
+
+    search.start();
+    visit(graph, search, search.root);
+}
+
+/// Preorder DFS with an explicit stack of (node, parent) pairs. A node is
+/// marked when it is popped and its unvisited neighbours are pushed in
+/// reverse, so the smallest is popped first and the order matches [`dfs`].
+/// A node can sit on the stack several times, so the stack holds up to one
+/// entry per edge.
+pub fn dfs_stack<G: Graph>(graph: &G, search: &mut Traversal) {
+    search.start();
+    let mut stack: Vec<(usize, usize)> = graph
+        .neighbors(search.root)
+        .filter(|&i| !search.is_reached(i))
+        .map(|i| (i, search.root))
+        .collect();
+    stack.reverse();
+    while let Some((current, parent)) = stack.pop() {
+        if search.is_reached(current) {
+            continue;
+        }
+        search.reach(current, parent);
+        let top = stack.len();
+        stack.extend(graph.neighbors(current).filter(|&i| !search.is_reached(i)).map(|i| (i, current)));
+        stack[top..].reverse();
+    }
+}
+
+/// DFS with an explicit stack of neighbour iterators, one per node on the
+/// current path: the same state the recursive calls keep in their frames,
+/// moved to the heap. Visits nodes in exactly the order of [`dfs`].
+pub fn dfs_iterator<G: Graph>(graph: &G, search: &mut Traversal) {
+    search.start();
+    let mut stack = vec![(search.root, graph.neighbors(search.root))];
+    while let Some((current, neighbors)) = stack.last_mut() {
+        let current = *current;
+        match neighbors.find(|&i| !search.is_reached(i)) {
+            Some(next) => {
+                search.reach(next, current);
+                stack.push((next, graph.neighbors(next)));
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// How the traversal keeps track of where it is, from `--style`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Style {
+    /// [`dfs`], on the main thread or, with `--stack-size`, on a thread
+    /// with a stack of that size.
+    #[default]
+    Recursive,
+    /// [`dfs_stack`].
+    Stack,
+    /// [`dfs_iterator`].
+    Iterator,
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Style::Recursive => write!(f, "recursive"),
+            Style::Stack => write!(f, "stack"),
+            Style::Iterator => write!(f, "iterator"),
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recursive" => Ok(Style::Recursive),
+            "stack" => Ok(Style::Stack),
+            "iterator" => Ok(Style::Iterator),
+            _ => Err(format!("unknown style: {s} (expected recursive, stack or iterator)")),
+        }
+    }
+}
+
+/// A size in bytes with an optional `K`, `M` or `G` suffix, e.g. `512M`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackSize(pub usize);
+
+impl fmt::Display for StackSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (suffix, unit) in [("G", 1 << 30), ("M", 1 << 20), ("K", 1 << 10)] {
+            if self.0 >= unit && self.0.is_multiple_of(unit) {
+                return write!(f, "{}{}", self.0 / unit, suffix);
+            }
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for StackSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, unit) = match s.char_indices().last() {
+            Some((i, 'K' | 'k')) => (&s[..i], 1 << 10),
+            Some((i, 'M' | 'm')) => (&s[..i], 1 << 20),
+            Some((i, 'G' | 'g')) => (&s[..i], 1 << 30),
+            _ => (s, 1),
+        };
+        digits
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_mul(unit))
+            .filter(|&n| n > 0)
+            .map(StackSize)
+            .ok_or_else(|| format!("expected a positive size such as 65536, 512K or 64M, got {s}"))
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// A thread with a stack of `--stack-size` bytes, started in setup and
+/// handed one run at a time, so the measured time does not include
+/// starting and joining it. Being started after the counters are opened,
+/// it inherits them.
+pub struct Worker {
+    jobs: Option<mpsc::Sender<Job>>,
+    done: mpsc::Receiver<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    pub fn new(StackSize(size): StackSize) -> Worker {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let (finished, done) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("dfs".to_string())
+            .stack_size(size)
+            .spawn(move || {
+                for job in queue {
+                    job();
+                    if finished.send(()).is_err() {
+                        break;
+                    }
+                }
+            })
+            .unwrap_or_else(|err| panic!("failed to start a thread with a {} stack: {}", StackSize(size), err));
+        Worker {
+            jobs: Some(jobs),
+            done,
+            thread: Some(thread),
+        }
+    }
+
+    /// Runs `f` on the worker and waits for it to finish.
+    pub fn run<'a, F: FnOnce() + Send + 'a>(&self, f: F) {
+        let job: Box<dyn FnOnce() + Send + 'a> = Box::new(f);
+        // SAFETY: only the lifetime is erased. This waits until the job has
+        // run, or exits if it panicked, so nothing it borrows can go away
+        // while the worker holds it.
+        let job: Job = unsafe { mem::transmute::<Box<dyn FnOnce() + Send + 'a>, Job>(job) };
+        if let Some(jobs) = &self.jobs {
+            if jobs.send(job).is_err() {
+                exit(1);
+            }
+        }
+        if self.done.recv().is_err() {
+            exit(1);
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        drop(self.jobs.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Runs `f` on `worker`, or on the current thread without one.
+fn on_stack<F: FnOnce() + Send>(worker: Option<&Worker>, f: F) {
+    match worker {
+        Some(worker) => worker.run(f),
+        None => f(),
+    }
+}
+
+pub struct DfsInput {
+    pub graph: AnyGraph,
+    pub search: Traversal,
+    /// Result of [`dfs_iterator`] at setup, which every style in either
+    /// language must reproduce.
+    pub expected: Traversal,
+    /// With `--stack-size`, the thread the recursive style runs on.
+    pub worker: Option<Worker>,
+}
+
+#[derive(Default)]
+pub struct Dfs {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+    style: Style,
+    /// `--stack-size`: run the recursive style on a thread with this stack.
+    stack_size: Option<StackSize>,
+    /// Checksum of the expected result, which every run has matched.
+    checksum: u64,
+}
+
+impl Benchmark for Dfs {
+    type Input = DfsInput;
+    /// Number of nodes reached.
+    type Output = usize;
+
+    fn name(&self) -> &str {
+        "dfs"
+    }
+
+    fn variant(&self) -> String {
+        match self.stack_size {
+            Some(size) => format!("{}/{}:stack={}", self.graph.variant(), self.style, size),
+            None => format!("{}/{}", self.graph.variant(), self.style),
+        }
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The C version only takes the dense matrix, and has no counterpart
+    /// of the preorder stack.
+    fn set_language(&mut self, language: Language) -> bool {
+        if language == Language::C && (self.graph.representation != Representation::Matrix || self.style == Style::Stack) {
+            return false;
+        }
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE] \
+         [--style recursive|stack|iterator] [--stack-size BYTES[K|M|G]]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.style = args.parse("--style")?.unwrap_or_default();
+        self.stack_size = args.parse("--stack-size")?;
+        if self.stack_size.is_some() && self.style != Style::Recursive {
+            return Err("--stack-size only applies to --style recursive".to_string());
+        }
+        self.graph.configure(args)
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let graph = self.graph.build(size, self.seed);
+        let mut expected = Traversal::new(size, 0);
+        with_graph!(&graph, g => dfs_iterator(g, &mut expected));
+        self.checksum = expected.checksum();
+        DfsInput {
+            graph,
+            search: Traversal::new(size, 0),
+            expected,
+            worker: self.stack_size.map(Worker::new),
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> usize {
+        let search = &mut input.search;
+        let worker = input.worker.as_ref();
+        match (self.language, &input.graph) {
+            (Language::C, AnyGraph::Matrix(g)) => match self.style {
+                Style::Recursive => on_stack(worker, || ffi::dfs(g, search)),
+                Style::Iterator => ffi::dfs_iterative(g, search),
+                Style::Stack => unreachable!("the C version has no preorder stack"),
+            },
+            (Language::C, _) => unreachable!("the C version needs the dense matrix"),
+            (Language::Rust, graph) => with_graph!(graph, g => match self.style {
+                Style::Recursive => on_stack(worker, || dfs(g, search)),
+                Style::Stack => dfs_stack(g, search),
+                Style::Iterator => dfs_iterator(g, search),
+            }),
+        }
+        search.reached()
+    }
+
+    /// The adjacency matrix, row by row.
+    fn input_arrays(&self, input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        match &input.graph {
+            AnyGraph::Matrix(adj_matrix) => Some(vec![adj_matrix.concat()]),
+            _ => None,
+        }
+    }
+
+    /// The graph is only read, so only the result needs clearing.
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.search.clear();
+    }
+
+    /// Validates the DFS tree against the graph, then compares the whole
+    /// result with the one from setup, so every style and language must
+    /// visit the nodes in the same order.
+    fn verify(&self, input: &Self::Input, _reached: &usize) -> Result<(), String> {
+        with_graph!(&input.graph, g => input.search.validate_dfs(g))?;
+        input.search.compare(&input.expected)
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to search graph of size {}", size)
+    }
+
+    fn summary(&self, reached: &usize) -> Option<String> {
+        Some(format!(
+            "{} nodes reached (checksum {:016x}), {}",
+            reached,
+            self.checksum,
+            self.graph.summary()
+        ))
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{FromEdges, Graph};
+
+/// Dense adjacency matrix with one bit per entry, stored row by row in a
+/// single allocation.
+pub struct BitMatrix {
+    n: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl BitMatrix {
+    pub fn new(n: usize) -> Self {
+        let words_per_row = n.div_ceil(64);
+        BitMatrix {
+            n,
+            words_per_row,
+            bits: vec![0; n * words_per_row],
+        }
+    }
+
+    pub fn set(&mut self, u: usize, v: usize) {
+        self.bits[u * self.words_per_row + v / 64] |= 1 << (v % 64);
+    }
+
+    pub fn contains(&self, u: usize, v: usize) -> bool {
+        self.bits[u * self.words_per_row + v / 64] & (1 << (v % 64)) != 0
+    }
+
+    fn row(&self, u: usize) -> &[u64] {
+        &self.bits[u * self.words_per_row..(u + 1) * self.words_per_row]
+    }
+}
+
+/// Set bits of one row, lowest first.
+pub struct BitNeighbors<'a> {
+    words: &'a [u64],
+    /// Remaining bits of `words[index]`.
+    current: u64,
+    index: usize,
+}
+
+impl Iterator for BitNeighbors<'_> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.index * 64 + bit)
+    }
+}
+
+impl Graph for BitMatrix {
+    type Neighbors<'a> = BitNeighbors<'a>;
+
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    fn edge_count(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    #[inline]
+    fn neighbors(&self, node: usize) -> BitNeighbors<'_> {
+        let words = self.row(node);
+        BitNeighbors {
+            words,
+            current: words.first().copied().unwrap_or(0),
+            index: 0,
+        }
+    }
+
+    fn degree(&self, node: usize) -> usize {
+        self.row(node).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.bits.capacity() * 8
+    }
+}
+
+impl FromEdges for BitMatrix {
+    fn from_edges(n: usize, edges: &[(u32, u32)]) -> Self {
+        let mut matrix = BitMatrix::new(n);
+        for &(u, v) in edges {
+            matrix.set(u as usize, v as usize);
+            matrix.set(v as usize, u as usize);
+        }
+        matrix
+    }
+
+    fn from_arcs(n: usize, arcs: &[(u32, u32)]) -> Self {
+        let mut matrix = BitMatrix::new(n);
+        for &(u, v) in arcs {
+            matrix.set(u as usize, v as usize);
+        }
+        matrix
+    }
+}
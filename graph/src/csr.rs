@@ -0,0 +1,57 @@
+use crate::{AdjList, FromEdges, Graph, NodeIds};
+
+/// Compressed sparse row: the neighbours of node `i` are
+/// `targets[offsets[i]..offsets[i + 1]]`, all in two allocations.
+pub struct Csr {
+    pub offsets: Vec<usize>,
+    pub targets: Vec<u32>,
+}
+
+impl Graph for Csr {
+    type Neighbors<'a> = NodeIds<'a>;
+
+    fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    #[inline]
+    fn neighbors(&self, node: usize) -> NodeIds<'_> {
+        NodeIds(self.targets[self.offsets[node]..self.offsets[node + 1]].iter())
+    }
+
+    #[inline]
+    fn degree(&self, node: usize) -> usize {
+        self.offsets[node + 1] - self.offsets[node]
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.offsets.capacity() * std::mem::size_of::<usize>() + self.targets.capacity() * 4
+    }
+}
+
+impl From<&AdjList> for Csr {
+    fn from(list: &AdjList) -> Self {
+        let mut offsets = Vec::with_capacity(list.adjacency.len() + 1);
+        let mut targets = Vec::with_capacity(list.edge_count());
+        offsets.push(0);
+        for neighbors in &list.adjacency {
+            targets.extend_from_slice(neighbors);
+            offsets.push(targets.len());
+        }
+        Csr { offsets, targets }
+    }
+}
+
+impl FromEdges for Csr {
+    fn from_edges(n: usize, edges: &[(u32, u32)]) -> Self {
+        Csr::from(&AdjList::from_edges(n, edges))
+    }
+
+    fn from_arcs(n: usize, arcs: &[(u32, u32)]) -> Self {
+        Csr::from(&AdjList::from_arcs(n, arcs))
+    }
+}
@@ -0,0 +1,545 @@
+//! Random graph generators.
+//!
+//! A generator is written as `name[:param=value,...]`, e.g. `er:p=0.001`,
+//! `ba:m=4` or `ws:k=6,beta=0.1`, and always produces an undirected graph
+//! on exactly `n` nodes. Edges are handed to a callback one by one, so a
+//! dense matrix can be filled without an intermediate edge list.
+//!
+//! A directed graph is made by giving each generated edge a direction with
+//! an [`Orientation`].
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use harness::args::Params;
+use harness::input::Rng;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Generator {
+    /// Erdős–Rényi G(n, p): every pair is connected with probability `p`.
+    ErdosRenyi { p: f64 },
+    /// Erdős–Rényi G(n, m): `m` distinct edges chosen uniformly.
+    ErdosRenyiEdges { m: u64 },
+    /// Barabási–Albert preferential attachment, `m` edges per new node.
+    BarabasiAlbert { m: usize },
+    /// R-MAT / Kronecker as in Graph500: `edge_factor * n` edges placed by
+    /// recursive quadrant probabilities `a`, `b`, `c` (and `1 - a - b - c`),
+    /// with node labels shuffled afterwards.
+    Rmat { edge_factor: usize, a: f64, b: f64, c: f64 },
+    /// 4-neighbour mesh, `ceil(sqrt(n))` nodes wide.
+    Grid2d,
+    /// 6-neighbour mesh, `ceil(cbrt(n))` nodes along each side.
+    Grid3d,
+    /// Uniform-ish random `d`-regular graph (pairing model with restarts).
+    RandomRegular { d: usize },
+    /// Watts–Strogatz small world: a ring where every node links to its `k`
+    /// nearest neighbours, each edge rewired with probability `beta`.
+    WattsStrogatz { k: usize, beta: f64 },
+}
+
+impl Default for Generator {
+    /// The original benchmark graph: every pair connected with probability 1/2.
+    fn default() -> Self {
+        Generator::ErdosRenyi { p: 0.5 }
+    }
+}
+
+impl fmt::Display for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Generator::ErdosRenyi { p } => write!(f, "er:p={}", p),
+            Generator::ErdosRenyiEdges { m } => write!(f, "er:m={}", m),
+            Generator::BarabasiAlbert { m } => write!(f, "ba:m={}", m),
+            Generator::Rmat { edge_factor, a, b, c } => {
+                write!(f, "rmat:edge_factor={},a={},b={},c={}", edge_factor, a, b, c)
+            }
+            Generator::Grid2d => write!(f, "grid2d"),
+            Generator::Grid3d => write!(f, "grid3d"),
+            Generator::RandomRegular { d } => write!(f, "regular:d={}", d),
+            Generator::WattsStrogatz { k, beta } => write!(f, "ws:k={},beta={}", k, beta),
+        }
+    }
+}
+
+impl FromStr for Generator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let mut params = Params::parse(params)?;
+        let generator = match name {
+            "er" => match params.get::<u64>("m")? {
+                Some(m) => Generator::ErdosRenyiEdges { m },
+                None => Generator::ErdosRenyi {
+                    p: params.get("p")?.unwrap_or(0.5),
+                },
+            },
+            "ba" => Generator::BarabasiAlbert {
+                m: params.get("m")?.unwrap_or(4),
+            },
+            "rmat" => Generator::Rmat {
+                edge_factor: params.get("edge_factor")?.unwrap_or(16),
+                a: params.get("a")?.unwrap_or(0.57),
+                b: params.get("b")?.unwrap_or(0.19),
+                c: params.get("c")?.unwrap_or(0.19),
+            },
+            "grid2d" => Generator::Grid2d,
+            "grid3d" => Generator::Grid3d,
+            "regular" => Generator::RandomRegular {
+                d: params.get("d")?.unwrap_or(4),
+            },
+            "ws" => Generator::WattsStrogatz {
+                k: params.get("k")?.unwrap_or(4),
+                beta: params.get("beta")?.unwrap_or(0.1),
+            },
+            _ => {
+                return Err(format!(
+                    "unknown generator: {name} (expected er, ba, rmat, grid2d, grid3d, regular or ws)"
+                ))
+            }
+        };
+        params.finish(name)?;
+        Ok(generator)
+    }
+}
+
+impl Generator {
+    /// Checks that the parameters make sense for a graph of `n` nodes.
+    pub fn check(&self, n: usize) -> Result<(), String> {
+        if n > u32::MAX as usize {
+            return Err(format!("{} nodes do not fit in 32-bit node ids", n));
+        }
+        match *self {
+            Generator::ErdosRenyi { p } if !(0.0..=1.0).contains(&p) => Err(format!("p must be in [0, 1], got {}", p)),
+            Generator::ErdosRenyiEdges { m } if m > pairs(n) => {
+                Err(format!("{} nodes have only {} possible edges, not {}", n, pairs(n), m))
+            }
+            Generator::BarabasiAlbert { m: 0 } => Err("m must be at least 1".to_string()),
+            Generator::Rmat { a, b, c, .. } if a < 0.0 || b < 0.0 || c < 0.0 || a + b + c > 1.0 => {
+                Err("a, b and c must be non-negative with a + b + c <= 1".to_string())
+            }
+            // Ids past n are drawn again, and only a draw starting in the
+            // top-left quadrant is sure to stay below n.
+            Generator::Rmat { a, .. } if a <= 0.0 && !n.is_power_of_two() => {
+                Err(format!("a must be positive unless n is a power of two, got n = {}", n))
+            }
+            Generator::RandomRegular { d } if d >= n || !(n * d).is_multiple_of(2) => {
+                Err(format!("no {}-regular graph on {} nodes (need d < n and n * d even)", d, n))
+            }
+            Generator::WattsStrogatz { k, .. } if !k.is_multiple_of(2) || k >= n => {
+                Err(format!("k must be even and less than n, got {}", k))
+            }
+            Generator::WattsStrogatz { beta, .. } if !(0.0..=1.0).contains(&beta) => {
+                Err(format!("beta must be in [0, 1], got {}", beta))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Calls `emit` once per undirected edge. Some generators may emit the
+    /// same edge twice; the graph builders merge duplicates.
+    pub fn for_each_edge(&self, n: usize, rng: &mut Rng, mut emit: impl FnMut(u32, u32)) {
+        match *self {
+            Generator::ErdosRenyi { p } => erdos_renyi(n, p, rng, &mut emit),
+            Generator::ErdosRenyiEdges { m } => erdos_renyi_edges(n, m, rng, &mut emit),
+            Generator::BarabasiAlbert { m } => barabasi_albert(n, m, rng, &mut emit),
+            Generator::Rmat { edge_factor, a, b, c } => rmat(n, edge_factor, [a, b, c], rng, &mut emit),
+            Generator::Grid2d => grid2d(n, &mut emit),
+            Generator::Grid3d => grid3d(n, &mut emit),
+            Generator::RandomRegular { d } => random_regular(n, d, rng, &mut emit),
+            Generator::WattsStrogatz { k, beta } => watts_strogatz(n, k, beta, rng, &mut emit),
+        }
+    }
+
+    pub fn edges(&self, n: usize, rng: &mut Rng) -> Vec<(u32, u32)> {
+        let mut edges = Vec::new();
+        self.for_each_edge(n, rng, |u, v| edges.push((u, v)));
+        edges
+    }
+}
+
+/// Random numbers for the edge from `u` to `v`, the same for the same seed
+/// whatever order the edges are visited in.
+pub fn edge_rng(seed: u64, u: u32, v: u32) -> Rng {
+    Rng::new(seed ^ ((u as u64) << 32 | v as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+/// Points every edge one way or the other by a coin flip per edge. The
+/// flips do not touch the generator's random numbers, so the directed
+/// graph has the same edges as the undirected one from the same seed.
+#[derive(Debug, Clone, Copy)]
+pub struct Orientation {
+    seed: u64,
+}
+
+impl Orientation {
+    pub fn new(seed: u64) -> Orientation {
+        // Kept apart from the weights drawn from the same seed.
+        Orientation {
+            seed: seed ^ 0x6469_7265_6374_6564,
+        }
+    }
+
+    pub fn orient(&self, u: u32, v: u32) -> (u32, u32) {
+        let (low, high) = (u.min(v), u.max(v));
+        if edge_rng(self.seed, low, high).chance(0.5) {
+            (low, high)
+        } else {
+            (high, low)
+        }
+    }
+}
+
+fn pairs(n: usize) -> u64 {
+    n as u64 * (n as u64).saturating_sub(1) / 2
+}
+
+/// Dense graphs draw once per pair. Sparse ones walk the pairs (v, w),
+/// w < v, in order and jump over the unconnected ones with geometrically
+/// distributed skips (Batagelj & Brandes), so the cost is proportional to
+/// the number of edges rather than n^2.
+fn erdos_renyi(n: usize, p: f64, rng: &mut Rng, emit: &mut impl FnMut(u32, u32)) {
+    if p <= 0.0 {
+        return;
+    }
+    if p >= 0.1 {
+        for v in 1..n {
+            for w in 0..v {
+                if rng.chance(p) {
+                    emit(w as u32, v as u32);
+                }
+            }
+        }
+        return;
+    }
+
+    // ln(1 - p) without rounding 1 - p to 1 for tiny p.
+    let log_q = (-p).ln_1p();
+    if log_q == 0.0 {
+        return;
+    }
+    let (mut v, mut w) = (1usize, -1i64);
+    while v < n {
+        let skip = ((1.0 - rng.next_f64()).ln() / log_q).floor();
+        // A skip past every pair left ends the graph, and would overflow.
+        if skip >= pairs(n) as f64 {
+            break;
+        }
+        w += 1 + skip as i64;
+        while w >= v as i64 && v < n {
+            w -= v as i64;
+            v += 1;
+        }
+        if v < n {
+            emit(w as u32, v as u32);
+        }
+    }
+}
+
+fn erdos_renyi_edges(n: usize, m: u64, rng: &mut Rng, emit: &mut impl FnMut(u32, u32)) {
+    let mut seen = HashSet::with_capacity(m as usize);
+    while (seen.len() as u64) < m {
+        let u = rng.below(n as u64) as u32;
+        let v = rng.below(n as u64) as u32;
+        if u != v && seen.insert((u.min(v), u.max(v))) {
+            emit(u.min(v), u.max(v));
+        }
+    }
+}
+
+/// Starts from a clique on the first `m + 1` nodes. Each later node picks
+/// `m` distinct targets with probability proportional to their degree,
+/// sampled from the list of all edge endpoints so far.
+fn barabasi_albert(n: usize, m: usize, rng: &mut Rng, emit: &mut impl FnMut(u32, u32)) {
+    let initial = (m + 1).min(n);
+    let mut endpoints: Vec<u32> = Vec::with_capacity(2 * n * m);
+    for v in 1..initial {
+        for u in 0..v {
+            emit(u as u32, v as u32);
+            endpoints.extend([u as u32, v as u32]);
+        }
+    }
+
+    let mut targets = Vec::with_capacity(m);
+    for v in initial..n {
+        targets.clear();
+        while targets.len() < m {
+            let t = endpoints[rng.below(endpoints.len() as u64) as usize];
+            if !targets.contains(&t) {
+                targets.push(t);
+            }
+        }
+        for &t in &targets {
+            emit(t, v as u32);
+            endpoints.extend([t, v as u32]);
+        }
+    }
+}
+
+fn rmat(n: usize, edge_factor: usize, [a, b, c]: [f64; 3], rng: &mut Rng, emit: &mut impl FnMut(u32, u32)) {
+    if n < 2 {
+        return;
+    }
+    let scale = usize::BITS - (n - 1).leading_zeros();
+
+    // Graph500 relabels the nodes so the hubs are not simply the low ids.
+    let mut labels: Vec<u32> = (0..n as u32).collect();
+    for i in (1..n).rev() {
+        labels.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+
+    let mut placed = 0;
+    while placed < edge_factor * n {
+        let (mut u, mut v) = (0usize, 0usize);
+        for _ in 0..scale {
+            let r = rng.next_f64();
+            let (row, column) = if r < a {
+                (0, 0)
+            } else if r < a + b {
+                (0, 1)
+            } else if r < a + b + c {
+                (1, 0)
+            } else {
+                (1, 1)
+            };
+            u = (u << 1) | row;
+            v = (v << 1) | column;
+        }
+        // Ids past n come from rounding n up to a power of two.
+        if u >= n || v >= n {
+            continue;
+        }
+        placed += 1;
+        if u != v {
+            emit(labels[u], labels[v]);
+        }
+    }
+}
+
+/// Smallest `s` with `s^power >= n`.
+fn side(n: usize, power: u32) -> usize {
+    let mut s = (n as f64).powf(1.0 / power as f64).round() as usize;
+    while s.pow(power) < n {
+        s += 1;
+    }
+    while s > 1 && (s - 1).pow(power) >= n {
+        s -= 1;
+    }
+    s.max(1)
+}
+
+fn grid2d(n: usize, emit: &mut impl FnMut(u32, u32)) {
+    let width = side(n, 2);
+    for i in 0..n {
+        if (i + 1) % width != 0 && i + 1 < n {
+            emit(i as u32, (i + 1) as u32);
+        }
+        if i + width < n {
+            emit(i as u32, (i + width) as u32);
+        }
+    }
+}
+
+fn grid3d(n: usize, emit: &mut impl FnMut(u32, u32)) {
+    let s = side(n, 3);
+    for i in 0..n {
+        let (x, y) = (i % s, (i / s) % s);
+        if x + 1 < s && i + 1 < n {
+            emit(i as u32, (i + 1) as u32);
+        }
+        if y + 1 < s && i + s < n {
+            emit(i as u32, (i + s) as u32);
+        }
+        if i + s * s < n {
+            emit(i as u32, (i + s * s) as u32);
+        }
+    }
+}
+
+/// Pairs up `d` stubs per node at random, refusing self-loops and repeated
+/// edges, and starts over if the last stubs cannot be paired.
+fn random_regular(n: usize, d: usize, rng: &mut Rng, emit: &mut impl FnMut(u32, u32)) {
+    'attempt: loop {
+        let mut stubs: Vec<u32> = (0..n as u32).flat_map(|v| std::iter::repeat_n(v, d)).collect();
+        let mut seen = HashSet::with_capacity(n * d / 2);
+        let mut edges = Vec::with_capacity(n * d / 2);
+        let mut failures = 0;
+        while !stubs.is_empty() {
+            let i = rng.below(stubs.len() as u64) as usize;
+            let j = rng.below(stubs.len() as u64) as usize;
+            let (u, v) = (stubs[i].min(stubs[j]), stubs[i].max(stubs[j]));
+            if i == j || u == v || seen.contains(&(u, v)) {
+                failures += 1;
+                if failures > 100 && !has_valid_pair(&stubs, &seen) {
+                    continue 'attempt;
+                }
+                continue;
+            }
+            failures = 0;
+            seen.insert((u, v));
+            edges.push((u, v));
+            stubs.swap_remove(i.max(j));
+            stubs.swap_remove(i.min(j));
+        }
+        for (u, v) in edges {
+            emit(u, v);
+        }
+        return;
+    }
+}
+
+fn has_valid_pair(stubs: &[u32], seen: &HashSet<(u32, u32)>) -> bool {
+    stubs.iter().enumerate().any(|(i, &a)| {
+        stubs[i + 1..]
+            .iter()
+            .any(|&b| a != b && !seen.contains(&(a.min(b), a.max(b))))
+    })
+}
+
+fn watts_strogatz(n: usize, k: usize, beta: f64, rng: &mut Rng, emit: &mut impl FnMut(u32, u32)) {
+    let key = |u: u32, v: u32| (u.min(v), u.max(v));
+    let mut edges = Vec::with_capacity(n * k / 2);
+    for j in 1..=k / 2 {
+        for u in 0..n {
+            edges.push((u as u32, ((u + j) % n) as u32));
+        }
+    }
+    let mut seen: HashSet<(u32, u32)> = edges.iter().map(|&(u, v)| key(u, v)).collect();
+    let mut degree = vec![k; n];
+
+    for edge in &mut edges {
+        if !rng.chance(beta) {
+            continue;
+        }
+        let (u, v) = *edge;
+        // A node already linked to everyone keeps its edge.
+        if degree[u as usize] == n - 1 {
+            continue;
+        }
+        let w = loop {
+            let w = rng.below(n as u64) as u32;
+            if w != u && !seen.contains(&key(u, w)) {
+                break w;
+            }
+        };
+        seen.remove(&key(u, v));
+        seen.insert(key(u, w));
+        degree[v as usize] -= 1;
+        degree[w as usize] += 1;
+        *edge = (u, w);
+    }
+
+    for (u, v) in edges {
+        emit(u, v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Csr, FromEdges, Graph};
+
+    const SEED: u64 = 42;
+
+    fn generate(spec: &str, n: usize, seed: u64) -> Vec<(u32, u32)> {
+        let generator: Generator = spec.parse().unwrap();
+        generator.check(n).unwrap();
+        generator.edges(n, &mut Rng::new(seed))
+    }
+
+    /// Checks the edges are valid and returns the number of distinct ones.
+    fn distinct_edges(spec: &str, n: usize, edges: &[(u32, u32)]) -> usize {
+        for &(u, v) in edges {
+            assert!(u != v, "{spec}: self-loop on {u}");
+            assert!((u as usize) < n && (v as usize) < n, "{spec}: edge ({u}, {v}) past {n} nodes");
+        }
+        let graph = Csr::from_edges(n, edges);
+        for u in 0..n {
+            for v in graph.neighbors(u) {
+                assert!(graph.neighbors(v).any(|w| w == u), "{spec}: ({u}, {v}) has no reverse");
+            }
+        }
+        graph.edge_count() / 2
+    }
+
+    #[test]
+    fn edge_counts() {
+        for (spec, n, expected) in [
+            ("er:p=1", 12, 66),
+            ("er:p=0", 12, 0),
+            ("er:m=50", 20, 50),
+            // Clique on m + 1 nodes, then m edges per node.
+            ("ba:m=3", 30, 6 + 26 * 3),
+            // 4 full rows of 5: 4 * 4 across and 15 down.
+            ("grid2d", 20, 31),
+            ("grid3d", 27, 54),
+            ("regular:d=3", 10, 15),
+            ("ws:k=4,beta=0.3", 20, 40),
+        ] {
+            let edges = generate(spec, n, SEED);
+            assert_eq!(distinct_edges(spec, n, &edges), expected, "{spec}");
+        }
+    }
+
+    #[test]
+    fn regular_graphs_are_regular() {
+        let graph = Csr::from_edges(50, &generate("regular:d=4", 50, SEED));
+        assert!((0..50).all(|u| graph.degree(u) == 4));
+    }
+
+    #[test]
+    fn sparse_erdos_renyi_has_about_p_of_the_pairs() {
+        // Below p = 0.1 the pairs are skipped over geometrically.
+        let (n, p) = (2000, 0.05);
+        let edges = generate(&format!("er:p={p}"), n, SEED);
+        assert!(edges.windows(2).all(|e| (e[0].1, e[0].0) < (e[1].1, e[1].0)), "pairs not visited in order");
+        let expected = pairs(n) as f64 * p;
+        let count = distinct_edges("er", n, &edges) as f64;
+        assert!((count - expected).abs() < 0.05 * expected, "{count} edges, expected about {expected}");
+    }
+
+    #[test]
+    fn tiny_p_gives_no_edges() {
+        for p in ["1e-17", "1e-300", "5e-324"] {
+            assert!(generate(&format!("er:p={p}"), 1000, SEED).is_empty(), "p = {p}");
+        }
+    }
+
+    #[test]
+    fn rmat_places_at_most_its_edges() {
+        let edges = generate("rmat:edge_factor=4", 64, SEED);
+        let distinct = distinct_edges("rmat", 64, &edges);
+        assert!(distinct > 0 && distinct <= 4 * 64, "{distinct} edges");
+        // Not a power of two: draws past n are repeated.
+        let edges = generate("rmat:edge_factor=4", 100, SEED);
+        assert!(distinct_edges("rmat", 100, &edges) > 0);
+    }
+
+    #[test]
+    fn same_seed_same_graph() {
+        for spec in ["er:p=0.5", "er:p=0.01", "er:m=40", "ba:m=2", "rmat", "regular:d=4", "ws:k=4,beta=0.5"] {
+            let n = 64;
+            let edges = generate(spec, n, SEED);
+            distinct_edges(spec, n, &edges);
+            assert_eq!(generate(spec, n, SEED), edges, "{spec}");
+            assert_ne!(generate(spec, n, SEED + 1), edges, "{spec}");
+        }
+    }
+
+    #[test]
+    fn rejects_impossible_parameters() {
+        for (spec, n) in [
+            ("er:p=1.5", 10),
+            ("er:m=46", 10),
+            ("ba:m=0", 10),
+            ("rmat:a=0.5,b=0.3,c=0.3", 16),
+            ("rmat:a=0,b=0.5,c=0.5", 10),
+            ("regular:d=3", 9),
+            ("ws:k=3", 10),
+            ("ws:beta=2", 10),
+        ] {
+            assert!(spec.parse::<Generator>().unwrap().check(n).is_err(), "{spec}");
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Graph representations shared by the BFS and DFS benchmarks.
+//!
+//! Every representation implements [`Graph`], so a traversal written once
+//! against the trait can be measured on each memory layout. Neighbours are
+//! always produced in ascending order, which keeps the visit order of a
+//! traversal identical across representations.
+//!
+//! Graphs are either drawn from a [`Generator`] or read from a file with
+//! [`load::load`]. They are undirected unless built from arcs, and carry no
+//! weights themselves: [`Weights`] holds those alongside, in neighbour
+//! order.
+
+use std::fmt;
+use std::str::FromStr;
+
+use harness::input::Rng;
+
+mod bit_matrix;
+mod csr;
+pub mod generate;
+mod list;
+pub mod load;
+mod matrix;
+pub mod options;
+pub mod traversal;
+pub mod weights;
+
+pub use bit_matrix::{BitMatrix, BitNeighbors};
+pub use csr::Csr;
+pub use generate::{Generator, Orientation};
+pub use list::AdjList;
+pub use load::EdgeList;
+pub use matrix::{AdjMatrix, MatrixNeighbors};
+pub use options::GraphOptions;
+pub use traversal::Traversal;
+pub use weights::{AnyWeights, Weight, WeightKind, WeightSpec, Weights};
+
+/// An unweighted graph over the nodes `0..node_count()`.
+pub trait Graph {
+    type Neighbors<'a>: Iterator<Item = usize>
+    where
+        Self: 'a;
+
+    fn node_count(&self) -> usize;
+
+    /// Number of stored adjacency entries; an undirected edge counts twice,
+    /// an arc once.
+    fn edge_count(&self) -> usize;
+
+    /// Neighbours of `node` in ascending order, all below `node_count()`.
+    fn neighbors(&self, node: usize) -> Self::Neighbors<'_>;
+
+    /// Number of neighbours of `node`. Counts them by default; the sparse
+    /// layouts know it up front.
+    fn degree(&self, node: usize) -> usize {
+        self.neighbors(node).count()
+    }
+
+    /// Heap memory held by the representation, in bytes.
+    fn memory_bytes(&self) -> usize;
+}
+
+/// Builds a graph from undirected edges or directed arcs. Duplicates are
+/// merged.
+pub trait FromEdges: Sized {
+    fn from_edges(n: usize, edges: &[(u32, u32)]) -> Self;
+
+    /// Every `(u, v)` makes `v` a neighbour of `u` only.
+    fn from_arcs(n: usize, arcs: &[(u32, u32)]) -> Self;
+}
+
+/// Iterator over a slice of node ids.
+pub struct NodeIds<'a>(std::slice::Iter<'a, u32>);
+
+impl Iterator for NodeIds<'_> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        self.0.next().map(|&id| id as usize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Selectable representation, e.g. from `--repr`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Representation {
+    /// `Vec<Vec<i32>>` of 0/1 entries, the layout the C programs use.
+    #[default]
+    Matrix,
+    Csr,
+    List,
+    BitMatrix,
+}
+
+impl fmt::Display for Representation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Representation::Matrix => write!(f, "matrix"),
+            Representation::Csr => write!(f, "csr"),
+            Representation::List => write!(f, "list"),
+            Representation::BitMatrix => write!(f, "bitmatrix"),
+        }
+    }
+}
+
+impl FromStr for Representation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "matrix" => Ok(Representation::Matrix),
+            "csr" => Ok(Representation::Csr),
+            "list" => Ok(Representation::List),
+            "bitmatrix" => Ok(Representation::BitMatrix),
+            _ => Err(format!("unknown representation: {s} (expected matrix, csr, list or bitmatrix)")),
+        }
+    }
+}
+
+/// Evaluates `$body` with `$g` bound to the concrete graph inside an
+/// [`AnyGraph`], so generic code is monomorphised for each representation:
+///
+/// ```ignore
+/// let reached = with_graph!(&graph, g => bfs(g, 0));
+/// ```
+#[macro_export]
+macro_rules! with_graph {
+    ($graph:expr, $g:ident => $body:expr) => {
+        match $graph {
+            $crate::AnyGraph::Matrix($g) => $body,
+            $crate::AnyGraph::Csr($g) => $body,
+            $crate::AnyGraph::List($g) => $body,
+            $crate::AnyGraph::BitMatrix($g) => $body,
+        }
+    };
+}
+
+/// A graph in any of the representations, so a benchmark can pick one at
+/// run time.
+pub enum AnyGraph {
+    Matrix(AdjMatrix),
+    Csr(Csr),
+    List(AdjList),
+    BitMatrix(BitMatrix),
+}
+
+impl AnyGraph {
+    pub fn from_edges(representation: Representation, n: usize, edges: &[(u32, u32)]) -> AnyGraph {
+        match representation {
+            Representation::Matrix => AnyGraph::Matrix(AdjMatrix::from_edges(n, edges)),
+            Representation::Csr => AnyGraph::Csr(Csr::from_edges(n, edges)),
+            Representation::List => AnyGraph::List(AdjList::from_edges(n, edges)),
+            Representation::BitMatrix => AnyGraph::BitMatrix(BitMatrix::from_edges(n, edges)),
+        }
+    }
+
+    pub fn from_arcs(representation: Representation, n: usize, arcs: &[(u32, u32)]) -> AnyGraph {
+        match representation {
+            Representation::Matrix => AnyGraph::Matrix(AdjMatrix::from_arcs(n, arcs)),
+            Representation::Csr => AnyGraph::Csr(Csr::from_arcs(n, arcs)),
+            Representation::List => AnyGraph::List(AdjList::from_arcs(n, arcs)),
+            Representation::BitMatrix => AnyGraph::BitMatrix(BitMatrix::from_arcs(n, arcs)),
+        }
+    }
+
+    /// Builds the graph straight from the generator, directed if given an
+    /// orientation. The dense layouts are filled edge by edge; the sparse
+    /// ones go through an edge list.
+    pub fn generate(
+        representation: Representation,
+        generator: &Generator,
+        n: usize,
+        rng: &mut Rng,
+        orientation: Option<Orientation>,
+    ) -> AnyGraph {
+        match (representation, orientation) {
+            (Representation::Matrix, _) => {
+                let mut matrix = vec![vec![0; n]; n];
+                generator.for_each_edge(n, rng, |u, v| match orientation {
+                    Some(orientation) => {
+                        let (u, v) = orientation.orient(u, v);
+                        matrix[u as usize][v as usize] = 1;
+                    }
+                    None => {
+                        matrix[u as usize][v as usize] = 1;
+                        matrix[v as usize][u as usize] = 1;
+                    }
+                });
+                AnyGraph::Matrix(matrix)
+            }
+            (Representation::BitMatrix, _) => {
+                let mut matrix = BitMatrix::new(n);
+                generator.for_each_edge(n, rng, |u, v| match orientation {
+                    Some(orientation) => {
+                        let (u, v) = orientation.orient(u, v);
+                        matrix.set(u as usize, v as usize);
+                    }
+                    None => {
+                        matrix.set(u as usize, v as usize);
+                        matrix.set(v as usize, u as usize);
+                    }
+                });
+                AnyGraph::BitMatrix(matrix)
+            }
+            (representation, Some(orientation)) => {
+                let mut arcs = generator.edges(n, rng);
+                for arc in &mut arcs {
+                    *arc = orientation.orient(arc.0, arc.1);
+                }
+                AnyGraph::from_arcs(representation, n, &arcs)
+            }
+            (representation, None) => AnyGraph::from_edges(representation, n, &generator.edges(n, rng)),
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        match self {
+            AnyGraph::Matrix(g) => g.node_count(),
+            AnyGraph::Csr(g) => g.node_count(),
+            AnyGraph::List(g) => g.node_count(),
+            AnyGraph::BitMatrix(g) => g.node_count(),
+        }
+    }
+
+    pub fn edge_count(&self) -> usize {
+        match self {
+            AnyGraph::Matrix(g) => g.edge_count(),
+            AnyGraph::Csr(g) => g.edge_count(),
+            AnyGraph::List(g) => g.edge_count(),
+            AnyGraph::BitMatrix(g) => g.edge_count(),
+        }
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        match self {
+            AnyGraph::Matrix(g) => g.memory_bytes(),
+            AnyGraph::Csr(g) => g.memory_bytes(),
+            AnyGraph::List(g) => g.memory_bytes(),
+            AnyGraph::BitMatrix(g) => g.memory_bytes(),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{FromEdges, Graph, NodeIds};
+
+/// One sorted `Vec` of neighbours per node.
+pub struct AdjList {
+    pub adjacency: Vec<Vec<u32>>,
+}
+
+impl Graph for AdjList {
+    type Neighbors<'a> = NodeIds<'a>;
+
+    fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum()
+    }
+
+    #[inline]
+    fn neighbors(&self, node: usize) -> NodeIds<'_> {
+        NodeIds(self.adjacency[node].iter())
+    }
+
+    #[inline]
+    fn degree(&self, node: usize) -> usize {
+        self.adjacency[node].len()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.adjacency.len() * std::mem::size_of::<Vec<u32>>()
+            + self.adjacency.iter().map(|n| n.capacity() * 4).sum::<usize>()
+    }
+}
+
+impl AdjList {
+    fn build(n: usize, edges: &[(u32, u32)], directed: bool) -> Self {
+        let mut adjacency = vec![Vec::new(); n];
+        for &(u, v) in edges {
+            adjacency[u as usize].push(v);
+            if !directed && u != v {
+                adjacency[v as usize].push(u);
+            }
+        }
+        for neighbors in &mut adjacency {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors.shrink_to_fit();
+        }
+        AdjList { adjacency }
+    }
+}
+
+impl FromEdges for AdjList {
+    fn from_edges(n: usize, edges: &[(u32, u32)]) -> Self {
+        AdjList::build(n, edges, false)
+    }
+
+    fn from_arcs(n: usize, arcs: &[(u32, u32)]) -> Self {
+        AdjList::build(n, arcs, true)
+    }
+}
@@ -0,0 +1,518 @@
+//! Loading real-world graphs from disk.
+//!
+//! Three text formats are understood, told apart by their first lines:
+//!
+//! - SNAP edge lists: one `u v` pair per line, `#` comments. Node ids may
+//!   be sparse and are remapped to `0..n` in ascending order. The pairs are
+//!   arcs; anything after them is ignored.
+//! - Matrix Market (`.mtx`): the `%%MatrixMarket matrix coordinate <field>
+//!   <symmetry>` banner, `%` comments, a `rows cols entries` line, then
+//!   1-based `i j [value]` entries. A `general` matrix holds arcs, the
+//!   symmetric kinds edges; `integer` and `real` values are weights.
+//! - DIMACS: `c` comments, a `p <kind> n m` line, then 1-based `a u v [w]`
+//!   (shortest path) arcs or `e u v` (clique/colouring) edges.
+//!
+//! Whether the graph is taken as directed, and whether its weights are
+//! used, is up to the benchmark. Weights are only checked then, so a file
+//! with negative values still loads as an unweighted graph.
+//!
+//! Parsing a large text file takes seconds, so the result is cached next to
+//! it as `<file>.rcbgraph` and reused while the file's length and
+//! modification time stay the same:
+//!
+//! ```text
+//! magic        8 bytes  "RCBGRAPH"
+//! version      u32      2
+//! source_len   u64      length of the text file the cache was built from
+//! source_time  u64      its modification time, in ns since the Unix epoch
+//! nodes        u64
+//! edges        u64
+//! directed     u32      0 or 1
+//! weights      u32      0 for none, 1 for integers, 2 for floats
+//! then edges x (u32, u32)
+//! then edges x i64 or f64 if weighted
+//! ```
+//!
+//! All integers are little-endian.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const MAGIC: &[u8; 8] = b"RCBGRAPH";
+const VERSION: u32 = 2;
+const HEADER_LEN: usize = 8 + 4 + 8 * 4 + 4 * 2;
+
+/// Edges over the nodes `0..nodes`, with their weights if the file has any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeList {
+    pub nodes: usize,
+    pub edges: Vec<(u32, u32)>,
+    /// Whether the file lists arcs rather than undirected edges.
+    pub directed: bool,
+    pub weights: Option<EdgeWeights>,
+}
+
+/// One weight per edge of an [`EdgeList`], as written in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeWeights {
+    Int(Vec<i64>),
+    Float(Vec<f64>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Snap,
+    MatrixMarket,
+    Dimacs,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Snap => write!(f, "SNAP edge list"),
+            Format::MatrixMarket => write!(f, "Matrix Market file"),
+            Format::Dimacs => write!(f, "DIMACS file"),
+        }
+    }
+}
+
+/// Where the graph came from, for reporting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    Parsed(Format),
+    Cache,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Origin::Parsed(format) => write!(f, "{}", format),
+            Origin::Cache => write!(f, "cache"),
+        }
+    }
+}
+
+/// Loads `path`, from its cache when that is still current. A cache that
+/// cannot be written (e.g. a read-only directory) is silently skipped.
+pub fn load(path: &Path) -> Result<(EdgeList, Origin), String> {
+    let stamp = source_stamp(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let cache = cache_path(path);
+    if let Some(graph) = read_cache(&cache, stamp) {
+        return Ok((graph, Origin::Cache));
+    }
+
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let format = detect(&text);
+    let graph = match format {
+        Format::Snap => parse_snap(&text),
+        Format::MatrixMarket => parse_matrix_market(&text),
+        Format::Dimacs => parse_dimacs(&text),
+    }
+    .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let _ = write_cache(&cache, stamp, &graph);
+    Ok((graph, Origin::Parsed(format)))
+}
+
+pub fn cache_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".rcbgraph");
+    PathBuf::from(name)
+}
+
+/// Guesses the format from the first line that is not blank.
+pub fn detect(text: &str) -> Format {
+    let first = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    if first.starts_with("%%MatrixMarket") {
+        Format::MatrixMarket
+    } else if first == "c" || first.starts_with("c ") || first.starts_with("p ") {
+        Format::Dimacs
+    } else {
+        Format::Snap
+    }
+}
+
+/// Data lines with their 1-based line numbers, skipping blanks and lines
+/// starting with `comment`.
+fn data_lines<'a>(text: &'a str, comment: &'a [char]) -> impl Iterator<Item = (usize, &'a str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(move |(_, line)| !line.is_empty() && !line.starts_with(comment))
+}
+
+fn field<T: std::str::FromStr>(fields: &mut std::str::SplitAsciiWhitespace, line: usize) -> Result<T, String> {
+    let raw = fields.next().ok_or_else(|| format!("line {}: missing field", line))?;
+    raw.parse().map_err(|_| format!("line {}: invalid number {:?}", line, raw))
+}
+
+fn check_nodes(nodes: u64) -> Result<usize, String> {
+    if nodes > u32::MAX as u64 {
+        return Err(format!("{} nodes do not fit 32-bit node ids", nodes));
+    }
+    Ok(nodes as usize)
+}
+
+pub fn parse_snap(text: &str) -> Result<EdgeList, String> {
+    let mut raw = Vec::new();
+    for (line, data) in data_lines(text, &['#', '%']) {
+        let mut fields = data.split_ascii_whitespace();
+        let u: u64 = field(&mut fields, line)?;
+        let v: u64 = field(&mut fields, line)?;
+        raw.push((u, v));
+    }
+
+    let mut ids: Vec<u64> = raw.iter().flat_map(|&(u, v)| [u, v]).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let nodes = check_nodes(ids.len() as u64)?;
+
+    // Already 0..n: nothing to remap.
+    let edges = if ids.last().is_none_or(|&max| max as usize + 1 == nodes) {
+        raw.iter().map(|&(u, v)| (u as u32, v as u32)).collect()
+    } else {
+        let remap = |id| ids.binary_search(&id).unwrap() as u32;
+        raw.iter().map(|&(u, v)| (remap(u), remap(v))).collect()
+    };
+    Ok(EdgeList {
+        nodes,
+        edges,
+        directed: true,
+        weights: None,
+    })
+}
+
+pub fn parse_matrix_market(text: &str) -> Result<EdgeList, String> {
+    let banner = text.lines().next().unwrap_or("").to_ascii_lowercase();
+    let mut words = banner.split_ascii_whitespace().skip(1);
+    if words.next() != Some("matrix") || words.next() != Some("coordinate") {
+        return Err("only Matrix Market coordinate matrices are supported".to_string());
+    }
+    let field_kind = words.next().unwrap_or("pattern");
+    let directed = words.next().unwrap_or("general") == "general";
+
+    let mut lines = data_lines(text, &['%']);
+    let (line, size) = lines.next().ok_or("missing size line")?;
+    let mut fields = size.split_ascii_whitespace();
+    let rows: u64 = field(&mut fields, line)?;
+    let cols: u64 = field(&mut fields, line)?;
+    let entries: usize = field(&mut fields, line)?;
+    let nodes = check_nodes(rows.max(cols))?;
+
+    // Every entry takes a few bytes, so a bogus count cannot reserve more
+    // than the text is long.
+    let capacity = entries.min(text.len());
+    let mut edges = Vec::with_capacity(capacity);
+    let mut weights = match field_kind {
+        "integer" => Some(EdgeWeights::Int(Vec::with_capacity(capacity))),
+        "real" | "double" => Some(EdgeWeights::Float(Vec::with_capacity(capacity))),
+        _ => None,
+    };
+    for (line, data) in lines {
+        let mut fields = data.split_ascii_whitespace();
+        let i: u64 = field(&mut fields, line)?;
+        let j: u64 = field(&mut fields, line)?;
+        if i == 0 || i > rows || j == 0 || j > cols {
+            return Err(format!("line {}: entry ({}, {}) outside the {}x{} matrix", line, i, j, rows, cols));
+        }
+        edges.push((i as u32 - 1, j as u32 - 1));
+        match &mut weights {
+            Some(EdgeWeights::Int(values)) => values.push(field(&mut fields, line)?),
+            Some(EdgeWeights::Float(values)) => values.push(field(&mut fields, line)?),
+            None => {}
+        }
+    }
+    if edges.len() != entries {
+        return Err(format!("expected {} entries, found {}", entries, edges.len()));
+    }
+    Ok(EdgeList {
+        nodes,
+        edges,
+        directed,
+        weights,
+    })
+}
+
+/// Arcs if there is any `a` line, weighted if every `a` line has a weight.
+pub fn parse_dimacs(text: &str) -> Result<EdgeList, String> {
+    let mut nodes = None;
+    let mut edges = Vec::new();
+    let mut directed = false;
+    let mut weights = Some(Vec::new());
+    for (line, data) in data_lines(text, &['c']) {
+        let mut fields = data.split_ascii_whitespace();
+        match fields.next() {
+            Some("p") => {
+                let _kind = fields.next();
+                let n: u64 = field(&mut fields, line)?;
+                let m: usize = field(&mut fields, line)?;
+                nodes = Some(check_nodes(n)?);
+                edges.reserve(m.min(text.len()));
+            }
+            Some(kind @ ("a" | "e")) => {
+                let n = nodes.ok_or_else(|| format!("line {}: edge before the \"p\" line", line))?;
+                let u: u64 = field(&mut fields, line)?;
+                let v: u64 = field(&mut fields, line)?;
+                if u == 0 || u > n as u64 || v == 0 || v > n as u64 {
+                    return Err(format!("line {}: edge ({}, {}) outside nodes 1..={}", line, u, v, n));
+                }
+                edges.push((u as u32 - 1, v as u32 - 1));
+                directed |= kind == "a";
+                match fields.next() {
+                    Some(raw) if kind == "a" => {
+                        if let Some(weights) = &mut weights {
+                            weights.push(raw.parse().map_err(|_| format!("line {}: invalid number {:?}", line, raw))?);
+                        }
+                    }
+                    _ => weights = None,
+                }
+            }
+            // Node descriptors and the like carry nothing we use.
+            _ => {}
+        }
+    }
+    let nodes = nodes.ok_or("missing \"p\" line")?;
+    Ok(EdgeList {
+        nodes,
+        directed,
+        weights: weights.filter(|_| !edges.is_empty()).map(EdgeWeights::Int),
+        edges,
+    })
+}
+
+/// Length and modification time of the source file.
+fn source_stamp(path: &Path) -> io::Result<(u64, u64)> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    Ok((metadata.len(), modified))
+}
+
+fn read_cache(path: &Path, stamp: (u64, u64)) -> Option<EdgeList> {
+    let bytes = fs::read(path).ok()?;
+    let header = bytes.get(..HEADER_LEN)?;
+    let u64_at = |at: usize| u64::from_le_bytes(header[at..at + 8].try_into().unwrap());
+    let u32_at = |b: &[u8]| u32::from_le_bytes(b.try_into().unwrap());
+    if &header[..8] != MAGIC || header[8..12] != VERSION.to_le_bytes() || (u64_at(12), u64_at(20)) != stamp {
+        return None;
+    }
+    let nodes = u64_at(28) as usize;
+    let count = u64_at(36) as usize;
+    let directed = u32_at(&header[44..48]) == 1;
+    let weight_kind = u32_at(&header[48..52]);
+    let weight_len = if weight_kind == 0 { 0 } else { count * 8 };
+    let data = &bytes[HEADER_LEN..];
+    if data.len() != count * 8 + weight_len {
+        return None;
+    }
+    let (data, weight_data) = data.split_at(count * 8);
+    let edges = data.chunks_exact(8).map(|b| (u32_at(&b[..4]), u32_at(&b[4..]))).collect();
+    let weights = weight_data.chunks_exact(8).map(|b| b.try_into().unwrap());
+    let weights = match weight_kind {
+        0 => None,
+        1 => Some(EdgeWeights::Int(weights.map(i64::from_le_bytes).collect())),
+        2 => Some(EdgeWeights::Float(weights.map(f64::from_le_bytes).collect())),
+        _ => return None,
+    };
+    Some(EdgeList {
+        nodes,
+        edges,
+        directed,
+        weights,
+    })
+}
+
+fn write_cache(path: &Path, stamp: (u64, u64), graph: &EdgeList) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    out.write_all(MAGIC)?;
+    out.write_all(&VERSION.to_le_bytes())?;
+    out.write_all(&stamp.0.to_le_bytes())?;
+    out.write_all(&stamp.1.to_le_bytes())?;
+    out.write_all(&(graph.nodes as u64).to_le_bytes())?;
+    out.write_all(&(graph.edges.len() as u64).to_le_bytes())?;
+    out.write_all(&(graph.directed as u32).to_le_bytes())?;
+    let weight_kind: u32 = match graph.weights {
+        None => 0,
+        Some(EdgeWeights::Int(_)) => 1,
+        Some(EdgeWeights::Float(_)) => 2,
+    };
+    out.write_all(&weight_kind.to_le_bytes())?;
+    for &(u, v) in &graph.edges {
+        out.write_all(&u.to_le_bytes())?;
+        out.write_all(&v.to_le_bytes())?;
+    }
+    match &graph.weights {
+        Some(EdgeWeights::Int(values)) => values.iter().try_for_each(|w| out.write_all(&w.to_le_bytes()))?,
+        Some(EdgeWeights::Float(values)) => values.iter().try_for_each(|w| out.write_all(&w.to_le_bytes()))?,
+        None => {}
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unweighted(nodes: usize, edges: &[(u32, u32)], directed: bool) -> EdgeList {
+        EdgeList {
+            nodes,
+            edges: edges.to_vec(),
+            directed,
+            weights: None,
+        }
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(detect("\n%%MatrixMarket matrix coordinate pattern general\n"), Format::MatrixMarket);
+        assert_eq!(detect("c comment\np sp 2 1\n"), Format::Dimacs);
+        assert_eq!(detect("p edge 2 1\n"), Format::Dimacs);
+        assert_eq!(detect("# Nodes: 2\n0 1\n"), Format::Snap);
+        assert_eq!(detect(""), Format::Snap);
+    }
+
+    #[test]
+    fn snap_remaps_sparse_ids_in_order() {
+        let text = "# Directed graph\n# FromNodeId\tToNodeId\n30 10\n\n10 7000000000\n% other comment\n30 30 extra\n";
+        assert_eq!(parse_snap(text).unwrap(), unweighted(3, &[(1, 0), (0, 2), (1, 1)], true));
+    }
+
+    #[test]
+    fn snap_keeps_dense_ids() {
+        assert_eq!(parse_snap("0 2\n2 1\n").unwrap(), unweighted(3, &[(0, 2), (2, 1)], true));
+        assert_eq!(parse_snap("# empty\n").unwrap(), unweighted(0, &[], true));
+    }
+
+    #[test]
+    fn matrix_market_symmetric_pattern() {
+        let text = "%%MatrixMarket matrix coordinate pattern symmetric\n% comment\n4 4 3\n2 1\n3 1\n4 4\n";
+        assert_eq!(parse_matrix_market(text).unwrap(), unweighted(4, &[(1, 0), (2, 0), (3, 3)], false));
+    }
+
+    #[test]
+    fn matrix_market_general_weights() {
+        let text = "%%MatrixMarket matrix coordinate integer general\n2 3 2\n1 3 -5\n2 1 7\n";
+        let graph = parse_matrix_market(text).unwrap();
+        assert_eq!((graph.nodes, graph.directed), (3, true));
+        assert_eq!(graph.edges, [(0, 2), (1, 0)]);
+        assert_eq!(graph.weights, Some(EdgeWeights::Int(vec![-5, 7])));
+
+        let text = "%%MatrixMarket matrix coordinate real symmetric\n2 2 1\n2 1 0.5\n";
+        let graph = parse_matrix_market(text).unwrap();
+        assert!(!graph.directed);
+        assert_eq!(graph.weights, Some(EdgeWeights::Float(vec![0.5])));
+    }
+
+    #[test]
+    fn dimacs_arcs_with_weights() {
+        let text = "c shortest path\np sp 3 2\nc arcs\na 1 2 4\na 3 1 -2\n";
+        let graph = parse_dimacs(text).unwrap();
+        assert_eq!((graph.nodes, graph.directed), (3, true));
+        assert_eq!(graph.edges, [(0, 1), (2, 0)]);
+        assert_eq!(graph.weights, Some(EdgeWeights::Int(vec![4, -2])));
+    }
+
+    #[test]
+    fn dimacs_edges() {
+        let text = "c colouring\np edge 4 2\nn 1 5\ne 1 2\ne 4 3\n";
+        assert_eq!(parse_dimacs(text).unwrap(), unweighted(4, &[(0, 1), (3, 2)], false));
+        // One unweighted arc makes the whole graph unweighted.
+        let graph = parse_dimacs("p sp 2 2\na 1 2 3\na 2 1\n").unwrap();
+        assert_eq!((graph.directed, graph.weights), (true, None));
+    }
+
+    #[test]
+    fn malformed_lines_are_errors() {
+        for text in ["0 1\n1\n", "0 x\n", "-1 2\n", "0 99999999999999999999\n"] {
+            assert!(parse_snap(text).is_err(), "{text:?}");
+        }
+        for text in [
+            "%%MatrixMarket matrix array real general\n2 2\n1\n2\n3\n4\n",
+            "%%MatrixMarket matrix coordinate pattern general\n",
+            "%%MatrixMarket matrix coordinate pattern general\n2 2\n",
+            "%%MatrixMarket matrix coordinate pattern general\n2 2 1\n0 1\n",
+            "%%MatrixMarket matrix coordinate pattern general\n2 2 1\n3 1\n",
+            "%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 2\n",
+            "%%MatrixMarket matrix coordinate pattern general\n2 2 1\n1 2\n2 1\n",
+            "%%MatrixMarket matrix coordinate integer general\n2 2 1\n1 2\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 2 x\n",
+            "%%MatrixMarket matrix coordinate pattern general\n4294967296 1 0\n",
+            "%%MatrixMarket matrix coordinate pattern general\n2 2 18446744073709551615\n1 2\n",
+        ] {
+            assert!(parse_matrix_market(text).is_err(), "{text:?}");
+        }
+        for text in [
+            "c no problem line\n",
+            "a 1 2 3\np sp 2 1\n",
+            "p sp 2\n",
+            "p sp 2 18446744073709551615\na 1 2 x\n",
+            "p sp 2 1\na 0 1\n",
+            "p sp 2 1\ne 1 3\n",
+            "p sp 2 1\na 1\n",
+            "p sp 2 1\na 1 2 x\n",
+        ] {
+            assert!(parse_dimacs(text).is_err(), "{text:?}");
+        }
+    }
+
+    /// A fresh file in the temporary directory, removed with its cache on drop.
+    struct TempGraph(PathBuf);
+
+    impl TempGraph {
+        fn new(name: &str, text: &str) -> TempGraph {
+            let path = std::env::temp_dir().join(format!("load-{}-{}.txt", std::process::id(), name));
+            fs::write(&path, text).unwrap();
+            TempGraph(path)
+        }
+    }
+
+    impl Drop for TempGraph {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+            let _ = fs::remove_file(cache_path(&self.0));
+        }
+    }
+
+    #[test]
+    fn cache_round_trip() {
+        let file = TempGraph::new("round-trip", "p sp 3 2\na 1 2 4\na 3 1 -2\n");
+        let (parsed, origin) = load(&file.0).unwrap();
+        assert_eq!(origin, Origin::Parsed(Format::Dimacs));
+        let (cached, origin) = load(&file.0).unwrap();
+        assert_eq!(origin, Origin::Cache);
+        assert_eq!(cached, parsed);
+
+        let text = "%%MatrixMarket matrix coordinate real symmetric\n3 3 2\n2 1 0.25\n3 3 -1.5\n";
+        let file = TempGraph::new("round-trip-float", text);
+        let (parsed, _) = load(&file.0).unwrap();
+        assert_eq!(load(&file.0).unwrap(), (parsed, Origin::Cache));
+    }
+
+    #[test]
+    fn stale_cache_is_reparsed() {
+        let file = TempGraph::new("stale", "0 1\n");
+        load(&file.0).unwrap();
+        fs::write(&file.0, "0 1\n1 2\n").unwrap();
+        let (graph, origin) = load(&file.0).unwrap();
+        assert_eq!(origin, Origin::Parsed(Format::Snap));
+        assert_eq!(graph, unweighted(3, &[(0, 1), (1, 2)], true));
+    }
+
+    #[test]
+    fn damaged_cache_is_reparsed() {
+        let file = TempGraph::new("damaged", "0 1\n1 2\n");
+        let (parsed, _) = load(&file.0).unwrap();
+        let cache = cache_path(&file.0);
+        let bytes = fs::read(&cache).unwrap();
+        for damaged in [&bytes[..bytes.len() - 1], &bytes[..HEADER_LEN - 1], &[b"RCBGRAPX", &bytes[8..]].concat()] {
+            fs::write(&cache, damaged).unwrap();
+            assert_eq!(load(&file.0).unwrap(), (parsed.clone(), Origin::Parsed(Format::Snap)));
+        }
+    }
+}
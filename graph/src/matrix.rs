@@ -0,0 +1,59 @@
+use std::iter::Enumerate;
+use std::slice;
+
+use crate::{FromEdges, Graph};
+
+/// Dense n x n matrix of 0/1 entries, one allocation per row. This is the
+/// layout the C programs take as `int**`.
+pub type AdjMatrix = Vec<Vec<i32>>;
+
+pub struct MatrixNeighbors<'a>(Enumerate<slice::Iter<'a, i32>>);
+
+impl Iterator for MatrixNeighbors<'_> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        self.0.by_ref().find(|&(_, &entry)| entry == 1).map(|(i, _)| i)
+    }
+}
+
+impl Graph for AdjMatrix {
+    type Neighbors<'a> = MatrixNeighbors<'a>;
+
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.iter().map(|row| row.iter().filter(|&&entry| entry == 1).count()).sum()
+    }
+
+    #[inline]
+    fn neighbors(&self, node: usize) -> MatrixNeighbors<'_> {
+        MatrixNeighbors(self[node].iter().enumerate())
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.len() * std::mem::size_of::<Vec<i32>>() + self.iter().map(|row| row.capacity() * 4).sum::<usize>()
+    }
+}
+
+impl FromEdges for AdjMatrix {
+    fn from_edges(n: usize, edges: &[(u32, u32)]) -> Self {
+        let mut matrix = vec![vec![0; n]; n];
+        for &(u, v) in edges {
+            matrix[u as usize][v as usize] = 1;
+            matrix[v as usize][u as usize] = 1;
+        }
+        matrix
+    }
+
+    fn from_arcs(n: usize, arcs: &[(u32, u32)]) -> Self {
+        let mut matrix = vec![vec![0; n]; n];
+        for &(u, v) in arcs {
+            matrix[u as usize][v as usize] = 1;
+        }
+        matrix
+    }
+}
@@ -0,0 +1,213 @@
+//! The command-line options every graph benchmark shares: the
+//! representation and where the graph comes from. Benchmarks that can use
+//! directed or weighted graphs also accept `--directed` and `--weights`.
+
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::Instant;
+
+use harness::input::Rng;
+use harness::Args;
+
+use crate::load::EdgeWeights;
+use crate::{load, with_graph, AnyGraph, AnyWeights, EdgeList, Generator, Orientation, Representation, WeightSpec, Weights};
+
+/// `--repr`, `--generator` and `--graph`, and the graph built from them.
+#[derive(Default)]
+pub struct GraphOptions {
+    pub representation: Representation,
+    pub generator: Generator,
+    /// `--graph`: the file and the edges loaded from it, used instead of
+    /// the generator.
+    pub graph_file: Option<PathBuf>,
+    loaded: Option<EdgeList>,
+    /// `--directed`: generated edges get a random direction, and a file's
+    /// arcs are kept as they are instead of being taken both ways.
+    pub directed: bool,
+    /// `--weights`: weights drawn for every edge, instead of the file's or
+    /// weight 1.
+    pub weights: Option<WeightSpec>,
+    /// Edges (arcs if directed) and memory of the last built graph, and
+    /// its weights if it had any.
+    pub edges: usize,
+    pub memory_bytes: usize,
+    arcs: bool,
+    weight_summary: Option<String>,
+}
+
+impl GraphOptions {
+    /// Reads `--directed`. Call before [`configure`](Self::configure) in
+    /// benchmarks that handle directed graphs; the others reject the flag
+    /// as an unknown option.
+    pub fn configure_directed(&mut self, args: &mut Args) {
+        self.directed = args.flag("--directed");
+    }
+
+    /// Reads `--weights`, for benchmarks that use [`build_weighted`](Self::build_weighted).
+    pub fn configure_weights(&mut self, args: &mut Args) -> Result<(), String> {
+        self.weights = args.parse("--weights")?;
+        Ok(())
+    }
+
+    /// Reads the options, loading the `--graph` file right away so that
+    /// its node count can serve as the default size.
+    pub fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.representation = args.parse("--repr")?.unwrap_or_default();
+        let generator = args.parse("--generator")?;
+        self.graph_file = args.value("--graph")?.map(PathBuf::from);
+        if let Some(path) = &self.graph_file {
+            if generator.is_some() {
+                return Err("--graph and --generator cannot be combined".to_string());
+            }
+            self.loaded = Some(load_file(path)?);
+        }
+        self.generator = generator.unwrap_or_default();
+        Ok(())
+    }
+
+    /// `representation/source[/directed][/weights]`, where the source is
+    /// the generator spec or the file name.
+    pub fn variant(&self) -> String {
+        let mut variant = match &self.graph_file {
+            Some(path) => format!("{}/{}", self.representation, file_name(path)),
+            None => format!("{}/{}", self.representation, self.generator),
+        };
+        if self.directed {
+            variant.push_str("/directed");
+        }
+        if let Some(weights) = &self.weights {
+            variant = format!("{}/{}", variant, weights);
+        }
+        variant
+    }
+
+    /// The size of a loaded graph is fixed by the file.
+    pub fn default_size(&self) -> Option<usize> {
+        self.loaded.as_ref().map(|graph| graph.nodes)
+    }
+
+    /// Only generated graphs and drawn weights depend on the seed.
+    pub fn seed(&self, seed: u64) -> Option<u64> {
+        match (&self.graph_file, &self.weights) {
+            (Some(_), None) => None,
+            _ => Some(seed),
+        }
+    }
+
+    /// Builds the graph on `size` nodes, exiting with an error if the
+    /// generator cannot make one or the loaded graph has another size.
+    pub fn build(&mut self, size: usize, seed: u64) -> AnyGraph {
+        let (graph, _) = self.build_graph(size, seed);
+        graph
+    }
+
+    /// Builds the graph like [`build`](Self::build), with the weights from
+    /// `--weights`, else those of the file, else weight 1 on every edge.
+    /// Exits with an error if the file's weights are negative or too large.
+    pub fn build_weighted(&mut self, size: usize, seed: u64) -> (AnyGraph, AnyWeights) {
+        let (graph, loaded) = self.build_graph(size, seed);
+        let weights = match (&self.weights, loaded) {
+            (Some(spec), _) => AnyWeights::draw(&graph, spec, seed, self.directed),
+            (None, Some(loaded @ EdgeList { weights: Some(_), .. })) => {
+                let symmetric = !(self.directed && loaded.directed);
+                file_weights(&graph, loaded, symmetric).unwrap_or_else(|err| {
+                    eprintln!("Error: {}", err);
+                    exit(1);
+                })
+            }
+            (None, _) => AnyWeights::unit(&graph),
+        };
+        self.memory_bytes += weights.memory_bytes();
+        self.weight_summary = Some(weights.summary());
+        (graph, weights)
+    }
+
+    /// The graph and, for a file, the edges it was built from.
+    fn build_graph(&mut self, size: usize, seed: u64) -> (AnyGraph, Option<EdgeList>) {
+        let (graph, loaded) = match self.loaded.take() {
+            Some(loaded) if loaded.nodes != size => {
+                eprintln!("Error: the graph has {} nodes, not {}", loaded.nodes, size);
+                exit(1);
+            }
+            // Edges are taken both ways unless both file and benchmark are directed.
+            Some(loaded) if self.directed && loaded.directed => {
+                (AnyGraph::from_arcs(self.representation, size, &loaded.edges), Some(loaded))
+            }
+            Some(loaded) => (AnyGraph::from_edges(self.representation, size, &loaded.edges), Some(loaded)),
+            None => {
+                if let Err(err) = self.generator.check(size) {
+                    eprintln!("Error: {}", err);
+                    exit(1);
+                }
+                let orientation = self.directed.then(|| Orientation::new(seed));
+                let graph = AnyGraph::generate(self.representation, &self.generator, size, &mut Rng::new(seed), orientation);
+                (graph, None)
+            }
+        };
+        self.arcs = self.directed && loaded.as_ref().is_none_or(|loaded| loaded.directed);
+        self.edges = if self.arcs { graph.edge_count() } else { graph.edge_count() / 2 };
+        self.memory_bytes = graph.memory_bytes();
+        (graph, loaded)
+    }
+
+    /// Size of the last built graph, for the timing line.
+    pub fn summary(&self) -> String {
+        let weights = match &self.weight_summary {
+            Some(summary) => format!("{}, ", summary),
+            None => String::new(),
+        };
+        format!(
+            "{} {}, {}{} representation ({:.1} MiB)",
+            self.edges,
+            if self.arcs { "arcs" } else { "edges" },
+            weights,
+            self.representation,
+            self.memory_bytes as f64 / (1 << 20) as f64
+        )
+    }
+}
+
+/// The weights of a loaded graph, checked to be non-negative and, for
+/// integers, to fit below the `u32::MAX` that marks unreachable nodes.
+fn file_weights(graph: &AnyGraph, loaded: EdgeList, symmetric: bool) -> Result<AnyWeights, String> {
+    let edges = loaded.edges.iter();
+    match loaded.weights {
+        Some(EdgeWeights::Int(values)) => {
+            if let Some(&w) = values.iter().find(|&&w| !(0..u32::MAX as i64).contains(&w)) {
+                return Err(format!("edge weight {} is negative or too large", w));
+            }
+            let triples = edges.zip(values).map(|(&(u, v), w)| (u, v, w as u32)).collect();
+            Ok(AnyWeights::Int(with_graph!(graph, g => Weights::from_triples(g, triples, symmetric))))
+        }
+        Some(EdgeWeights::Float(values)) => {
+            if let Some(&w) = values.iter().find(|&&w| w.is_nan() || w < 0.0) {
+                return Err(format!("edge weight {} is negative or not a number", w));
+            }
+            let triples = edges.zip(values).map(|(&(u, v), w)| (u, v, w)).collect();
+            Ok(AnyWeights::Float(with_graph!(graph, g => Weights::from_triples(g, triples, symmetric))))
+        }
+        None => Ok(AnyWeights::unit(graph)),
+    }
+}
+
+/// Loads a `--graph` file and reports how long that took on stderr, out of
+/// the way of the records on stdout.
+fn load_file(path: &Path) -> Result<EdgeList, String> {
+    let start = Instant::now();
+    let (graph, origin) = load::load(path)?;
+    eprintln!(
+        "Loaded {} from the {} in {:.3} seconds: {} nodes, {} {}{}",
+        path.display(),
+        origin,
+        start.elapsed().as_secs_f64(),
+        graph.nodes,
+        graph.edges.len(),
+        if graph.directed { "arcs" } else { "edges" },
+        if graph.weights.is_some() { " with weights" } else { "" }
+    );
+    Ok(graph)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned()
+}
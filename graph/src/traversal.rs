@@ -0,0 +1,413 @@
+//! What a BFS or DFS found, and checks that it is a valid search.
+//!
+//! The arrays use `u32` with [`UNREACHED`] (all bits set) for nodes the
+//! search did not reach, so the C implementations can fill the very same
+//! buffers as `int*` with `-1`.
+
+use std::sync::atomic::AtomicU32;
+
+use crate::Graph;
+
+/// Parent and level of a node the search did not reach; `-1` in C.
+pub const UNREACHED: u32 = u32::MAX;
+
+/// Views result arrays as atomics, so that threads of a parallel search can
+/// fill them in place.
+pub fn as_atomic(values: &mut [u32]) -> &[AtomicU32] {
+    // SAFETY: `AtomicU32` has the size and alignment of `u32`, and the
+    // exclusive borrow rules out plain accesses while the view lives.
+    unsafe { &*(values as *mut [u32] as *const [AtomicU32]) }
+}
+
+/// Result of a search from `root`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Traversal {
+    pub root: usize,
+    /// Nodes in the order they were reached; its length is the number of
+    /// reached nodes.
+    pub order: Vec<u32>,
+    /// Parent of every reached node in the search tree. The root is its
+    /// own parent.
+    pub parent: Vec<u32>,
+    /// Depth in the search tree, which for a BFS is the distance from the
+    /// root.
+    pub level: Vec<u32>,
+}
+
+impl Traversal {
+    /// An empty search of an `n`-node graph, with room for every node.
+    pub fn new(n: usize, root: usize) -> Traversal {
+        Traversal {
+            root,
+            order: Vec::with_capacity(n),
+            parent: vec![UNREACHED; n],
+            level: vec![UNREACHED; n],
+        }
+    }
+
+    /// Forgets everything reached, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.order.clear();
+        self.parent.fill(UNREACHED);
+        self.level.fill(UNREACHED);
+    }
+
+    #[inline]
+    pub fn is_reached(&self, node: usize) -> bool {
+        self.parent[node] != UNREACHED
+    }
+
+    /// Marks the root as reached.
+    #[inline]
+    pub fn start(&mut self) {
+        let root = self.root;
+        self.parent[root] = root as u32;
+        self.level[root] = 0;
+        self.order.push(root as u32);
+    }
+
+    /// Marks `node` as reached over the tree edge from `parent`.
+    #[inline]
+    pub fn reach(&mut self, node: usize, parent: usize) {
+        self.parent[node] = parent as u32;
+        self.level[node] = self.level[parent] + 1;
+        self.order.push(node as u32);
+    }
+
+    pub fn reached(&self) -> usize {
+        self.order.len()
+    }
+
+    /// FNV-1a over the visit order and the levels, so two searches with the
+    /// same checksum visited the same nodes in the same order.
+    pub fn checksum(&self) -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        for &node in &self.order {
+            for value in [node, self.level[node as usize]] {
+                hash = (hash ^ value as u64).wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        hash
+    }
+
+    /// First difference from `expected`, if any.
+    pub fn compare(&self, expected: &Traversal) -> Result<(), String> {
+        if let Some(i) = self.order.iter().zip(&expected.order).position(|(a, b)| a != b) {
+            return Err(format!("visit {} is node {}, expected node {}", i, self.order[i], expected.order[i]));
+        }
+        if self.reached() != expected.reached() {
+            return Err(format!("reached {} nodes, expected {}", self.reached(), expected.reached()));
+        }
+        for node in 0..self.parent.len() {
+            if (self.parent[node], self.level[node]) != (expected.parent[node], expected.level[node]) {
+                return Err(format!(
+                    "node {} has parent {} at level {}, expected parent {} at level {}",
+                    node,
+                    signed(self.parent[node]),
+                    signed(self.level[node]),
+                    signed(expected.parent[node]),
+                    signed(expected.level[node])
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares only the levels with `expected`. Two valid BFS agree on
+    /// every distance even when they pick different parents or visit a
+    /// level in a different order.
+    pub fn compare_levels(&self, expected: &Traversal) -> Result<(), String> {
+        if self.reached() != expected.reached() {
+            return Err(format!("reached {} nodes, expected {}", self.reached(), expected.reached()));
+        }
+        match (0..self.level.len()).find(|&node| self.level[node] != expected.level[node]) {
+            Some(node) => Err(format!(
+                "node {} is at level {}, expected level {}",
+                node,
+                signed(self.level[node]),
+                signed(expected.level[node])
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that this is a breadth-first search of `graph`, following
+    /// the Graph500 validation rules:
+    ///
+    /// 1. the tree has no cycles and every tree edge goes down one level;
+    /// 2. every tree edge is an edge of the graph;
+    /// 3. the levels at the two ends of any graph edge differ by at most one;
+    /// 4. the tree spans the whole component of the root;
+    ///
+    /// and, beyond Graph500, that the visit order lists every reached node
+    /// once by non-decreasing level.
+    pub fn validate_bfs<G: Graph>(&self, graph: &G) -> Result<(), String> {
+        self.validate_tree(graph)?;
+        for pair in self.order.windows(2) {
+            let (a, b) = (pair[0] as usize, pair[1] as usize);
+            if self.level[a] > self.level[b] {
+                return Err(format!(
+                    "node {} at level {} is visited after node {} at level {}",
+                    b, self.level[b], a, self.level[a]
+                ));
+            }
+        }
+        for u in 0..graph.node_count() {
+            if !self.is_reached(u) {
+                continue;
+            }
+            for v in graph.neighbors(u) {
+                if self.level[u].abs_diff(self.level[v]) > 1 {
+                    return Err(format!(
+                        "edge ({}, {}) joins levels {} and {}",
+                        u,
+                        v,
+                        self.level[u],
+                        signed(self.level[v])
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that this is a depth-first search of `graph`: a valid search
+    /// tree (rules 1, 2 and 4 of [`validate_bfs`](Self::validate_bfs)),
+    /// visited in a preorder of that tree, in which every graph edge joins a
+    /// node to one of its ancestors, as there are no cross edges in an
+    /// undirected DFS.
+    pub fn validate_dfs<G: Graph>(&self, graph: &G) -> Result<(), String> {
+        self.validate_tree(graph)?;
+
+        // Subtree sizes, from the leaves up. A preorder puts every subtree
+        // in one contiguous run starting at its root.
+        let mut position = vec![0; graph.node_count()];
+        for (i, &node) in self.order.iter().enumerate() {
+            position[node as usize] = i;
+        }
+        let mut size = vec![1usize; graph.node_count()];
+        for &node in self.order.iter().skip(1).rev() {
+            size[self.parent[node as usize] as usize] += size[node as usize];
+        }
+        let contains = |ancestor: usize, node: usize| {
+            (position[ancestor]..position[ancestor] + size[ancestor]).contains(&position[node])
+        };
+        for &node in self.order.iter().skip(1) {
+            let node = node as usize;
+            if !contains(self.parent[node] as usize, node) {
+                return Err(format!("node {} is visited outside the subtree of its parent", node));
+            }
+        }
+        for &u in &self.order {
+            let u = u as usize;
+            for v in graph.neighbors(u) {
+                if !contains(u, v) && !contains(v, u) {
+                    return Err(format!("edge ({}, {}) joins two separate subtrees", u, v));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rules shared by BFS and DFS trees.
+    fn validate_tree<G: Graph>(&self, graph: &G) -> Result<(), String> {
+        let n = graph.node_count();
+        if self.parent.len() != n || self.level.len() != n {
+            return Err(format!(
+                "result has {} parents and {} levels, the graph has {} nodes",
+                self.parent.len(),
+                self.level.len(),
+                n
+            ));
+        }
+        if self.parent[self.root] != self.root as u32 || self.level[self.root] != 0 {
+            return Err(format!("root {} is not at the top of the tree", self.root));
+        }
+
+        let mut listed = vec![false; n];
+        for &node in &self.order {
+            let node = node as usize;
+            if node >= n || !self.is_reached(node) || std::mem::replace(&mut listed[node], true) {
+                return Err(format!("node {} is listed twice or was never reached", node));
+            }
+        }
+        let reached = (0..n).filter(|&node| self.is_reached(node)).count();
+        if reached != self.reached() {
+            return Err(format!("{} nodes have a parent, but {} were visited", reached, self.reached()));
+        }
+
+        // Rule 1: a tree edge goes down exactly one level, so following
+        // parents always ends at the root.
+        let mut tree_edges = 0;
+        for node in (0..n).filter(|&node| node != self.root && self.is_reached(node)) {
+            let parent = self.parent[node] as usize;
+            if parent >= n || !self.is_reached(parent) || self.level[node] != self.level[parent] + 1 {
+                return Err(format!(
+                    "node {} at level {} has parent {} at level {}",
+                    node,
+                    self.level[node],
+                    parent,
+                    signed(self.level.get(parent).copied().unwrap_or(UNREACHED))
+                ));
+            }
+            tree_edges += 1;
+        }
+
+        // Rules 2 and 4, in one pass over the edges of reached nodes.
+        let mut found = 0;
+        for u in (0..n).filter(|&u| self.is_reached(u)) {
+            for v in graph.neighbors(u) {
+                if !self.is_reached(v) {
+                    return Err(format!("node {} is unreached, but its neighbour {} was reached", v, u));
+                }
+                if v != self.root && self.parent[v] as usize == u {
+                    found += 1;
+                }
+            }
+        }
+        if found != tree_edges {
+            return Err(format!("{} of the {} tree edges are not in the graph", tree_edges - found, tree_edges));
+        }
+        Ok(())
+    }
+}
+
+/// `UNREACHED` as the `-1` the C code writes.
+fn signed(value: u32) -> i64 {
+    value as i32 as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Csr, FromEdges};
+
+    const U: u32 = UNREACHED;
+
+    /// A square 0-1-3-2 with a tail 3-4, and node 5 on its own.
+    fn graph() -> Csr {
+        Csr::from_edges(6, &[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)])
+    }
+
+    fn search(order: &[u32], parent: &[u32], level: &[u32]) -> Traversal {
+        Traversal {
+            root: 0,
+            order: order.to_vec(),
+            parent: parent.to_vec(),
+            level: level.to_vec(),
+        }
+    }
+
+    fn bfs() -> Traversal {
+        search(&[0, 1, 2, 3, 4], &[0, 0, 0, 1, 3, U], &[0, 1, 1, 2, 3, U])
+    }
+
+    /// Smallest neighbour first: 0, 1, 3, 2, then back up to 3 for 4.
+    fn dfs() -> Traversal {
+        search(&[0, 1, 3, 2, 4], &[0, 0, 3, 1, 3, U], &[0, 1, 3, 2, 3, U])
+    }
+
+    fn assert_rejected(result: Result<(), String>, what: &str) {
+        assert!(result.is_err(), "accepted {what}");
+    }
+
+    #[test]
+    fn accepts_valid_searches() {
+        let graph = graph();
+        bfs().validate_bfs(&graph).unwrap();
+        // Another parent and another order within a level are just as valid.
+        search(&[0, 2, 1, 3, 4], &[0, 0, 0, 2, 3, U], &[0, 1, 1, 2, 3, U]).validate_bfs(&graph).unwrap();
+        dfs().validate_dfs(&graph).unwrap();
+        // Children in another order: 4 before 2.
+        search(&[0, 1, 3, 4, 2], &[0, 0, 3, 1, 3, U], &[0, 1, 3, 2, 3, U]).validate_dfs(&graph).unwrap();
+        // From the isolated node only itself is reached.
+        let alone = Traversal {
+            root: 5,
+            order: vec![5],
+            parent: vec![U, U, U, U, U, 5],
+            level: vec![U, U, U, U, U, 0],
+        };
+        alone.validate_bfs(&graph).unwrap();
+        alone.validate_dfs(&graph).unwrap();
+    }
+
+    #[test]
+    fn rejects_broken_trees() {
+        let graph = graph();
+        for (what, corrupt) in [
+            ("a result of the wrong length", (|t| t.level.truncate(5)) as fn(&mut Traversal)),
+            ("a root with another parent", |t| t.parent[0] = 1),
+            ("a root below level 0", |t| t.level[0] = 1),
+            ("a node listed twice", |t| t.order[2] = 1),
+            ("a node listed past the graph", |t| t.order[4] = 9),
+            ("an unlisted node", |t| {
+                t.order.pop();
+            }),
+            ("a listed node without a parent", |t| t.parent[4] = U),
+            ("a level skipped", |t| t.level[4] = 4),
+            ("a parent past the graph", |t| t.parent[4] = 99),
+            ("a parent that was not reached", |t| t.parent[4] = 5),
+            ("a parent cycle", |t| {
+                t.parent[1] = 3;
+                t.parent[3] = 1;
+            }),
+            ("a tree edge missing from the graph", |t| {
+                t.parent[4] = 1;
+                t.level[4] = 2;
+            }),
+            ("an unreached neighbour", |t| {
+                t.order.pop();
+                t.parent[4] = U;
+                t.level[4] = U;
+            }),
+            ("a node of another component", |t| {
+                t.order.push(5);
+                t.parent[5] = 4;
+                t.level[5] = 4;
+            }),
+        ] {
+            let mut search = bfs();
+            corrupt(&mut search);
+            assert_rejected(search.validate_bfs(&graph), what);
+            let mut search = dfs();
+            corrupt(&mut search);
+            assert_rejected(search.validate_dfs(&graph), what);
+        }
+    }
+
+    #[test]
+    fn rejects_searches_of_the_other_kind() {
+        let graph = graph();
+        // Levels are not distances: edge (0, 2) spans three levels.
+        assert_rejected(dfs().validate_bfs(&graph), "a DFS as a BFS");
+        // Edge (2, 3) joins two subtrees of 0.
+        assert_rejected(bfs().validate_dfs(&graph), "a BFS as a DFS");
+    }
+
+    #[test]
+    fn rejects_bfs_visits_out_of_level_order() {
+        let mut out_of_order = bfs();
+        out_of_order.order = vec![0, 1, 3, 2, 4];
+        assert_rejected(out_of_order.validate_bfs(&graph()), "level 2 before level 1");
+    }
+
+    #[test]
+    fn rejects_dfs_visits_out_of_preorder() {
+        let mut child_first = dfs();
+        // 2 is visited before its parent 3.
+        child_first.order = vec![0, 1, 2, 3, 4];
+        assert_rejected(child_first.validate_dfs(&graph()), "a child before its parent");
+        // 3 is visited after leaving the subtree of its parent 1 for node 4.
+        let graph = Csr::from_edges(5, &[(0, 1), (0, 4), (1, 2), (1, 3)]);
+        let reentered = search(&[0, 1, 2, 4, 3], &[0, 0, 1, 1, 0], &[0, 1, 2, 2, 1]);
+        assert_rejected(reentered.validate_dfs(&graph), "a subtree left and re-entered");
+    }
+
+    #[test]
+    fn compares_levels_only() {
+        let other = search(&[0, 2, 1, 3, 4], &[0, 0, 0, 2, 3, U], &[0, 1, 1, 2, 3, U]);
+        other.compare_levels(&bfs()).unwrap();
+        assert!(other.compare(&bfs()).is_err());
+        assert!(dfs().compare_levels(&bfs()).is_err());
+    }
+}
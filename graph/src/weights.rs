@@ -0,0 +1,379 @@
+//! Edge weights, kept next to a graph rather than inside it.
+//!
+//! [`Weights`] stores one value per adjacency entry, in the order
+//! [`Graph::neighbors`] produces them, so every representation can be
+//! weighted the same way. Weights are drawn from a [`WeightSpec`], written
+//! as `int|float[:param=value,...]`, e.g. `int:min=1,max=10` or
+//! `float:dist=exp,mean=2`, or taken from a weighted graph file.
+
+use std::fmt;
+use std::str::FromStr;
+
+use harness::args::Params;
+use harness::input::Rng;
+
+use crate::generate::edge_rng;
+use crate::{with_graph, AnyGraph, Graph};
+
+/// A non-negative edge weight, and the distances summed from them.
+pub trait Weight: Copy + PartialOrd + fmt::Display + Send + Sync + 'static {
+    const ZERO: Self;
+    /// Distance of an unreachable node.
+    const INFINITY: Self;
+
+    /// `self + other`, staying at [`INFINITY`](Weight::INFINITY) instead of
+    /// overflowing.
+    fn plus(self, other: Self) -> Self;
+
+    /// An integer ordered like the value, for the non-negative values that
+    /// distances take. Lets heaps of floats use `Ord`.
+    fn key(self) -> u64;
+
+    /// Whether two distances agree: exactly for integers, up to rounding
+    /// for floats, whose sums depend on the order they are added in.
+    fn close(self, other: Self) -> bool;
+}
+
+impl Weight for u32 {
+    const ZERO: u32 = 0;
+    const INFINITY: u32 = u32::MAX;
+
+    #[inline]
+    fn plus(self, other: u32) -> u32 {
+        self.saturating_add(other)
+    }
+
+    #[inline]
+    fn key(self) -> u64 {
+        self as u64
+    }
+
+    fn close(self, other: u32) -> bool {
+        self == other
+    }
+}
+
+impl Weight for f64 {
+    const ZERO: f64 = 0.0;
+    const INFINITY: f64 = f64::INFINITY;
+
+    #[inline]
+    fn plus(self, other: f64) -> f64 {
+        self + other
+    }
+
+    /// The bits of a non-negative float sort like the float.
+    #[inline]
+    fn key(self) -> u64 {
+        self.to_bits()
+    }
+
+    fn close(self, other: f64) -> bool {
+        self == other || (self - other).abs() <= 1e-9 * self.abs().max(other.abs())
+    }
+}
+
+/// One weight per adjacency entry: the weights of `node`'s neighbours are
+/// `values[offsets[node]..offsets[node + 1]]`.
+#[derive(Debug, Clone)]
+pub struct Weights<W> {
+    offsets: Vec<usize>,
+    values: Vec<W>,
+}
+
+impl<W: Weight> Weights<W> {
+    /// Weighs the edge from `u` to every neighbour `v` with `weight(u, v)`.
+    pub fn from_fn<G: Graph>(graph: &G, mut weight: impl FnMut(u32, u32) -> W) -> Weights<W> {
+        let n = graph.node_count();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut values = Vec::with_capacity(graph.edge_count());
+        offsets.push(0);
+        for u in 0..n {
+            values.extend(graph.neighbors(u).map(|v| weight(u as u32, v as u32)));
+            offsets.push(values.len());
+        }
+        Weights { offsets, values }
+    }
+
+    /// Weights from `(u, v, weight)` triples covering every adjacency entry
+    /// of `graph`, e.g. from a file. With `symmetric`, each triple weighs
+    /// both directions. Of duplicate edges the lightest is kept, as the
+    /// graph keeps only one of them.
+    pub fn from_triples<G: Graph>(graph: &G, mut triples: Vec<(u32, u32, W)>, symmetric: bool) -> Weights<W> {
+        if symmetric {
+            let reversed: Vec<_> = triples.iter().filter(|t| t.0 != t.1).map(|&(u, v, w)| (v, u, w)).collect();
+            triples.extend(reversed);
+        }
+        triples.sort_unstable_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then(a.2.partial_cmp(&b.2).unwrap()));
+        triples.dedup_by_key(|t| (t.0, t.1));
+        assert_eq!(triples.len(), graph.edge_count(), "the weights do not match the edges of the graph");
+
+        let mut next = triples.iter();
+        Weights::from_fn(graph, |u, v| {
+            let &(a, b, w) = next.next().unwrap();
+            assert_eq!((a, b), (u, v), "the weights do not match the edges of the graph");
+            w
+        })
+    }
+
+    /// Weights of the edges to `node`'s neighbours, in neighbour order.
+    #[inline]
+    pub fn of(&self, node: usize) -> &[W] {
+        &self.values[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    pub fn values(&self) -> &[W] {
+        &self.values
+    }
+
+    /// Heaviest weight, or zero without edges.
+    pub fn max(&self) -> W {
+        self.values.iter().copied().fold(W::ZERO, |max, w| if w > max { w } else { max })
+    }
+
+    /// Lightest and heaviest weight, unless there are no edges.
+    pub fn range(&self) -> Option<(W, W)> {
+        let first = *self.values.first()?;
+        Some(self.values.iter().fold((first, first), |(min, max), &w| {
+            (if w < min { w } else { min }, if w > max { w } else { max })
+        }))
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.offsets.capacity() * size_of::<usize>() + self.values.capacity() * size_of::<W>()
+    }
+}
+
+/// Evaluates `$body` with `$w` bound to the [`Weights`] inside an
+/// [`AnyWeights`], like [`with_graph!`](crate::with_graph) for graphs.
+#[macro_export]
+macro_rules! with_weights {
+    ($weights:expr, $w:ident => $body:expr) => {
+        match $weights {
+            $crate::AnyWeights::Int($w) => $body,
+            $crate::AnyWeights::Float($w) => $body,
+        }
+    };
+}
+
+/// Integer or float weights, picked at run time.
+#[derive(Debug, Clone)]
+pub enum AnyWeights {
+    Int(Weights<u32>),
+    Float(Weights<f64>),
+}
+
+impl AnyWeights {
+    /// Weight 1 on every edge, for graphs that come without weights.
+    pub fn unit(graph: &AnyGraph) -> AnyWeights {
+        AnyWeights::Int(with_graph!(graph, g => Weights::from_fn(g, |_, _| 1)))
+    }
+
+    /// Draws a weight per edge from `spec`. The draw depends only on the
+    /// seed and the edge, so an undirected edge weighs the same both ways
+    /// and a graph gets the same weights in every representation.
+    pub fn draw(graph: &AnyGraph, spec: &WeightSpec, seed: u64, directed: bool) -> AnyWeights {
+        let seed = seed ^ 0x7765_6967_6874_7321;
+        let rng = |u: u32, v: u32| {
+            if directed {
+                edge_rng(seed, u, v)
+            } else {
+                edge_rng(seed, u.min(v), u.max(v))
+            }
+        };
+        match spec.kind {
+            WeightKind::Int => {
+                AnyWeights::Int(with_graph!(graph, g => Weights::from_fn(g, |u, v| spec.draw_int(&mut rng(u, v)))))
+            }
+            WeightKind::Float => {
+                AnyWeights::Float(with_graph!(graph, g => Weights::from_fn(g, |u, v| spec.draw_float(&mut rng(u, v)))))
+            }
+        }
+    }
+
+    pub fn kind(&self) -> WeightKind {
+        match self {
+            AnyWeights::Int(_) => WeightKind::Int,
+            AnyWeights::Float(_) => WeightKind::Float,
+        }
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        with_weights!(self, w => w.memory_bytes())
+    }
+
+    /// Kind and range of the weights, for the timing line.
+    pub fn summary(&self) -> String {
+        match self {
+            AnyWeights::Int(w) => match w.range() {
+                Some((min, max)) => format!("int weights {}..={}", min, max),
+                None => "no weights".to_string(),
+            },
+            AnyWeights::Float(w) => match w.range() {
+                Some((min, max)) => format!("float weights {:.3}..={:.3}", min, max),
+                None => "no weights".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightKind {
+    Int,
+    Float,
+}
+
+impl fmt::Display for WeightKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightKind::Int => write!(f, "int"),
+            WeightKind::Float => write!(f, "float"),
+        }
+    }
+}
+
+/// Distribution of drawn weights. Integer weights are rounded, and weights
+/// below zero are raised to zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// Uniform in `[min, max]`.
+    Uniform { min: f64, max: f64 },
+    /// Exponential with the given mean.
+    Exponential { mean: f64 },
+    /// Normal with the given mean and standard deviation.
+    Normal { mean: f64, sd: f64 },
+}
+
+/// `--weights`: the kind of weights and how they are drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightSpec {
+    pub kind: WeightKind,
+    pub distribution: Distribution,
+}
+
+impl WeightSpec {
+    fn draw_float(&self, rng: &mut Rng) -> f64 {
+        let value = match self.distribution {
+            Distribution::Uniform { min, max } => min + rng.next_f64() * (max - min),
+            Distribution::Exponential { mean } => -mean * (1.0 - rng.next_f64()).ln(),
+            Distribution::Normal { mean, sd } => {
+                // Box-Muller.
+                let (a, b) = (1.0 - rng.next_f64(), rng.next_f64());
+                mean + sd * (-2.0 * a.ln()).sqrt() * (std::f64::consts::TAU * b).cos()
+            }
+        };
+        value.max(0.0)
+    }
+
+    fn draw_int(&self, rng: &mut Rng) -> u32 {
+        match self.distribution {
+            // Every integer in the range equally likely, ends included.
+            Distribution::Uniform { min, max } => {
+                let (min, max) = (min.round() as u64, max.round() as u64);
+                (min + rng.below(max - min + 1)) as u32
+            }
+            // Kept below u32::MAX, which marks unreachable nodes.
+            _ => self.draw_float(rng).round().min((u32::MAX - 1) as f64) as u32,
+        }
+    }
+}
+
+impl fmt::Display for WeightSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.distribution {
+            Distribution::Uniform { min, max } => write!(f, "{}:dist=uniform,min={},max={}", self.kind, min, max),
+            Distribution::Exponential { mean } => write!(f, "{}:dist=exp,mean={}", self.kind, mean),
+            Distribution::Normal { mean, sd } => write!(f, "{}:dist=normal,mean={},sd={}", self.kind, mean, sd),
+        }
+    }
+}
+
+impl FromStr for WeightSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let kind = match name {
+            "int" => WeightKind::Int,
+            "float" => WeightKind::Float,
+            _ => return Err(format!("unknown weights: {name} (expected int or float)")),
+        };
+        let mut params = Params::parse(params)?;
+        let distribution = match params.get::<String>("dist")?.as_deref().unwrap_or("uniform") {
+            "uniform" => {
+                let min: f64 = params.get("min")?.unwrap_or(1.0);
+                let max: f64 = params.get("max")?.unwrap_or(100.0);
+                if min.is_nan() || max.is_nan() || min < 0.0 || min > max || max >= u32::MAX as f64 {
+                    return Err(format!("need 0 <= min <= max < 2^32, got min={} and max={}", min, max));
+                }
+                Distribution::Uniform { min, max }
+            }
+            "exp" => {
+                let mean: f64 = params.get("mean")?.unwrap_or(10.0);
+                if mean.is_nan() || mean <= 0.0 {
+                    return Err(format!("mean must be positive, got {}", mean));
+                }
+                Distribution::Exponential { mean }
+            }
+            "normal" => {
+                let mean = params.get("mean")?.unwrap_or(50.0);
+                let sd: f64 = params.get("sd")?.unwrap_or(15.0);
+                if sd.is_nan() || sd < 0.0 {
+                    return Err(format!("sd must not be negative, got {}", sd));
+                }
+                Distribution::Normal { mean, sd }
+            }
+            dist => return Err(format!("unknown distribution: {dist} (expected uniform, exp or normal)")),
+        };
+        params.finish(name)?;
+        Ok(WeightSpec { kind, distribution })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_round_trip() {
+        for spec in [
+            WeightSpec {
+                kind: WeightKind::Int,
+                distribution: Distribution::Uniform { min: 1.0, max: 10.0 },
+            },
+            WeightSpec {
+                kind: WeightKind::Float,
+                distribution: Distribution::Exponential { mean: 2.5 },
+            },
+            WeightSpec {
+                kind: WeightKind::Float,
+                distribution: Distribution::Normal { mean: -3.0, sd: 0.5 },
+            },
+        ] {
+            let text = spec.to_string();
+            assert_eq!(text.parse(), Ok(spec), "{text}");
+        }
+    }
+
+    #[test]
+    fn specs_fill_in_defaults() {
+        let spec: WeightSpec = "int".parse().unwrap();
+        assert_eq!(spec.to_string(), "int:dist=uniform,min=1,max=100");
+        let spec: WeightSpec = "float:dist=exp".parse().unwrap();
+        assert_eq!(spec.to_string(), "float:dist=exp,mean=10");
+    }
+
+    #[test]
+    fn specs_reject_bad_parameters() {
+        for spec in [
+            "int:min=5,max=4",
+            "int:min=-1",
+            "float:dist=exp,mean=0",
+            "float:dist=normal,sd=-1",
+            "bool",
+            "int:k=1",
+        ] {
+            assert!(spec.parse::<WeightSpec>().is_err(), "{spec}");
+        }
+    }
+}
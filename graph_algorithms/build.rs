@@ -0,0 +1,15 @@
+// Compiles the C implementations so the benchmarks can call them through
+// FFI.
+const SOURCES: [&str; 4] = ["shortest_path_algo", "components_algo", "toposort_algo", "pagerank_algo"];
+
+fn main() {
+    // Included by shortest_path_algo.c once per weight type.
+    println!("cargo:rerun-if-changed=shortest_path_generic.inc");
+    let mut build = cc::Build::new();
+    for source in SOURCES {
+        println!("cargo:rerun-if-changed={}.c", source);
+        println!("cargo:rerun-if-changed={}.h", source);
+        build.file(format!("{}.c", source));
+    }
+    build.opt_level(3).compile("graph_algorithms_algo");
+}
@@ -0,0 +1,134 @@
+#include <stdlib.h>
+#include "components_algo.h"
+
+static int find(int* parent, int node) {
+    while (parent[node] != node) {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    return node;
+}
+
+int union_find_components(const int* offsets, const int* targets, int n, int* labels) {
+    int* parent = (int*)malloc(n * sizeof(int));
+    int* size = (int*)malloc(n * sizeof(int));
+    if (!parent || !size) {
+        free(parent);
+        free(size);
+        return -1;
+    }
+    for (int i = 0; i < n; i++) {
+        parent[i] = i;
+        size[i] = 1;
+    }
+
+    for (int u = 0; u < n; u++) {
+        for (int e = offsets[u]; e < offsets[u + 1]; e++) {
+            int v = targets[e];
+            if (v <= u) {
+                continue;
+            }
+            int a = find(parent, u);
+            int b = find(parent, v);
+            if (a == b) {
+                continue;
+            }
+            if (size[a] < size[b]) {
+                int t = a;
+                a = b;
+                b = t;
+            }
+            parent[b] = a;
+            size[a] += size[b];
+        }
+    }
+
+    // In ascending order, the first node of a set labels its root.
+    int count = 0;
+    for (int i = 0; i < n; i++) {
+        int root = find(parent, i);
+        if (labels[root] == -1) {
+            labels[root] = i;
+            count++;
+        }
+        labels[i] = labels[root];
+    }
+
+    free(parent);
+    free(size);
+    return count;
+}
+
+int tarjan_scc(const int* offsets, const int* targets, int n, int* component) {
+    int* index = (int*)malloc(n * sizeof(int));
+    int* low = (int*)malloc(n * sizeof(int));
+    int* stack = (int*)malloc(n * sizeof(int));
+    // Each call frame is a node and its next edge, like the locals of a
+    // suspended recursive call.
+    int* callNodes = (int*)malloc(n * sizeof(int));
+    int* callEdges = (int*)malloc(n * sizeof(int));
+    if (!index || !low || !stack || !callNodes || !callEdges) {
+        free(index);
+        free(low);
+        free(stack);
+        free(callNodes);
+        free(callEdges);
+        return -1;
+    }
+    for (int i = 0; i < n; i++) {
+        index[i] = -1;
+    }
+
+    int nextIndex = 0;
+    int top = 0;
+    int count = 0;
+    for (int root = 0; root < n; root++) {
+        if (index[root] != -1) {
+            continue;
+        }
+        int calls = 0;
+        index[root] = low[root] = nextIndex++;
+        stack[top++] = root;
+        callNodes[calls] = root;
+        callEdges[calls++] = offsets[root];
+
+        while (calls > 0) {
+            int node = callNodes[calls - 1];
+            int e = callEdges[calls - 1];
+            if (e < offsets[node + 1]) {
+                callEdges[calls - 1] = e + 1;
+                int next = targets[e];
+                if (index[next] == -1) {
+                    index[next] = low[next] = nextIndex++;
+                    stack[top++] = next;
+                    callNodes[calls] = next;
+                    callEdges[calls++] = offsets[next];
+                } else if (component[next] == -1 && index[next] < low[node]) {
+                    // Visited but not yet in a component: still on the stack.
+                    low[node] = index[next];
+                }
+                continue;
+            }
+
+            calls--;
+            if (calls > 0 && low[node] < low[callNodes[calls - 1]]) {
+                low[callNodes[calls - 1]] = low[node];
+            }
+            if (low[node] == index[node]) {
+                int member;
+                do {
+                    member = stack[--top];
+                    component[member] = count;
+                } while (member != node);
+                count++;
+            }
+        }
+    }
+
+    free(index);
+    free(low);
+    free(stack);
+    free(callNodes);
+    free(callEdges);
+    return count;
+}
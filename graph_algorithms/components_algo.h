@@ -0,0 +1,20 @@
+#ifndef COMPONENTS_ALGO_H
+#define COMPONENTS_ALGO_H
+
+// Components of a graph in compressed sparse row form: the neighbours of
+// node i are targets[offsets[i]..offsets[i + 1]].
+
+// Connected components of an undirected graph with union-find (union by
+// size, path halving). labels must hold n entries set to -1; every node is
+// labelled with the smallest node of its component. Returns the number of
+// components, or -1 if it cannot allocate the forest.
+int union_find_components(const int* offsets, const int* targets, int n, int* labels);
+
+// Strongly connected components with Tarjan's algorithm, run without
+// recursion. component must hold n entries set to -1; components are
+// numbered from 0 in the order Tarjan's algorithm completes them, which is
+// a reverse topological order. Returns the number of components, or -1 if
+// it cannot allocate its stacks.
+int tarjan_scc(const int* offsets, const int* targets, int n, int* component);
+
+#endif
@@ -0,0 +1,36 @@
+#include <stdlib.h>
+#include "pagerank_algo.h"
+
+int pagerank(const int* offsets, const int* targets, int n, double damping, int iterations, double* rank) {
+    double* contribution = (double*)malloc(n * sizeof(double));
+    if (!contribution) {
+        return -1;
+    }
+    for (int i = 0; i < n; i++) {
+        rank[i] = 1.0 / n;
+    }
+
+    for (int iteration = 0; iteration < iterations; iteration++) {
+        double dangling = 0.0;
+        for (int u = 0; u < n; u++) {
+            int degree = offsets[u + 1] - offsets[u];
+            if (degree == 0) {
+                dangling += rank[u];
+                contribution[u] = 0.0;
+            } else {
+                contribution[u] = rank[u] / degree;
+            }
+        }
+        double base = (1.0 - damping + damping * dangling) / n;
+        for (int v = 0; v < n; v++) {
+            double sum = 0.0;
+            for (int e = offsets[v]; e < offsets[v + 1]; e++) {
+                sum += contribution[targets[e]];
+            }
+            rank[v] = base + damping * sum;
+        }
+    }
+
+    free(contribution);
+    return 0;
+}
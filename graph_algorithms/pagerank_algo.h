@@ -0,0 +1,11 @@
+#ifndef PAGERANK_ALGO_H
+#define PAGERANK_ALGO_H
+
+// PageRank by power iteration over a graph in compressed sparse row form:
+// the neighbours of node i are targets[offsets[i]..offsets[i + 1]]. Each
+// iteration pulls rank along the edges of an undirected graph; the rank of
+// nodes without neighbours is spread over all nodes. rank must hold n
+// entries. Returns 0, or -1 if it cannot allocate.
+int pagerank(const int* offsets, const int* targets, int n, double damping, int iterations, double* rank);
+
+#endif
@@ -0,0 +1,102 @@
+#include <limits.h>
+#include <math.h>
+#include <stdlib.h>
+#include "shortest_path_algo.h"
+
+static unsigned addUnsigned(unsigned a, unsigned b) {
+    return a > UINT_MAX - b ? UINT_MAX : a + b;
+}
+
+static double addDouble(double a, double b) {
+    return a + b;
+}
+
+#define WEIGHT unsigned
+#define INF UINT_MAX
+#define ADD addUnsigned
+#define NAME(name) name
+#include "shortest_path_generic.inc"
+#undef WEIGHT
+#undef INF
+#undef ADD
+#undef NAME
+
+#define WEIGHT double
+#define INF INFINITY
+#define ADD addDouble
+#define NAME(name) name##_f64
+#include "shortest_path_generic.inc"
+#undef WEIGHT
+#undef INF
+#undef ADD
+#undef NAME
+
+typedef struct {
+    int* nodes;
+    int size;
+    int capacity;
+} Bucket;
+
+static int bucketPush(Bucket* bucket, int node) {
+    if (bucket->size == bucket->capacity) {
+        int capacity = bucket->capacity ? bucket->capacity * 2 : 64;
+        int* nodes = (int*)realloc(bucket->nodes, capacity * sizeof(int));
+        if (!nodes) {
+            return 0;
+        }
+        bucket->nodes = nodes;
+        bucket->capacity = capacity;
+    }
+    bucket->nodes[bucket->size++] = node;
+    return 1;
+}
+
+int dijkstra_buckets(const int* offsets, const int* targets, const unsigned* weights, int n, int source,
+                     unsigned* dist) {
+    unsigned longest = 0;
+    for (int e = 0; e < offsets[n]; e++) {
+        if (weights[e] > longest) {
+            longest = weights[e];
+        }
+    }
+    size_t count = (size_t)longest + 1;
+    Bucket* buckets = (Bucket*)calloc(count, sizeof(Bucket));
+    if (!buckets) {
+        return -1;
+    }
+
+    int reached = 0;
+    int queued = 1;
+    int failed = 0;
+    dist[source] = 0;
+    failed |= !bucketPush(&buckets[0], source);
+    for (unsigned d = 0; queued > 0 && !failed; d++) {
+        Bucket* bucket = &buckets[d % count];
+        // Edges of length 0 add to this bucket while it is emptied, so its
+        // size is read again every time.
+        for (int b = 0; b < bucket->size && !failed; b++) {
+            int node = bucket->nodes[b];
+            queued--;
+            if (dist[node] != d) {
+                continue;
+            }
+            reached++;
+            for (int e = offsets[node]; e < offsets[node + 1]; e++) {
+                int i = targets[e];
+                unsigned through = addUnsigned(d, weights[e]);
+                if (through < dist[i]) {
+                    dist[i] = through;
+                    failed |= !bucketPush(&buckets[through % count], i);
+                    queued++;
+                }
+            }
+        }
+        bucket->size = 0;
+    }
+
+    for (size_t b = 0; b < count; b++) {
+        free(buckets[b].nodes);
+    }
+    free(buckets);
+    return failed ? -1 : reached;
+}
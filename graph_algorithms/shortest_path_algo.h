@@ -0,0 +1,33 @@
+#ifndef SHORTEST_PATH_ALGO_H
+#define SHORTEST_PATH_ALGO_H
+
+// Single-source shortest paths over a weighted graph in compressed sparse
+// row form: the neighbours of node i are targets[offsets[i]..offsets[i + 1]],
+// and the edge to targets[e] has length weights[e]. dist must hold n
+// entries set to UINT_MAX, or INFINITY for the _f64 versions; each function
+// fills in the distance of every node reachable from source and returns the
+// number of such nodes, or -1 if it cannot allocate its queue. Sums of
+// unsigned lengths stop at UINT_MAX instead of wrapping around.
+
+// Dijkstra's algorithm with a binary heap of (distance, node) entries.
+// Entries made stale by a shorter path are skipped when popped.
+int dijkstra_heap(const int* offsets, const int* targets, const unsigned* weights, int n, int source, unsigned* dist);
+int dijkstra_heap_f64(const int* offsets, const int* targets, const double* weights, int n, int source, double* dist);
+
+// Dijkstra's algorithm with Dial's bucket queue: a ring of buckets, one per
+// distance, as long as the largest edge length plus one.
+int dijkstra_buckets(const int* offsets, const int* targets, const unsigned* weights, int n, int source,
+                     unsigned* dist);
+
+// Bellman-Ford: relaxes every edge in rounds until a round changes nothing.
+// Returns the number of rounds instead.
+int bellman_ford(const int* offsets, const int* targets, const unsigned* weights, int n, int source, unsigned* dist);
+int bellman_ford_f64(const int* offsets, const int* targets, const double* weights, int n, int source, double* dist);
+
+// All-pairs shortest paths in place: dist is an n x n matrix holding 0 on
+// the diagonal, the edge lengths, and UINT_MAX or INFINITY for missing
+// edges.
+void floyd_warshall(unsigned** dist, int n);
+void floyd_warshall_f64(double** dist, int n);
+
+#endif
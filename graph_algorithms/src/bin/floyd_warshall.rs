@@ -0,0 +1,6 @@
+use graph_algorithms::FloydWarshall;
+use harness::Runner;
+
+fn main() {
+    Runner::new().run(FloydWarshall::default());
+}
@@ -0,0 +1,6 @@
+use graph_algorithms::Pagerank;
+use harness::Runner;
+
+fn main() {
+    Runner::new().run(Pagerank::default());
+}
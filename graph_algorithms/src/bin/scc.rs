@@ -0,0 +1,6 @@
+use graph_algorithms::Scc;
+use harness::Runner;
+
+fn main() {
+    Runner::new().run(Scc::default());
+}
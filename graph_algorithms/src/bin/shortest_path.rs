@@ -0,0 +1,6 @@
+use graph_algorithms::ShortestPath;
+use harness::Runner;
+
+fn main() {
+    Runner::new().run(ShortestPath::default());
+}
@@ -0,0 +1,6 @@
+use graph_algorithms::Toposort;
+use harness::Runner;
+
+fn main() {
+    Runner::new().run(Toposort::default());
+}
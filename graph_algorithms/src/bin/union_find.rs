@@ -0,0 +1,6 @@
+use graph_algorithms::UnionFind;
+use harness::Runner;
+
+fn main() {
+    Runner::new().run(UnionFind::default());
+}
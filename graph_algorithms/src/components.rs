@@ -0,0 +1,451 @@
+//! Connected components with union-find, and strongly connected components
+//! with Tarjan's algorithm.
+//!
+//! Both fill a `u32` per node that starts out [`UNREACHED`] (`-1` in C).
+
+use graph::traversal::UNREACHED;
+use graph::{with_graph, AnyGraph, Graph, GraphOptions};
+use harness::{Args, Benchmark, Language};
+
+use crate::ffi::{self, CGraph};
+
+/// Root of `node`'s set, halving the path on the way.
+#[inline]
+fn find(parent: &mut [u32], mut node: usize) -> usize {
+    while parent[node] as usize != node {
+        parent[node] = parent[parent[node] as usize];
+        node = parent[node] as usize;
+    }
+    node
+}
+
+/// Connected components of an undirected graph by union-find, with union
+/// by size and path halving. Every node is labelled with the smallest node
+/// of its component, as by [`dfs::components::components`]. Returns the
+/// number of components.
+pub fn union_find_components<G: Graph>(graph: &G, labels: &mut [u32]) -> usize {
+    let n = graph.node_count();
+    let mut parent: Vec<u32> = (0..n as u32).collect();
+    let mut size = vec![1u32; n];
+
+    for u in 0..n {
+        for v in graph.neighbors(u).filter(|&v| v > u) {
+            let (mut a, mut b) = (find(&mut parent, u), find(&mut parent, v));
+            if a == b {
+                continue;
+            }
+            if size[a] < size[b] {
+                std::mem::swap(&mut a, &mut b);
+            }
+            parent[b] = a as u32;
+            size[a] += size[b];
+        }
+    }
+
+    // In ascending order, the first node of a set labels its root.
+    let mut count = 0;
+    for node in 0..n {
+        let root = find(&mut parent, node);
+        if labels[root] == UNREACHED {
+            labels[root] = node as u32;
+            count += 1;
+        }
+        labels[node] = labels[root];
+    }
+    count
+}
+
+/// Strongly connected components with Tarjan's algorithm, keeping the
+/// suspended calls in a stack of neighbour iterators instead of recursing.
+/// Components are numbered from 0 in the order they are completed, which is
+/// a reverse topological order of the condensation. Returns the number of
+/// components.
+pub fn tarjan_scc<G: Graph>(graph: &G, component: &mut [u32]) -> usize {
+    let n = graph.node_count();
+    let mut index = vec![UNREACHED; n];
+    let mut low = vec![0; n];
+    let mut stack = Vec::new();
+    let mut calls = Vec::new();
+    let mut next_index = 0;
+    let mut count = 0;
+
+    for root in 0..n {
+        if index[root] != UNREACHED {
+            continue;
+        }
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root as u32);
+        calls.push((root, graph.neighbors(root)));
+
+        while let Some((node, neighbors)) = calls.last_mut() {
+            let node = *node;
+            if let Some(next) = neighbors.next() {
+                if index[next] == UNREACHED {
+                    index[next] = next_index;
+                    low[next] = next_index;
+                    next_index += 1;
+                    stack.push(next as u32);
+                    calls.push((next, graph.neighbors(next)));
+                } else if component[next] == UNREACHED {
+                    // Visited but not yet in a component: still on the stack.
+                    low[node] = low[node].min(index[next]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some((caller, _)) = calls.last() {
+                low[*caller] = low[*caller].min(low[node]);
+            }
+            if low[node] == index[node] {
+                while let Some(member) = stack.pop() {
+                    component[member as usize] = count;
+                    if member as usize == node {
+                        break;
+                    }
+                }
+                count += 1;
+            }
+        }
+    }
+    count as usize
+}
+
+/// Checks the reverse topological numbering of [`tarjan_scc`]: no edge
+/// leads to a component completed later. A strongly connected component
+/// split in two would have edges both ways between its halves.
+pub fn validate_scc<G: Graph>(graph: &G, component: &[u32]) -> Result<(), String> {
+    if let Some(node) = component.iter().position(|&c| c == UNREACHED) {
+        return Err(format!("node {} is in no component", node));
+    }
+    for u in 0..graph.node_count() {
+        if let Some(v) = graph.neighbors(u).find(|&v| component[v] > component[u]) {
+            return Err(format!(
+                "edge ({}, {}) leads from component {} to the later component {}",
+                u, v, component[u], component[v]
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// First node labelled differently from `expected`, if any.
+fn compare_labels(labels: &[u32], expected: &[u32]) -> Result<(), String> {
+    match (0..labels.len()).find(|&node| labels[node] != expected[node]) {
+        Some(node) => Err(format!(
+            "node {} is labelled {}, expected {}",
+            node, labels[node] as i32, expected[node]
+        )),
+        None => Ok(()),
+    }
+}
+
+pub struct LabelInput {
+    pub graph: AnyGraph,
+    pub c_graph: CGraph,
+    pub labels: Vec<u32>,
+    pub expected: Vec<u32>,
+}
+
+impl LabelInput {
+    fn new(graph: AnyGraph, expected: Vec<u32>) -> LabelInput {
+        let c_graph = with_graph!(&graph, g => CGraph::new(g));
+        LabelInput {
+            labels: vec![UNREACHED; graph.node_count()],
+            graph,
+            c_graph,
+            expected,
+        }
+    }
+}
+
+/// Connected components by union-find.
+#[derive(Default)]
+pub struct UnionFind {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+}
+
+impl Benchmark for UnionFind {
+    type Input = LabelInput;
+    /// Number of components.
+    type Output = usize;
+
+    fn name(&self) -> &str {
+        "union_find"
+    }
+
+    fn variant(&self) -> String {
+        self.graph.variant()
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.graph.configure(args)
+    }
+
+    /// The expected labels come from the DFS in the `components` benchmark.
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let graph = self.graph.build(size, self.seed);
+        let mut expected = vec![UNREACHED; size];
+        with_graph!(&graph, g => dfs::components::components(g, &mut expected));
+        LabelInput::new(graph, expected)
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> usize {
+        match self.language {
+            Language::C => ffi::union_find_components(&input.c_graph, &mut input.labels),
+            Language::Rust => with_graph!(&input.graph, g => union_find_components(g, &mut input.labels)),
+        }
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.labels.fill(UNREACHED);
+    }
+
+    fn verify(&self, input: &Self::Input, _count: &usize) -> Result<(), String> {
+        compare_labels(&input.labels, &input.expected)
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to label the components of graph of size {}", size)
+    }
+
+    fn summary(&self, count: &usize) -> Option<String> {
+        Some(format!("{} components, {}", count, self.graph.summary()))
+    }
+}
+
+/// Strongly connected components with Tarjan's algorithm, of an undirected
+/// graph (where they are the connected components) or, with `--directed`,
+/// of its arcs.
+#[derive(Default)]
+pub struct Scc {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+}
+
+impl Benchmark for Scc {
+    type Input = LabelInput;
+    /// Number of components.
+    type Output = usize;
+
+    fn name(&self) -> &str {
+        "scc"
+    }
+
+    fn variant(&self) -> String {
+        self.graph.variant()
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE] \
+         [--directed]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.graph.configure_directed(args);
+        self.graph.configure(args)
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let graph = self.graph.build(size, self.seed);
+        let mut expected = vec![UNREACHED; size];
+        with_graph!(&graph, g => tarjan_scc(g, &mut expected));
+        LabelInput::new(graph, expected)
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> usize {
+        match self.language {
+            Language::C => ffi::tarjan_scc(&input.c_graph, &mut input.labels),
+            Language::Rust => with_graph!(&input.graph, g => tarjan_scc(g, &mut input.labels)),
+        }
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.labels.fill(UNREACHED);
+    }
+
+    /// Checks the numbering against the graph, then compares it with the
+    /// one from setup.
+    fn verify(&self, input: &Self::Input, _count: &usize) -> Result<(), String> {
+        with_graph!(&input.graph, g => validate_scc(g, &input.labels))?;
+        compare_labels(&input.labels, &input.expected)
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to find the strongly connected components of graph of size {}", size)
+    }
+
+    fn summary(&self, count: &usize) -> Option<String> {
+        Some(format!("{} components, {}", count, self.graph.summary()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{Csr, FromEdges, Generator, Orientation};
+    use harness::input::Rng;
+
+    fn scc(graph: &Csr) -> (usize, Vec<u32>) {
+        let mut component = vec![UNREACHED; graph.node_count()];
+        let count = tarjan_scc(graph, &mut component);
+        let mut c_component = vec![UNREACHED; graph.node_count()];
+        assert_eq!(ffi::tarjan_scc(&CGraph::new(graph), &mut c_component), count);
+        assert_eq!(c_component, component, "the C labels differ");
+        (count, component)
+    }
+
+    #[test]
+    fn union_find_labels() {
+        // 0-3-5 and 1-4, with 2 and 6 alone.
+        let graph = Csr::from_edges(7, &[(3, 0), (5, 3), (4, 1)]);
+        let expected = [0, 1, 2, 0, 1, 0, 6];
+        let mut labels = vec![UNREACHED; 7];
+        assert_eq!(union_find_components(&graph, &mut labels), 4);
+        assert_eq!(labels, expected);
+        let mut labels = vec![UNREACHED; 7];
+        assert_eq!(ffi::union_find_components(&CGraph::new(&graph), &mut labels), 4);
+        assert_eq!(labels, expected);
+    }
+
+    #[test]
+    fn union_find_matches_dfs() {
+        for (spec, n) in [("er:p=0.002", 1000), ("er:m=300", 500), ("rmat:edge_factor=1", 1024), ("grid2d", 400)] {
+            let generator: Generator = spec.parse().unwrap();
+            let graph = Csr::from_edges(n, &generator.edges(n, &mut Rng::new(5)));
+            let mut expected = vec![UNREACHED; n];
+            let count = dfs::components::components(&graph, &mut expected);
+            let mut labels = vec![UNREACHED; n];
+            assert_eq!(union_find_components(&graph, &mut labels), count, "{spec}");
+            compare_labels(&labels, &expected).unwrap();
+            let mut labels = vec![UNREACHED; n];
+            assert_eq!(ffi::union_find_components(&CGraph::new(&graph), &mut labels), count, "{spec}");
+            compare_labels(&labels, &expected).unwrap();
+        }
+    }
+
+    #[test]
+    fn scc_labels() {
+        // Cycle 0-1-2, then the pair 3-4, then 5 on its own; 6 is apart.
+        let graph = Csr::from_arcs(7, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (4, 5)]);
+        // Completed last to first: 5, then 3 and 4, then the cycle, then 6.
+        assert_eq!(scc(&graph), (4, vec![2, 2, 2, 1, 1, 0, 3]));
+        // Every node on one cycle.
+        let graph = Csr::from_arcs(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(scc(&graph), (1, vec![0; 4]));
+        // A path is all singletons, the deepest first.
+        let graph = Csr::from_arcs(4, &[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(scc(&graph), (4, vec![3, 2, 1, 0]));
+    }
+
+    #[test]
+    fn scc_matches_mutual_reachability() {
+        for (spec, n) in [("er:p=0.01", 300), ("er:p=0.005", 400), ("ba:m=2", 300), ("grid2d", 225)] {
+            let generator: Generator = spec.parse().unwrap();
+            let orientation = Orientation::new(9);
+            let arcs: Vec<_> =
+                generator.edges(n, &mut Rng::new(5)).into_iter().map(|(u, v)| orientation.orient(u, v)).collect();
+            let graph = Csr::from_arcs(n, &arcs);
+            let (count, component) = scc(&graph);
+            validate_scc(&graph, &component).unwrap();
+
+            // Nodes reachable from each node, by DFS.
+            let reach: Vec<Vec<bool>> = (0..n)
+                .map(|source| {
+                    let mut seen = vec![false; n];
+                    let mut stack = vec![source];
+                    seen[source] = true;
+                    while let Some(u) = stack.pop() {
+                        for v in graph.neighbors(u) {
+                            if !std::mem::replace(&mut seen[v], true) {
+                                stack.push(v);
+                            }
+                        }
+                    }
+                    seen
+                })
+                .collect();
+            for u in 0..n {
+                for v in 0..n {
+                    let together = reach[u][v] && reach[v][u];
+                    assert_eq!(component[u] == component[v], together, "{spec}: nodes {u} and {v}");
+                }
+            }
+            let mut labels = component.clone();
+            labels.sort_unstable();
+            labels.dedup();
+            assert_eq!(labels, (0..count as u32).collect::<Vec<_>>(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn validate_scc_rejects_split_and_misordered_components() {
+        let graph = Csr::from_arcs(4, &[(0, 1), (1, 0), (1, 2), (2, 3)]);
+        validate_scc(&graph, &[2, 2, 1, 0]).unwrap();
+        for component in [[2, 3, 1, 0], [2, 2, 0, 1], [2, 2, 1, UNREACHED]] {
+            assert!(validate_scc(&graph, &component).is_err(), "{component:?}");
+        }
+        // Merged components still pass, which is why the tests above check
+        // the exact labels.
+        validate_scc(&graph, &[0, 0, 0, 0]).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+//! Bindings to the C implementations in `*_algo.c`, which take graphs in
+//! compressed sparse row form.
+
+use std::os::raw::{c_double, c_int, c_uint};
+
+use graph::{Graph, Weight};
+
+extern "C" {
+    #[link_name = "dijkstra_heap"]
+    fn c_dijkstra_heap(
+        offsets: *const c_int,
+        targets: *const c_int,
+        weights: *const c_uint,
+        n: c_int,
+        source: c_int,
+        dist: *mut c_uint,
+    ) -> c_int;
+    #[link_name = "dijkstra_heap_f64"]
+    fn c_dijkstra_heap_f64(
+        offsets: *const c_int,
+        targets: *const c_int,
+        weights: *const c_double,
+        n: c_int,
+        source: c_int,
+        dist: *mut c_double,
+    ) -> c_int;
+    #[link_name = "dijkstra_buckets"]
+    fn c_dijkstra_buckets(
+        offsets: *const c_int,
+        targets: *const c_int,
+        weights: *const c_uint,
+        n: c_int,
+        source: c_int,
+        dist: *mut c_uint,
+    ) -> c_int;
+    #[link_name = "bellman_ford"]
+    fn c_bellman_ford(
+        offsets: *const c_int,
+        targets: *const c_int,
+        weights: *const c_uint,
+        n: c_int,
+        source: c_int,
+        dist: *mut c_uint,
+    ) -> c_int;
+    #[link_name = "bellman_ford_f64"]
+    fn c_bellman_ford_f64(
+        offsets: *const c_int,
+        targets: *const c_int,
+        weights: *const c_double,
+        n: c_int,
+        source: c_int,
+        dist: *mut c_double,
+    ) -> c_int;
+    #[link_name = "floyd_warshall"]
+    fn c_floyd_warshall(dist: *const *mut c_uint, n: c_int);
+    #[link_name = "floyd_warshall_f64"]
+    fn c_floyd_warshall_f64(dist: *const *mut c_double, n: c_int);
+    #[link_name = "union_find_components"]
+    fn c_union_find_components(offsets: *const c_int, targets: *const c_int, n: c_int, labels: *mut c_int) -> c_int;
+    #[link_name = "tarjan_scc"]
+    fn c_tarjan_scc(offsets: *const c_int, targets: *const c_int, n: c_int, component: *mut c_int) -> c_int;
+    #[link_name = "topological_sort"]
+    fn c_topological_sort(offsets: *const c_int, targets: *const c_int, n: c_int, order: *mut c_int) -> c_int;
+    #[link_name = "pagerank"]
+    fn c_pagerank(
+        offsets: *const c_int,
+        targets: *const c_int,
+        n: c_int,
+        damping: c_double,
+        iterations: c_int,
+        rank: *mut c_double,
+    ) -> c_int;
+}
+
+/// One of the C single-source searches over `W` weights.
+pub type CSearch<W> = unsafe extern "C" fn(
+    offsets: *const c_int,
+    targets: *const c_int,
+    weights: *const W,
+    n: c_int,
+    source: c_int,
+    dist: *mut W,
+) -> c_int;
+
+/// A weight type the C code has versions for: `unsigned` for `u32`, and
+/// the `_f64` functions for `f64`.
+pub trait CWeight: Weight {
+    const DIJKSTRA_HEAP: CSearch<Self>;
+    const BELLMAN_FORD: CSearch<Self>;
+    const FLOYD_WARSHALL: unsafe extern "C" fn(dist: *const *mut Self, n: c_int);
+}
+
+impl CWeight for u32 {
+    const DIJKSTRA_HEAP: CSearch<u32> = c_dijkstra_heap;
+    const BELLMAN_FORD: CSearch<u32> = c_bellman_ford;
+    const FLOYD_WARSHALL: unsafe extern "C" fn(*const *mut u32, c_int) = c_floyd_warshall;
+}
+
+impl CWeight for f64 {
+    const DIJKSTRA_HEAP: CSearch<f64> = c_dijkstra_heap_f64;
+    const BELLMAN_FORD: CSearch<f64> = c_bellman_ford_f64;
+    const FLOYD_WARSHALL: unsafe extern "C" fn(*const *mut f64, c_int) = c_floyd_warshall_f64;
+}
+
+/// A graph as the C code takes it: the neighbours of node `i` are
+/// `targets[offsets[i]..offsets[i + 1]]`, all as ints.
+pub struct CGraph {
+    offsets: Vec<c_int>,
+    targets: Vec<c_int>,
+}
+
+impl CGraph {
+    /// Copies any representation into the C layout.
+    pub fn new<G: Graph>(graph: &G) -> CGraph {
+        let n = graph.node_count();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut targets = Vec::with_capacity(graph.edge_count());
+        offsets.push(0);
+        for node in 0..n {
+            targets.extend(graph.neighbors(node).map(|i| i as c_int));
+            offsets.push(c_int::try_from(targets.len()).expect("graph too large for the C implementation"));
+        }
+        CGraph { offsets, targets }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn n(&self) -> c_int {
+        self.node_count() as c_int
+    }
+}
+
+/// Calls one of the C single-source searches from `source`, with the
+/// weights in the order of `graph`'s targets.
+fn search<W>(search_fn: CSearch<W>, graph: &CGraph, weights: &[W], source: usize, dist: &mut [W]) -> usize {
+    assert!(source < graph.node_count() && dist.len() == graph.node_count());
+    assert_eq!(weights.len(), graph.targets.len());
+    // SAFETY: the CSR arrays are consistent, as built by `CGraph::new`,
+    // there is a weight per target and `dist` holds an entry for every
+    // node. `u32` and `f64` are `unsigned` and `double`, and
+    // `Weight::INFINITY` is the C code's unreached distance.
+    let result = unsafe {
+        search_fn(
+            graph.offsets.as_ptr(),
+            graph.targets.as_ptr(),
+            weights.as_ptr(),
+            graph.n(),
+            source as c_int,
+            dist.as_mut_ptr(),
+        )
+    };
+    assert!(result >= 0, "the C search could not allocate its queue");
+    result as usize
+}
+
+/// Runs the C `dijkstra_heap`; `dist` must be all `W::INFINITY`.
+pub fn dijkstra_heap<W: CWeight>(graph: &CGraph, weights: &[W], source: usize, dist: &mut [W]) -> usize {
+    search(W::DIJKSTRA_HEAP, graph, weights, source, dist)
+}
+
+/// Runs the C `dijkstra_buckets`, which has only an integer version; `dist`
+/// must be all UNREACHED.
+pub fn dijkstra_buckets(graph: &CGraph, weights: &[u32], source: usize, dist: &mut [u32]) -> usize {
+    search(c_dijkstra_buckets, graph, weights, source, dist)
+}
+
+/// Runs the C `bellman_ford`; `dist` must be all `W::INFINITY`.
+pub fn bellman_ford<W: CWeight>(graph: &CGraph, weights: &[W], source: usize, dist: &mut [W]) -> usize {
+    search(W::BELLMAN_FORD, graph, weights, source, dist)
+}
+
+/// Runs the C `floyd_warshall` over the `n` x `n` matrix `dist`, stored row
+/// by row. It takes an `unsigned**` or `double**`, so a table of row
+/// pointers is built first.
+pub fn floyd_warshall<W: CWeight>(dist: &mut [W], n: usize) {
+    assert_eq!(dist.len(), n * n);
+    let rows: Vec<*mut W> = dist.chunks_exact_mut(n.max(1)).map(|row| row.as_mut_ptr()).collect();
+    let n = c_int::try_from(n).expect("matrix too large for the C implementation");
+    // SAFETY: each of the n row pointers points to n entries of `dist`.
+    unsafe { W::FLOYD_WARSHALL(rows.as_ptr(), n) }
+}
+
+/// Runs the C `union_find_components`; `labels` must be all UNREACHED,
+/// which is -1 as an int.
+pub fn union_find_components(graph: &CGraph, labels: &mut [u32]) -> usize {
+    assert_eq!(labels.len(), graph.node_count());
+    // SAFETY: consistent CSR arrays and an entry of `labels` per node.
+    let count = unsafe {
+        c_union_find_components(graph.offsets.as_ptr(), graph.targets.as_ptr(), graph.n(), labels.as_mut_ptr().cast())
+    };
+    assert!(count >= 0, "the C union-find could not allocate its forest");
+    count as usize
+}
+
+/// Runs the C `tarjan_scc`; `component` must be all UNREACHED.
+pub fn tarjan_scc(graph: &CGraph, component: &mut [u32]) -> usize {
+    assert_eq!(component.len(), graph.node_count());
+    // SAFETY: consistent CSR arrays and an entry of `component` per node.
+    let count = unsafe {
+        c_tarjan_scc(graph.offsets.as_ptr(), graph.targets.as_ptr(), graph.n(), component.as_mut_ptr().cast())
+    };
+    assert!(count >= 0, "the C Tarjan could not allocate its stacks");
+    count as usize
+}
+
+/// Runs the C `topological_sort`, replacing `order` with the sorted nodes.
+pub fn topological_sort(graph: &CGraph, order: &mut Vec<u32>) -> usize {
+    order.clear();
+    order.reserve(graph.node_count());
+    // SAFETY: consistent CSR arrays, and room for n entries in `order`, of
+    // which the C code writes the first `sorted`.
+    unsafe {
+        let sorted = c_topological_sort(graph.offsets.as_ptr(), graph.targets.as_ptr(), graph.n(), order.as_mut_ptr().cast());
+        assert!(sorted >= 0, "the C topological sort could not allocate");
+        order.set_len(sorted as usize);
+    }
+    order.len()
+}
+
+/// Runs the C `pagerank`, filling in `rank`.
+pub fn pagerank(graph: &CGraph, damping: f64, iterations: usize, rank: &mut [f64]) {
+    assert_eq!(rank.len(), graph.node_count());
+    let iterations = c_int::try_from(iterations).expect("too many iterations for the C implementation");
+    // SAFETY: consistent CSR arrays and an entry of `rank` per node.
+    let result = unsafe {
+        c_pagerank(graph.offsets.as_ptr(), graph.targets.as_ptr(), graph.n(), damping, iterations, rank.as_mut_ptr())
+    };
+    assert!(result == 0, "the C PageRank could not allocate");
+}
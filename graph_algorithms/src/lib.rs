@@ -0,0 +1,16 @@
+//! Graph algorithms beyond BFS and DFS, over the same generated or loaded
+//! graphs and representations: shortest paths, connected and strongly
+//! connected components, topological sort and PageRank. Each has a C
+//! counterpart in one of the `*_algo.c` files, which takes the graph as
+//! CSR arrays copied from the representation at setup.
+
+pub mod components;
+pub mod ffi;
+pub mod pagerank;
+pub mod shortest_path;
+pub mod toposort;
+
+pub use components::{Scc, UnionFind};
+pub use pagerank::Pagerank;
+pub use shortest_path::{FloydWarshall, ShortestPath};
+pub use toposort::Toposort;
@@ -0,0 +1,248 @@
+//! PageRank by power iteration.
+
+use graph::{with_graph, AnyGraph, Graph, GraphOptions};
+use harness::{Args, Benchmark, Language};
+
+use crate::ffi::{self, CGraph};
+
+/// Largest difference from the setup ranks a run may show. Both languages
+/// add up the same terms in the same order, so they should agree exactly.
+const TOLERANCE: f64 = 1e-12;
+
+/// Runs `iterations` steps of PageRank with the given damping factor,
+/// starting from the uniform distribution. Every step pulls rank over the
+/// edges of the undirected graph, and the rank of nodes without neighbours
+/// is spread over all nodes, so the ranks always sum to 1.
+pub fn pagerank<G: Graph>(graph: &G, damping: f64, iterations: usize, rank: &mut [f64]) {
+    let n = graph.node_count();
+    let mut contribution = vec![0.0; n];
+    rank.fill(1.0 / n as f64);
+
+    for _ in 0..iterations {
+        let mut dangling = 0.0;
+        for u in 0..n {
+            match graph.degree(u) {
+                0 => {
+                    dangling += rank[u];
+                    contribution[u] = 0.0;
+                }
+                degree => contribution[u] = rank[u] / degree as f64,
+            }
+        }
+        let base = (1.0 - damping + damping * dangling) / n as f64;
+        for (v, rank) in rank.iter_mut().enumerate() {
+            let mut sum = 0.0;
+            for u in graph.neighbors(v) {
+                sum += contribution[u];
+            }
+            *rank = base + damping * sum;
+        }
+    }
+}
+
+pub struct PagerankInput {
+    pub graph: AnyGraph,
+    pub c_graph: CGraph,
+    pub rank: Vec<f64>,
+    /// Ranks from the Rust version at setup.
+    pub expected: Vec<f64>,
+}
+
+pub struct Pagerank {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+    damping: f64,
+    iterations: usize,
+    /// Highest-ranked node and its rank, from setup.
+    top: (usize, f64),
+}
+
+impl Default for Pagerank {
+    fn default() -> Self {
+        Pagerank {
+            language: Language::default(),
+            seed: 0,
+            graph: GraphOptions::default(),
+            damping: 0.85,
+            iterations: 20,
+            top: (0, 0.0),
+        }
+    }
+}
+
+impl Benchmark for Pagerank {
+    type Input = PagerankInput;
+    type Output = ();
+
+    fn name(&self) -> &str {
+        "pagerank"
+    }
+
+    fn variant(&self) -> String {
+        format!("{}/damping={},iterations={}", self.graph.variant(), self.damping, self.iterations)
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE] \
+         [--damping D] [--iterations N]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        if let Some(damping) = args.parse::<f64>("--damping")? {
+            if !(0.0..=1.0).contains(&damping) {
+                return Err(format!("--damping must be between 0 and 1, not {}", damping));
+            }
+            self.damping = damping;
+        }
+        self.iterations = args.parse("--iterations")?.unwrap_or(self.iterations);
+        self.graph.configure(args)
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let graph = self.graph.build(size, self.seed);
+        let c_graph = with_graph!(&graph, g => CGraph::new(g));
+        let mut expected = vec![0.0; size];
+        with_graph!(&graph, g => pagerank(g, self.damping, self.iterations, &mut expected));
+        self.top = expected
+            .iter()
+            .copied()
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |top, (node, rank)| if rank > top.1 { (node, rank) } else { top });
+        PagerankInput {
+            graph,
+            c_graph,
+            rank: vec![0.0; size],
+            expected,
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) {
+        match self.language {
+            Language::C => ffi::pagerank(&input.c_graph, self.damping, self.iterations, &mut input.rank),
+            Language::Rust => {
+                with_graph!(&input.graph, g => pagerank(g, self.damping, self.iterations, &mut input.rank))
+            }
+        }
+    }
+
+    /// `pagerank` starts from scratch every time.
+    fn reset(&mut self, _input: &mut Self::Input, _size: usize) {}
+
+    /// The ranks must sum to 1 and match the ones from setup.
+    fn verify(&self, input: &Self::Input, _output: &()) -> Result<(), String> {
+        let total: f64 = input.rank.iter().sum();
+        if !input.rank.is_empty() && (total - 1.0).abs() > 1e-6 {
+            return Err(format!("the ranks sum to {}", total));
+        }
+        match (0..input.rank.len()).find(|&i| (input.rank[i] - input.expected[i]).abs() > TOLERANCE) {
+            Some(i) => Err(format!("node {} has rank {}, expected {}", i, input.rank[i], input.expected[i])),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to rank graph of size {}", size)
+    }
+
+    fn summary(&self, _output: &()) -> Option<String> {
+        Some(format!(
+            "node {} ranks highest ({:.6}), {}",
+            self.top.0,
+            self.top.1,
+            self.graph.summary()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{Csr, FromEdges, Generator};
+    use harness::input::Rng;
+
+    /// Ranks after `iterations` steps, the same in both languages.
+    fn ranks(graph: &Csr, damping: f64, iterations: usize) -> Vec<f64> {
+        let n = graph.node_count();
+        let mut rank = vec![0.0; n];
+        pagerank(graph, damping, iterations, &mut rank);
+        let mut c_rank = vec![0.0; n];
+        ffi::pagerank(&CGraph::new(graph), damping, iterations, &mut c_rank);
+        assert!(rank.iter().zip(&c_rank).all(|(a, b)| (a - b).abs() <= TOLERANCE), "the C ranks differ");
+        rank
+    }
+
+    fn assert_close(rank: &[f64], expected: &[f64]) {
+        assert!(rank.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-9), "{rank:?}, expected {expected:?}");
+    }
+
+    #[test]
+    fn starts_uniform() {
+        let graph = Csr::from_edges(4, &[(0, 1)]);
+        assert_eq!(ranks(&graph, 0.85, 0), [0.25; 4]);
+    }
+
+    #[test]
+    fn regular_graph_stays_uniform() {
+        let cycle = Csr::from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        assert_close(&ranks(&cycle, 0.85, 20), &[0.2; 5]);
+    }
+
+    #[test]
+    fn star_converges_to_known_ranks() {
+        // Centre c and leaves l: c = (1 - d) / 4 + 3d * l and
+        // l = (1 - d) / 4 + d * c / 3, so c = 0.133125 / 0.2775 for d = 0.85.
+        let star = Csr::from_edges(4, &[(0, 1), (0, 2), (0, 3)]);
+        let centre = 0.133125 / 0.2775;
+        let leaf = (1.0 - centre) / 3.0;
+        assert_close(&ranks(&star, 0.85, 300), &[centre, leaf, leaf, leaf]);
+        // Without damping every node gets the teleport share alone.
+        assert_close(&ranks(&star, 0.0, 5), &[0.25; 4]);
+    }
+
+    #[test]
+    fn isolated_nodes_share_their_rank() {
+        // 2 and 3 have no neighbours, so their rank goes to everyone.
+        let graph = Csr::from_edges(4, &[(0, 1)]);
+        let rank = ranks(&graph, 0.85, 50);
+        assert_close(&[rank.iter().sum()], &[1.0]);
+        assert_close(&[rank[1], rank[3]], &[rank[0], rank[2]]);
+        assert!(rank[0] > rank[2]);
+    }
+
+    #[test]
+    fn ranks_sum_to_one() {
+        for (spec, n) in [("er:p=0.002", 1000), ("ba:m=2", 500), ("rmat", 256), ("grid2d", 300)] {
+            let generator: Generator = spec.parse().unwrap();
+            let graph = Csr::from_edges(n, &generator.edges(n, &mut Rng::new(6)));
+            let rank = ranks(&graph, 0.85, 20);
+            assert!(rank.iter().all(|&r| r > 0.0), "{spec}");
+            assert!((rank.iter().sum::<f64>() - 1.0).abs() < 1e-9, "{spec}");
+        }
+    }
+}
@@ -0,0 +1,755 @@
+//! Shortest paths: Dijkstra with a binary heap or a bucket queue and
+//! Bellman-Ford from one source, and Floyd-Warshall between all pairs.
+//!
+//! Edge lengths are the graph's [`Weights`], integer or float; graphs
+//! without weights have length 1 on every edge. Distances are of the same
+//! type, with [`Weight::INFINITY`] (`UINT_MAX` or `INFINITY` in C) for
+//! nodes that cannot be reached.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::process::exit;
+use std::str::FromStr;
+
+use graph::{with_graph, AnyGraph, AnyWeights, Graph, GraphOptions, Weight, Weights};
+use harness::{Args, Benchmark, Language};
+
+use crate::ffi::{self, CGraph, CWeight};
+
+/// Longest edge [`dijkstra_buckets`] takes, as it allocates a bucket for
+/// every length up to it.
+pub const MAX_BUCKET_WEIGHT: u32 = 1 << 20;
+
+/// Dijkstra's algorithm with a binary heap. A node is pushed again
+/// whenever its distance drops, and the stale entries are skipped when
+/// popped. `dist` must be all `W::INFINITY`; returns the number of nodes
+/// reached.
+pub fn dijkstra<G: Graph, W: Weight>(graph: &G, weights: &Weights<W>, source: usize, dist: &mut [W]) -> usize {
+    let mut heap = BinaryHeap::new();
+    let mut reached = 0;
+    dist[source] = W::ZERO;
+    heap.push(Reverse((W::ZERO.key(), source as u32)));
+
+    while let Some(Reverse((key, node))) = heap.pop() {
+        let node = node as usize;
+        let d = dist[node];
+        if key > d.key() {
+            continue;
+        }
+        reached += 1;
+        for (i, &length) in graph.neighbors(node).zip(weights.of(node)) {
+            let through = d.plus(length);
+            if through < dist[i] {
+                dist[i] = through;
+                heap.push(Reverse((through.key(), i as u32)));
+            }
+        }
+    }
+    reached
+}
+
+/// Dijkstra's algorithm with Dial's bucket queue: a ring of one bucket per
+/// distance, as many as the longest edge plus one, emptied in order. Only
+/// for integer weights, and memory grows with the longest edge.
+pub fn dijkstra_buckets<G: Graph>(graph: &G, weights: &Weights<u32>, source: usize, dist: &mut [u32]) -> usize {
+    let count = weights.max() as usize + 1;
+    let mut buckets = vec![Vec::new(); count];
+    let mut queued = 1;
+    let mut reached = 0;
+    dist[source] = 0;
+    buckets[0].push(source as u32);
+
+    let mut d: u32 = 0;
+    while queued > 0 {
+        let slot = d as usize % count;
+        // Edges of length 0 add to this bucket while it is emptied, so it
+        // is walked by index.
+        let mut b = 0;
+        while b < buckets[slot].len() {
+            let node = buckets[slot][b] as usize;
+            b += 1;
+            queued -= 1;
+            if dist[node] != d {
+                continue;
+            }
+            reached += 1;
+            for (i, &length) in graph.neighbors(node).zip(weights.of(node)) {
+                let through = d.plus(length);
+                if through < dist[i] {
+                    dist[i] = through;
+                    buckets[through as usize % count].push(i as u32);
+                    queued += 1;
+                }
+            }
+        }
+        buckets[slot].clear();
+        d += 1;
+    }
+    reached
+}
+
+/// Bellman-Ford: relaxes every edge, node by node, in rounds until a round
+/// changes nothing. Returns the number of rounds.
+pub fn bellman_ford<G: Graph, W: Weight>(graph: &G, weights: &Weights<W>, source: usize, dist: &mut [W]) -> usize {
+    let n = graph.node_count();
+    dist[source] = W::ZERO;
+    let mut rounds = 0;
+    let mut changed = true;
+    while changed && rounds < n {
+        changed = false;
+        rounds += 1;
+        for u in 0..n {
+            if dist[u] == W::INFINITY {
+                continue;
+            }
+            for (i, &length) in graph.neighbors(u).zip(weights.of(u)) {
+                let through = dist[u].plus(length);
+                if through < dist[i] {
+                    dist[i] = through;
+                    changed = true;
+                }
+            }
+        }
+    }
+    rounds
+}
+
+/// Fills `dist` with the `n` x `n` matrix Floyd-Warshall starts from, row
+/// by row: 0 on the diagonal, the edge lengths, and `W::INFINITY` for
+/// missing edges.
+pub fn distance_matrix<G: Graph, W: Weight>(graph: &G, weights: &Weights<W>, dist: &mut [W]) {
+    let n = graph.node_count();
+    dist.fill(W::INFINITY);
+    for (u, row) in dist.chunks_exact_mut(n.max(1)).enumerate() {
+        for (i, &length) in graph.neighbors(u).zip(weights.of(u)) {
+            row[i] = length;
+        }
+        row[u] = W::ZERO;
+    }
+}
+
+/// Floyd-Warshall over the `n` x `n` matrix from [`distance_matrix`],
+/// leaving the distance from `i` to `j` at `dist[i * n + j]`. Row `k` is
+/// copied out for each `k`, so the inner loop reads one row and writes
+/// another and can be vectorised.
+pub fn floyd_warshall<W: Weight>(dist: &mut [W], n: usize) {
+    let mut row_k = vec![W::ZERO; n];
+    for k in 0..n {
+        row_k.copy_from_slice(&dist[k * n..(k + 1) * n]);
+        for row in dist.chunks_exact_mut(n) {
+            let dik = row[k];
+            if dik == W::INFINITY {
+                continue;
+            }
+            for (d, &dkj) in row.iter_mut().zip(&row_k) {
+                let through = dik.plus(dkj);
+                if through < *d {
+                    *d = through;
+                }
+            }
+        }
+    }
+}
+
+/// Checks that `dist` holds the shortest distances from `source`: it is 0
+/// at the source, no edge offers a shortcut, and every other reached node
+/// is reached over an edge whose length accounts exactly for its
+/// distance.
+pub fn validate_distances<G: Graph, W: Weight>(
+    graph: &G,
+    weights: &Weights<W>,
+    source: usize,
+    dist: &[W],
+) -> Result<(), String> {
+    let n = graph.node_count();
+    if dist.len() != n {
+        return Err(format!("result covers {} nodes, the graph has {}", dist.len(), n));
+    }
+    if dist[source] != W::ZERO {
+        return Err(format!("source {} is at distance {}", source, dist[source]));
+    }
+    let mut tight = vec![false; n];
+    tight[source] = true;
+    for u in (0..n).filter(|&u| dist[u] != W::INFINITY) {
+        for (i, &length) in graph.neighbors(u).zip(weights.of(u)) {
+            let through = dist[u].plus(length);
+            if through < dist[i] {
+                return Err(format!(
+                    "node {} is at distance {}, but its neighbour {} at distance {} is closer",
+                    i,
+                    show(dist[i]),
+                    u,
+                    dist[u]
+                ));
+            }
+            tight[i] |= through == dist[i];
+        }
+    }
+    match (0..n).find(|&i| dist[i] != W::INFINITY && !tight[i]) {
+        Some(i) => Err(format!("no path of length {} leads to node {}", dist[i], i)),
+        None => Ok(()),
+    }
+}
+
+/// A distance for an error message.
+fn show<W: Weight>(d: W) -> String {
+    if d == W::INFINITY {
+        "unreached".to_string()
+    } else {
+        d.to_string()
+    }
+}
+
+/// Single-source algorithm, from `--algo`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// [`dijkstra`].
+    #[default]
+    DijkstraHeap,
+    /// [`dijkstra_buckets`].
+    DijkstraBuckets,
+    /// [`bellman_ford`].
+    BellmanFord,
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::DijkstraHeap => write!(f, "dijkstra-heap"),
+            Algorithm::DijkstraBuckets => write!(f, "dijkstra-buckets"),
+            Algorithm::BellmanFord => write!(f, "bellman-ford"),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dijkstra-heap" => Ok(Algorithm::DijkstraHeap),
+            "dijkstra-buckets" => Ok(Algorithm::DijkstraBuckets),
+            "bellman-ford" => Ok(Algorithm::BellmanFord),
+            _ => Err(format!(
+                "unknown algorithm: {s} (expected dijkstra-heap, dijkstra-buckets or bellman-ford)"
+            )),
+        }
+    }
+}
+
+/// Weights and distances of one weight type.
+pub struct Paths<W> {
+    pub weights: Weights<W>,
+    pub dist: Vec<W>,
+    /// Distances from the Rust Dijkstra at setup.
+    pub expected: Vec<W>,
+}
+
+impl<W: Weight> Paths<W> {
+    fn new(graph: &AnyGraph, weights: Weights<W>) -> Paths<W> {
+        let n = graph.node_count();
+        let mut expected = vec![W::INFINITY; n];
+        with_graph!(graph, g => dijkstra(g, &weights, 0, &mut expected));
+        Paths {
+            weights,
+            dist: vec![W::INFINITY; n],
+            expected,
+        }
+    }
+
+    /// Reached nodes and the largest distance among them.
+    fn extent(&self) -> (usize, String) {
+        let reached = self.expected.iter().filter(|&&d| d != W::INFINITY);
+        let farthest = reached.clone().fold(W::ZERO, |max, &d| if d > max { d } else { max });
+        // Integers ignore the precision.
+        (reached.count(), format!("{:.3}", farthest))
+    }
+
+    fn verify(&self, graph: &AnyGraph) -> Result<(), String> {
+        with_graph!(graph, g => validate_distances(g, &self.weights, 0, &self.dist))?;
+        compare_distances(&self.dist, &self.expected)
+    }
+}
+
+/// [`Paths`] with integer or float weights.
+pub enum AnyPaths {
+    Int(Paths<u32>),
+    Float(Paths<f64>),
+}
+
+pub struct ShortestPathInput {
+    pub graph: AnyGraph,
+    pub c_graph: CGraph,
+    pub paths: AnyPaths,
+}
+
+/// Shortest paths from node 0.
+#[derive(Default)]
+pub struct ShortestPath {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+    algorithm: Algorithm,
+    /// Reached nodes and the largest distance, from setup.
+    reached: usize,
+    farthest: String,
+}
+
+impl ShortestPath {
+    /// Runs the heap Dijkstra or Bellman-Ford, which take either weight
+    /// type.
+    fn search<W: CWeight>(&self, graph: &AnyGraph, c_graph: &CGraph, paths: &mut Paths<W>) -> usize {
+        let (weights, dist) = (&paths.weights, &mut paths.dist);
+        match (self.language, self.algorithm) {
+            (_, Algorithm::DijkstraBuckets) => unreachable!("dijkstra-buckets takes integer weights only"),
+            (Language::C, Algorithm::DijkstraHeap) => ffi::dijkstra_heap(c_graph, weights.values(), 0, dist),
+            (Language::C, Algorithm::BellmanFord) => ffi::bellman_ford(c_graph, weights.values(), 0, dist),
+            (Language::Rust, Algorithm::DijkstraHeap) => with_graph!(graph, g => dijkstra(g, weights, 0, dist)),
+            (Language::Rust, Algorithm::BellmanFord) => with_graph!(graph, g => bellman_ford(g, weights, 0, dist)),
+        }
+    }
+}
+
+impl Benchmark for ShortestPath {
+    type Input = ShortestPathInput;
+    /// Nodes reached, or rounds for Bellman-Ford.
+    type Output = usize;
+
+    fn name(&self) -> &str {
+        "shortest_path"
+    }
+
+    fn variant(&self) -> String {
+        format!("{}/{}", self.graph.variant(), self.algorithm)
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The C versions take any representation, copied to CSR arrays.
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE] \
+         [--directed] [--weights int|float[:dist=uniform|exp|normal,param=value,...]] \
+         [--algo dijkstra-heap|dijkstra-buckets|bellman-ford]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.algorithm = args.parse("--algo")?.unwrap_or_default();
+        self.graph.configure_directed(args);
+        self.graph.configure_weights(args)?;
+        self.graph.configure(args)
+    }
+
+    /// Exits with an error for dijkstra-buckets on float weights or on
+    /// edges longer than [`MAX_BUCKET_WEIGHT`].
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let (graph, weights) = self.graph.build_weighted(size, self.seed);
+        let paths = match weights {
+            AnyWeights::Float(_) if self.algorithm == Algorithm::DijkstraBuckets => {
+                eprintln!("Error: dijkstra-buckets needs integer weights");
+                exit(1);
+            }
+            AnyWeights::Int(weights)
+                if self.algorithm == Algorithm::DijkstraBuckets && weights.max() > MAX_BUCKET_WEIGHT =>
+            {
+                eprintln!(
+                    "Error: dijkstra-buckets takes weights up to {}, got {}",
+                    MAX_BUCKET_WEIGHT,
+                    weights.max()
+                );
+                exit(1);
+            }
+            AnyWeights::Int(weights) => AnyPaths::Int(Paths::new(&graph, weights)),
+            AnyWeights::Float(weights) => AnyPaths::Float(Paths::new(&graph, weights)),
+        };
+        (self.reached, self.farthest) = match &paths {
+            AnyPaths::Int(paths) => paths.extent(),
+            AnyPaths::Float(paths) => paths.extent(),
+        };
+        let c_graph = with_graph!(&graph, g => CGraph::new(g));
+        ShortestPathInput { graph, c_graph, paths }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> usize {
+        let (graph, c_graph) = (&input.graph, &input.c_graph);
+        match &mut input.paths {
+            AnyPaths::Int(paths) if self.algorithm == Algorithm::DijkstraBuckets => match self.language {
+                Language::C => ffi::dijkstra_buckets(c_graph, paths.weights.values(), 0, &mut paths.dist),
+                Language::Rust => with_graph!(graph, g => dijkstra_buckets(g, &paths.weights, 0, &mut paths.dist)),
+            },
+            AnyPaths::Int(paths) => self.search(graph, c_graph, paths),
+            AnyPaths::Float(paths) => self.search(graph, c_graph, paths),
+        }
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        match &mut input.paths {
+            AnyPaths::Int(paths) => paths.dist.fill(u32::INFINITY),
+            AnyPaths::Float(paths) => paths.dist.fill(f64::INFINITY),
+        }
+    }
+
+    /// Checks the distances against the graph, then compares them with the
+    /// ones from setup.
+    fn verify(&self, input: &Self::Input, _output: &usize) -> Result<(), String> {
+        match &input.paths {
+            AnyPaths::Int(paths) => paths.verify(&input.graph),
+            AnyPaths::Float(paths) => paths.verify(&input.graph),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to find the shortest paths in graph of size {}", size)
+    }
+
+    fn summary(&self, output: &usize) -> Option<String> {
+        let rounds = match self.algorithm {
+            Algorithm::BellmanFord => format!("{} rounds, ", output),
+            _ => String::new(),
+        };
+        Some(format!(
+            "{}{} nodes reached, farthest at distance {}, {}",
+            rounds,
+            self.reached,
+            self.farthest,
+            self.graph.summary()
+        ))
+    }
+}
+
+/// First difference from `expected`, if any. Float distances may differ in
+/// the last bits, as paths of equal length can add up in another order.
+fn compare_distances<W: Weight>(dist: &[W], expected: &[W]) -> Result<(), String> {
+    match (0..dist.len()).find(|&i| !dist[i].close(expected[i])) {
+        Some(i) => Err(format!(
+            "node {} is at distance {}, expected {}",
+            i,
+            show(dist[i]),
+            show(expected[i])
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Rows of the Floyd-Warshall result checked against Dijkstra after a run.
+const CHECKED_ROWS: usize = 8;
+
+/// Weights and the distance matrix, row by row, of one weight type.
+pub struct AllPairs<W> {
+    pub weights: Weights<W>,
+    pub dist: Vec<W>,
+}
+
+impl<W: CWeight> AllPairs<W> {
+    fn new(graph: &AnyGraph, weights: Weights<W>) -> AllPairs<W> {
+        let n = graph.node_count();
+        let mut dist = vec![W::INFINITY; n * n];
+        with_graph!(graph, g => distance_matrix(g, &weights, &mut dist));
+        AllPairs { weights, dist }
+    }
+
+    fn run(&mut self, language: Language, n: usize) {
+        match language {
+            Language::C => ffi::floyd_warshall(&mut self.dist, n),
+            Language::Rust => floyd_warshall(&mut self.dist, n),
+        }
+    }
+
+    fn reset(&mut self, graph: &AnyGraph) {
+        with_graph!(graph, g => distance_matrix(g, &self.weights, &mut self.dist));
+    }
+
+    /// Compares a few evenly spaced rows with Dijkstra from those nodes; all
+    /// of them would take longer than Floyd-Warshall itself on sparse
+    /// graphs.
+    fn verify(&self, graph: &AnyGraph) -> Result<(), String> {
+        let n = graph.node_count();
+        let mut expected = vec![W::INFINITY; n];
+        for source in (0..CHECKED_ROWS.min(n)).map(|i| i * n / CHECKED_ROWS.min(n)) {
+            expected.fill(W::INFINITY);
+            with_graph!(graph, g => dijkstra(g, &self.weights, source, &mut expected));
+            compare_distances(&self.dist[source * n..(source + 1) * n], &expected)
+                .map_err(|e| format!("from node {}: {}", source, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// [`AllPairs`] with integer or float weights.
+pub enum AnyAllPairs {
+    Int(AllPairs<u32>),
+    Float(AllPairs<f64>),
+}
+
+pub struct FloydWarshallInput {
+    pub graph: AnyGraph,
+    pub all_pairs: AnyAllPairs,
+}
+
+/// All-pairs shortest paths with Floyd-Warshall.
+#[derive(Default)]
+pub struct FloydWarshall {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+}
+
+impl Benchmark for FloydWarshall {
+    type Input = FloydWarshallInput;
+    type Output = ();
+
+    fn name(&self) -> &str {
+        "floyd_warshall"
+    }
+
+    fn variant(&self) -> String {
+        self.graph.variant()
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE] \
+         [--directed] [--weights int|float[:dist=uniform|exp|normal,param=value,...]]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.graph.configure_directed(args);
+        self.graph.configure_weights(args)?;
+        self.graph.configure(args)
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let (graph, weights) = self.graph.build_weighted(size, self.seed);
+        let all_pairs = match weights {
+            AnyWeights::Int(weights) => AnyAllPairs::Int(AllPairs::new(&graph, weights)),
+            AnyWeights::Float(weights) => AnyAllPairs::Float(AllPairs::new(&graph, weights)),
+        };
+        FloydWarshallInput { graph, all_pairs }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) {
+        let n = input.graph.node_count();
+        match &mut input.all_pairs {
+            AnyAllPairs::Int(all_pairs) => all_pairs.run(self.language, n),
+            AnyAllPairs::Float(all_pairs) => all_pairs.run(self.language, n),
+        }
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        match &mut input.all_pairs {
+            AnyAllPairs::Int(all_pairs) => all_pairs.reset(&input.graph),
+            AnyAllPairs::Float(all_pairs) => all_pairs.reset(&input.graph),
+        }
+    }
+
+    fn verify(&self, input: &Self::Input, _output: &()) -> Result<(), String> {
+        match &input.all_pairs {
+            AnyAllPairs::Int(all_pairs) => all_pairs.verify(&input.graph),
+            AnyAllPairs::Float(all_pairs) => all_pairs.verify(&input.graph),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to find all shortest paths in graph of size {}", size)
+    }
+
+    fn summary(&self, _output: &()) -> Option<String> {
+        Some(self.graph.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{Csr, FromEdges, Generator, Orientation};
+    use harness::input::Rng;
+
+    const INF: u32 = u32::INFINITY;
+
+    fn weighted<W: Weight>(n: usize, arcs: &[(u32, u32, W)]) -> (Csr, Weights<W>) {
+        let graph = Csr::from_arcs(n, &arcs.iter().map(|&(u, v, _)| (u, v)).collect::<Vec<_>>());
+        let weights = Weights::from_triples(&graph, arcs.to_vec(), false);
+        (graph, weights)
+    }
+
+    /// Distances from `source` by every algorithm that takes `W`, in both
+    /// languages, with Floyd-Warshall's row for `source`.
+    fn all_distances<W: CWeight>(graph: &Csr, weights: &Weights<W>, source: usize) -> Vec<(&'static str, Vec<W>)> {
+        let n = graph.node_count();
+        let c_graph = CGraph::new(graph);
+        let run = |search: &dyn Fn(&mut [W]) -> usize| {
+            let mut dist = vec![W::INFINITY; n];
+            search(&mut dist);
+            dist
+        };
+        let mut results = vec![
+            ("dijkstra", run(&|dist| dijkstra(graph, weights, source, dist))),
+            ("bellman-ford", run(&|dist| bellman_ford(graph, weights, source, dist))),
+            ("C dijkstra", run(&|dist| ffi::dijkstra_heap(&c_graph, weights.values(), source, dist))),
+            ("C bellman-ford", run(&|dist| ffi::bellman_ford(&c_graph, weights.values(), source, dist))),
+        ];
+
+        let mut matrix = vec![W::ZERO; n * n];
+        distance_matrix(graph, weights, &mut matrix);
+        let mut c_matrix = matrix.clone();
+        floyd_warshall(&mut matrix, n);
+        ffi::floyd_warshall(&mut c_matrix, n);
+        results.push(("floyd-warshall", matrix[source * n..(source + 1) * n].to_vec()));
+        results.push(("C floyd-warshall", c_matrix[source * n..(source + 1) * n].to_vec()));
+        results
+    }
+
+    /// [`all_distances`] and the bucket queues in both languages.
+    fn all_int_distances(graph: &Csr, weights: &Weights<u32>, source: usize) -> Vec<(&'static str, Vec<u32>)> {
+        let n = graph.node_count();
+        let mut results = all_distances(graph, weights, source);
+        let mut dist = vec![INF; n];
+        let reached = dijkstra_buckets(graph, weights, source, &mut dist);
+        assert_eq!(reached, dist.iter().filter(|&&d| d != INF).count());
+        results.push(("dijkstra-buckets", dist));
+        let mut dist = vec![INF; n];
+        ffi::dijkstra_buckets(&CGraph::new(graph), weights.values(), source, &mut dist);
+        results.push(("C dijkstra-buckets", dist));
+        results
+    }
+
+    #[test]
+    fn known_distances() {
+        // 5 leads into the graph but cannot be reached from 0.
+        let arcs = [(0, 1, 4), (0, 2, 1), (2, 1, 2), (1, 3, 1), (2, 3, 5), (3, 4, 3), (5, 4, 1), (4, 0, 7)];
+        let (graph, weights) = weighted(6, &arcs);
+        for (name, dist) in all_int_distances(&graph, &weights, 0) {
+            assert_eq!(dist, [0, 3, 1, 4, 7, INF], "{name}");
+        }
+        for (name, dist) in all_int_distances(&graph, &weights, 5) {
+            assert_eq!(dist, [8, 11, 9, 12, 1, 0], "{name}");
+        }
+
+        let arcs = arcs.map(|(u, v, w)| (u, v, w as f64 / 4.0));
+        let (graph, weights) = weighted(6, &arcs);
+        for (name, dist) in all_distances(&graph, &weights, 0) {
+            assert_eq!(dist, [0.0, 0.75, 0.25, 1.0, 1.75, f64::INFINITY], "{name}");
+        }
+    }
+
+    #[test]
+    fn zero_length_edges() {
+        let arcs = [(0, 1, 0), (1, 2, 0), (2, 3, 2), (0, 3, 3), (3, 4, 0), (1, 4, 5), (4, 2, 0)];
+        let (graph, weights) = weighted(6, &arcs);
+        for (name, dist) in all_int_distances(&graph, &weights, 0) {
+            assert_eq!(dist, [0, 0, 0, 2, 2, INF], "{name}");
+        }
+        // A single bucket when every edge is free.
+        let (graph, weights) = weighted(4, &[(0, 1, 0), (1, 2, 0), (2, 0, 0), (3, 0, 0)]);
+        for (name, dist) in all_int_distances(&graph, &weights, 0) {
+            assert_eq!(dist, [0, 0, 0, INF], "{name}");
+        }
+    }
+
+    #[test]
+    fn lone_source() {
+        let (graph, weights) = weighted::<u32>(3, &[(1, 2, 1)]);
+        for (name, dist) in all_int_distances(&graph, &weights, 0) {
+            assert_eq!(dist, [0, INF, INF], "{name}");
+        }
+    }
+
+    #[test]
+    fn algorithms_agree_on_random_graphs() {
+        for (spec, n, directed) in
+            [("er:p=0.05", 150, false), ("er:p=0.02", 200, true), ("grid2d", 144, false), ("ba:m=2", 150, true)]
+        {
+            let generator: Generator = spec.parse().unwrap();
+            let mut edges = generator.edges(n, &mut Rng::new(3));
+            let graph = if directed {
+                let orientation = Orientation::new(5);
+                edges.iter_mut().for_each(|edge| *edge = orientation.orient(edge.0, edge.1));
+                Csr::from_arcs(n, &edges)
+            } else {
+                Csr::from_edges(n, &edges)
+            };
+            // Lengths from 0, so that some paths tie and some are free.
+            let mut rng = Rng::new(11);
+            let ints = Weights::from_fn(&graph, |_, _| rng.below(20) as u32);
+            let floats = Weights::from_fn(&graph, |_, _| rng.next_f64() * 10.0);
+            for source in [0, n / 3, n - 1] {
+                let results = all_int_distances(&graph, &ints, source);
+                validate_distances(&graph, &ints, source, &results[0].1).unwrap();
+                for (name, dist) in &results {
+                    compare_distances(dist, &results[0].1).map_err(|e| format!("{spec}, {name}: {e}")).unwrap();
+                }
+                let results = all_distances(&graph, &floats, source);
+                validate_distances(&graph, &floats, source, &results[0].1).unwrap();
+                for (name, dist) in &results {
+                    compare_distances(dist, &results[0].1).map_err(|e| format!("{spec}, {name}: {e}")).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_distances() {
+        let (graph, weights) = weighted(4, &[(0, 1, 2), (1, 2, 2), (0, 2, 5)]);
+        validate_distances(&graph, &weights, 0, &[0, 2, 4, INF]).unwrap();
+        for dist in [
+            [0, 2, 4, INF, INF].as_slice(),
+            &[1, 2, 4, INF],
+            // A shortcut over 1.
+            &[0, 2, 5, INF],
+            // No path that short.
+            &[0, 2, 3, INF],
+            &[0, 1, 3, INF],
+            // 3 cannot be reached at all.
+            &[0, 2, 4, 9],
+        ] {
+            assert!(validate_distances(&graph, &weights, 0, dist).is_err(), "{dist:?}");
+        }
+    }
+}
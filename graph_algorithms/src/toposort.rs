@@ -0,0 +1,312 @@
+//! Topological sort with Kahn's algorithm.
+//!
+//! An undirected graph is sorted as the DAG [`Upward`] makes of it. With
+//! `--directed` the arcs are sorted as they are, and nodes on a cycle or
+//! behind one are left out of the order.
+
+use std::collections::VecDeque;
+
+use graph::{with_graph, AnyGraph, Graph, GraphOptions};
+use harness::{Args, Benchmark, Language};
+
+use crate::ffi::{self, CGraph};
+
+/// `graph` with every edge directed from its lower to its higher node,
+/// which turns any undirected graph into a DAG.
+pub struct Upward<'a, G>(pub &'a G);
+
+/// Neighbours above `node`.
+pub struct UpwardNeighbors<I> {
+    neighbors: I,
+    node: usize,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for UpwardNeighbors<I> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        let node = self.node;
+        self.neighbors.find(|&i| i > node)
+    }
+}
+
+impl<G: Graph> Graph for Upward<'_, G> {
+    type Neighbors<'b>
+        = UpwardNeighbors<G::Neighbors<'b>>
+    where
+        Self: 'b;
+
+    fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.0.edge_count() / 2
+    }
+
+    #[inline]
+    fn neighbors(&self, node: usize) -> Self::Neighbors<'_> {
+        UpwardNeighbors {
+            neighbors: self.0.neighbors(node),
+            node,
+        }
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.0.memory_bytes()
+    }
+}
+
+/// Kahn's algorithm: takes the nodes without incoming edges in ascending
+/// order, then every node as soon as its last incoming edge is removed.
+/// Replaces `order` with the sorted nodes and returns how many there are,
+/// which is less than the node count if the graph has a cycle.
+pub fn topological_sort<G: Graph>(graph: &G, order: &mut Vec<u32>) -> usize {
+    let n = graph.node_count();
+    let mut indegree = vec![0u32; n];
+    for u in 0..n {
+        for i in graph.neighbors(u) {
+            indegree[i] += 1;
+        }
+    }
+
+    let mut queue: VecDeque<usize> = (0..n).filter(|&i| indegree[i] == 0).collect();
+    order.clear();
+    while let Some(node) = queue.pop_front() {
+        order.push(node as u32);
+        for i in graph.neighbors(node) {
+            indegree[i] -= 1;
+            if indegree[i] == 0 {
+                queue.push_back(i);
+            }
+        }
+    }
+    order.len()
+}
+
+/// Checks that `order` lists nodes at most once, that every edge into an
+/// ordered node comes from an earlier one, and that every node left out
+/// has an edge from another left-out node, so that they cannot be ordered
+/// because of a cycle.
+pub fn validate_order<G: Graph>(graph: &G, order: &[u32]) -> Result<(), String> {
+    let n = graph.node_count();
+    let mut position = vec![usize::MAX; n];
+    for (i, &node) in order.iter().enumerate() {
+        if node as usize >= n || position[node as usize] != usize::MAX {
+            return Err(format!("node {} is listed twice or does not exist", node));
+        }
+        position[node as usize] = i;
+    }
+    let mut blocked = vec![false; n];
+    for u in 0..n {
+        for v in graph.neighbors(u) {
+            if position[v] != usize::MAX && position[u] >= position[v] {
+                return Err(format!("edge ({}, {}) points backwards", u, v));
+            }
+            blocked[v] |= position[u] == usize::MAX;
+        }
+    }
+    match (0..n).find(|&i| position[i] == usize::MAX && !blocked[i]) {
+        Some(i) => Err(format!("node {} is left out, but nothing keeps it from being ordered", i)),
+        None => Ok(()),
+    }
+}
+
+pub struct ToposortInput {
+    pub graph: AnyGraph,
+    /// The graph, or [`Upward`] of it if undirected, for the C version.
+    pub c_graph: CGraph,
+    pub order: Vec<u32>,
+    /// Order from the Rust version at setup.
+    pub expected: Vec<u32>,
+}
+
+#[derive(Default)]
+pub struct Toposort {
+    language: Language,
+    seed: u64,
+    graph: GraphOptions,
+}
+
+impl Benchmark for Toposort {
+    type Input = ToposortInput;
+    /// Number of nodes ordered.
+    type Output = usize;
+
+    fn name(&self) -> &str {
+        "toposort"
+    }
+
+    fn variant(&self) -> String {
+        self.graph.variant()
+    }
+
+    fn size_name(&self) -> &str {
+        "number_of_nodes"
+    }
+
+    fn default_size(&self) -> Option<usize> {
+        self.graph.default_size()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.graph.seed(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--repr matrix|csr|list|bitmatrix] [--generator er|ba|rmat|grid2d|grid3d|regular|ws[:param=value,...] | --graph FILE] \
+         [--directed]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.graph.configure_directed(args);
+        self.graph.configure(args)
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let graph = self.graph.build(size, self.seed);
+        let mut expected = Vec::with_capacity(size);
+        let c_graph = if self.graph.directed {
+            with_graph!(&graph, g => topological_sort(g, &mut expected));
+            with_graph!(&graph, g => CGraph::new(g))
+        } else {
+            with_graph!(&graph, g => topological_sort(&Upward(g), &mut expected));
+            with_graph!(&graph, g => CGraph::new(&Upward(g)))
+        };
+        ToposortInput {
+            graph,
+            c_graph,
+            order: Vec::with_capacity(size),
+            expected,
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> usize {
+        match self.language {
+            Language::C => ffi::topological_sort(&input.c_graph, &mut input.order),
+            Language::Rust if self.graph.directed => {
+                with_graph!(&input.graph, g => topological_sort(g, &mut input.order))
+            }
+            Language::Rust => with_graph!(&input.graph, g => topological_sort(&Upward(g), &mut input.order)),
+        }
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.order.clear();
+    }
+
+    /// Checks the order against the graph, then compares it with the one
+    /// from setup.
+    fn verify(&self, input: &Self::Input, _sorted: &usize) -> Result<(), String> {
+        if self.graph.directed {
+            with_graph!(&input.graph, g => validate_order(g, &input.order))?;
+        } else {
+            with_graph!(&input.graph, g => validate_order(&Upward(g), &input.order))?;
+        }
+        if input.order.len() != input.expected.len() {
+            return Err(format!("{} nodes ordered, expected {}", input.order.len(), input.expected.len()));
+        }
+        match input.order.iter().zip(&input.expected).position(|(a, b)| a != b) {
+            Some(i) => Err(format!("position {} holds node {}, expected node {}", i, input.order[i], input.expected[i])),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to sort graph of size {} topologically", size)
+    }
+
+    fn summary(&self, sorted: &usize) -> Option<String> {
+        Some(format!("{} nodes ordered, {}", sorted, self.graph.summary()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{Csr, FromEdges, Generator, Orientation};
+    use harness::input::Rng;
+
+    /// Sorts `graph` in both languages, which must agree, and validates
+    /// the order.
+    fn sort<G: Graph>(graph: &G) -> Vec<u32> {
+        let mut order = Vec::new();
+        assert_eq!(topological_sort(graph, &mut order), order.len());
+        let mut c_order = Vec::new();
+        ffi::topological_sort(&CGraph::new(graph), &mut c_order);
+        assert_eq!(c_order, order, "the C order differs");
+        validate_order(graph, &order).unwrap();
+        order
+    }
+
+    #[test]
+    fn sorts_a_dag() {
+        let graph = Csr::from_arcs(6, &[(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)]);
+        // Sources in ascending order, then nodes as they are freed.
+        assert_eq!(sort(&graph), [4, 5, 0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn leaves_out_cycles_and_what_follows() {
+        // The cycle 0-1-2 blocks 3, which 4 also leads to; 5 is free.
+        let graph = Csr::from_arcs(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (4, 3)]);
+        assert_eq!(sort(&graph), [4, 5]);
+        // A self-loop is a cycle too.
+        let graph = Csr::from_arcs(3, &[(0, 1), (1, 1), (1, 2)]);
+        assert_eq!(sort(&graph), [0]);
+    }
+
+    #[test]
+    fn sorts_undirected_graphs_upward() {
+        for (spec, n) in [("er:p=0.05", 200), ("grid2d", 100), ("ba:m=3", 200)] {
+            let generator: Generator = spec.parse().unwrap();
+            let graph = Csr::from_edges(n, &generator.edges(n, &mut Rng::new(4)));
+            let upward = Upward(&graph);
+            assert_eq!(upward.edge_count(), graph.edge_count() / 2);
+            assert!((0..n).all(|u| upward.neighbors(u).all(|v| v > u)), "{spec}");
+            assert_eq!(sort(&upward).len(), n, "{spec}");
+        }
+    }
+
+    #[test]
+    fn sorts_random_orientations() {
+        // Orienting a sparse graph at random leaves some cycles.
+        let generator: Generator = "er:p=0.01".parse().unwrap();
+        let orientation = Orientation::new(2);
+        let arcs: Vec<_> =
+            generator.edges(300, &mut Rng::new(4)).into_iter().map(|(u, v)| orientation.orient(u, v)).collect();
+        let sorted = sort(&Csr::from_arcs(300, &arcs)).len();
+        assert!(0 < sorted && sorted < 300, "{sorted} nodes sorted");
+    }
+
+    #[test]
+    fn rejects_wrong_orders() {
+        let graph = Csr::from_arcs(5, &[(0, 1), (1, 2), (3, 4), (4, 3)]);
+        validate_order(&graph, &[0, 1, 2]).unwrap();
+        for order in [
+            [1, 0, 2].as_slice(),
+            &[0, 1, 1, 2],
+            &[0, 1, 2, 5],
+            // 2 could have been ordered.
+            &[0, 1],
+            // 3 and 4 cannot be ordered at all.
+            &[0, 1, 2, 3, 4],
+        ] {
+            assert!(validate_order(&graph, order).is_err(), "{order:?}");
+        }
+    }
+}
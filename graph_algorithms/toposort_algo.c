@@ -0,0 +1,31 @@
+#include <stdlib.h>
+#include "toposort_algo.h"
+
+int topological_sort(const int* offsets, const int* targets, int n, int* order) {
+    int* indegree = (int*)calloc(n, sizeof(int));
+    if (!indegree) {
+        return -1;
+    }
+    for (int e = 0; e < offsets[n]; e++) {
+        indegree[targets[e]]++;
+    }
+
+    // order doubles as the queue.
+    int rear = 0;
+    for (int i = 0; i < n; i++) {
+        if (indegree[i] == 0) {
+            order[rear++] = i;
+        }
+    }
+    for (int front = 0; front < rear; front++) {
+        int node = order[front];
+        for (int e = offsets[node]; e < offsets[node + 1]; e++) {
+            if (--indegree[targets[e]] == 0) {
+                order[rear++] = targets[e];
+            }
+        }
+    }
+
+    free(indegree);
+    return rear;
+}
@@ -0,0 +1,12 @@
+#ifndef TOPOSORT_ALGO_H
+#define TOPOSORT_ALGO_H
+
+// Kahn's topological sort of a directed graph in compressed sparse row
+// form: the edges out of node i go to targets[offsets[i]..offsets[i + 1]].
+// It takes the nodes without
+// incoming edges in ascending order and the rest first come, first served.
+// order must hold n entries. Returns the number of nodes ordered, which is
+// less than n if the graph has a cycle, or -1 if it cannot allocate.
+int topological_sort(const int* offsets, const int* targets, int n, int* order);
+
+#endif
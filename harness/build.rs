@@ -0,0 +1,37 @@
+// Captures build-time facts that end up in every result record.
+
+use std::env;
+use std::process::Command;
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn main() {
+    let git_commit = command_output("git", &["rev-parse", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let compiler = command_output(&rustc, &["--version"]).unwrap_or_else(|| "rustc (unknown version)".to_string());
+
+    let mut flags = format!(
+        "profile={} opt-level={} debug={}",
+        env::var("PROFILE").unwrap_or_default(),
+        env::var("OPT_LEVEL").unwrap_or_default(),
+        env::var("DEBUG").unwrap_or_default(),
+    );
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    if !rustflags.is_empty() {
+        flags.push(' ');
+        flags.push_str(&rustflags.replace('\u{1f}', " "));
+    }
+
+    println!("cargo:rustc-env=HARNESS_GIT_COMMIT={}", git_commit);
+    println!("cargo:rustc-env=HARNESS_COMPILER={}", compiler);
+    println!("cargo:rustc-env=HARNESS_COMPILER_FLAGS={}", flags);
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/index");
+    println!("cargo:rerun-if-env-changed=CARGO_ENCODED_RUSTFLAGS");
+}
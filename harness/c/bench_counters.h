@@ -0,0 +1,155 @@
+#ifndef BENCH_COUNTERS_H
+#define BENCH_COUNTERS_H
+
+// Hardware counters read in-process through perf_event_open(2), the
+// counterpart of harness/src/counters.rs: the same four events, counted in
+// user space, opened on the calling thread and inherited by the threads it
+// starts afterwards, and enabled only around the measured code. The values
+// are printed in the layout of `perf stat`, so harness/src/perf_log.rs
+// reads them like the logs of the old fork-and-attach programs.
+
+#include <errno.h>
+#include <linux/perf_event.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <sys/syscall.h>
+#include <time.h>
+#include <unistd.h>
+
+#define BENCH_COUNTERS 4
+
+static const char* const bench_counter_names[BENCH_COUNTERS] = {
+    "cycles", "instructions", "cache-references", "cache-misses",
+};
+
+static const uint64_t bench_counter_events[BENCH_COUNTERS] = {
+    PERF_COUNT_HW_CPU_CYCLES, PERF_COUNT_HW_INSTRUCTIONS,
+    PERF_COUNT_HW_CACHE_REFERENCES, PERF_COUNT_HW_CACHE_MISSES,
+};
+
+typedef struct {
+    int fds[BENCH_COUNTERS];
+    uint64_t values[BENCH_COUNTERS];
+    // Whether each counter was ever scheduled on the PMU while enabled
+    int counted[BENCH_COUNTERS];
+    // errno of the failed open, 0 if the counters are available
+    int error;
+} BenchCounters;
+
+// Seconds on CLOCK_MONOTONIC, the clock of the non-perf programs and of
+// the Rust harness.
+static double bench_now(void) {
+    struct timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    return now.tv_sec + now.tv_nsec / 1e9;
+}
+
+// Opens the counters disabled. On failure `error` is set and the other
+// calls leave them alone.
+static void bench_counters_open(BenchCounters* counters) {
+    memset(counters, 0, sizeof(*counters));
+    for (int i = 0; i < BENCH_COUNTERS; i++) {
+        counters->fds[i] = -1;
+    }
+    for (int i = 0; i < BENCH_COUNTERS; i++) {
+        struct perf_event_attr attr;
+        memset(&attr, 0, sizeof(attr));
+        attr.type = PERF_TYPE_HARDWARE;
+        attr.size = sizeof(attr);
+        attr.config = bench_counter_events[i];
+        attr.read_format = PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING;
+        attr.disabled = 1;
+        attr.inherit = 1;
+        attr.exclude_kernel = 1;
+        attr.exclude_hv = 1;
+        int fd = (int)syscall(SYS_perf_event_open, &attr, 0, -1, -1, PERF_FLAG_FD_CLOEXEC);
+        if (fd < 0) {
+            counters->error = errno;
+            for (int j = 0; j < i; j++) {
+                close(counters->fds[j]);
+                counters->fds[j] = -1;
+            }
+            return;
+        }
+        counters->fds[i] = fd;
+    }
+}
+
+static void bench_counters_ioctl(BenchCounters* counters, unsigned long request) {
+    if (counters->error) {
+        return;
+    }
+    for (int i = 0; i < BENCH_COUNTERS; i++) {
+        ioctl(counters->fds[i], request, 0);
+    }
+}
+
+static void bench_counters_start(BenchCounters* counters) {
+    bench_counters_ioctl(counters, PERF_EVENT_IOC_RESET);
+    bench_counters_ioctl(counters, PERF_EVENT_IOC_ENABLE);
+}
+
+// Stops the counters and reads them, scaled up if the kernel had to
+// multiplex them.
+static void bench_counters_stop(BenchCounters* counters) {
+    bench_counters_ioctl(counters, PERF_EVENT_IOC_DISABLE);
+    if (counters->error) {
+        return;
+    }
+    for (int i = 0; i < BENCH_COUNTERS; i++) {
+        // struct read_format { value, time_enabled, time_running }
+        uint64_t data[3];
+        if (read(counters->fds[i], data, sizeof(data)) != (ssize_t)sizeof(data) || data[2] == 0) {
+            continue;
+        }
+        counters->values[i] = data[2] < data[1] ? (uint64_t)((double)data[0] * data[1] / data[2]) : data[0];
+        counters->counted[i] = 1;
+    }
+}
+
+static void bench_counters_close(BenchCounters* counters) {
+    for (int i = 0; i < BENCH_COUNTERS; i++) {
+        if (counters->fds[i] >= 0) {
+            close(counters->fds[i]);
+            counters->fds[i] = -1;
+        }
+    }
+}
+
+// Formats `value` with thousands separators, as perf does.
+static void bench_group_thousands(uint64_t value, char* out, size_t length) {
+    char digits[32];
+    int n = snprintf(digits, sizeof(digits), "%llu", (unsigned long long)value);
+    size_t j = 0;
+    for (int i = 0; i < n && j + 2 < length; i++) {
+        if (i > 0 && (n - i) % 3 == 0) {
+            out[j++] = ',';
+        }
+        out[j++] = digits[i];
+    }
+    out[j] = '\0';
+}
+
+// Prints the counters as a `perf stat` block for `program`.
+static void bench_counters_print(const BenchCounters* counters, const char* program) {
+    printf("\n[ Hardware Counters ]\n");
+    if (counters->error) {
+        printf("counters unavailable: perf_event_open failed: %s\n", strerror(counters->error));
+    }
+    printf("\n Performance counter stats for '%s':\n\n", program);
+    for (int i = 0; i < BENCH_COUNTERS; i++) {
+        if (counters->error) {
+            printf("%20s      %s\n", "<not supported>", bench_counter_names[i]);
+        } else if (!counters->counted[i]) {
+            printf("%20s      %s\n", "<not counted>", bench_counter_names[i]);
+        } else {
+            char value[32];
+            bench_group_thousands(counters->values[i], value, sizeof(value));
+            printf("%20s      %s\n", value, bench_counter_names[i]);
+        }
+    }
+}
+
+#endif
@@ -0,0 +1,69 @@
+#ifndef BENCH_INPUT_H
+#define BENCH_INPUT_H
+
+// Reader for the input files written by the Rust benchmarks with
+// --dump-input (see harness/src/input.rs for the layout). Assumes a
+// little-endian host, like the writer.
+
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Loads array `index` of the input file at `path`, checking that the file
+// was generated for `size` and that the array holds `length` ints.
+// Returns a malloc'd array, or NULL after printing an error.
+static int* bench_input_load(const char* path, long long size, uint32_t index, long long length) {
+    FILE* file = fopen(path, "rb");
+    if (!file) {
+        perror(path);
+        return NULL;
+    }
+
+    char magic[8];
+    uint32_t version, arrays;
+    uint64_t seed, file_size;
+    if (fread(magic, 1, 8, file) != 8 || memcmp(magic, "RCBINPUT", 8) != 0
+        || fread(&version, 4, 1, file) != 1 || version != 1
+        || fread(&arrays, 4, 1, file) != 1
+        || fread(&seed, 8, 1, file) != 1
+        || fread(&file_size, 8, 1, file) != 1) {
+        printf("Error: %s is not a benchmark input file.\n", path);
+        fclose(file);
+        return NULL;
+    }
+    if ((long long)file_size != size || index >= arrays) {
+        printf("Error: %s was generated for size %llu, not %lld.\n", path, (unsigned long long)file_size, size);
+        fclose(file);
+        return NULL;
+    }
+
+    uint64_t array_length = 0;
+    for (uint32_t i = 0; i <= index; i++) {
+        if (i > 0 && fseek(file, (long)(array_length * sizeof(int32_t)), SEEK_CUR) != 0) {
+            break;
+        }
+        if (fread(&array_length, 8, 1, file) != 1) {
+            array_length = (uint64_t)-1;
+            break;
+        }
+    }
+    if ((long long)array_length != length) {
+        printf("Error: unexpected array length in %s.\n", path);
+        fclose(file);
+        return NULL;
+    }
+
+    int* data = (int*)malloc(length * sizeof(int));
+    if (!data || fread(data, sizeof(int), length, file) != (size_t)length) {
+        printf("Error: failed to read %s.\n", path);
+        free(data);
+        fclose(file);
+        return NULL;
+    }
+
+    fclose(file);
+    return data;
+}
+
+#endif
@@ -0,0 +1,63 @@
+#ifndef BENCH_RNG_H
+#define BENCH_RNG_H
+
+// SplitMix64, the generator of the Rust benchmarks (see Rng in
+// harness/src/input.rs), so the C programs draw their own input from a
+// seed as well: the same numbers for the same --seed, on any libc.
+
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// The seed without --seed, as in the Rust benchmarks.
+#define BENCH_DEFAULT_SEED 10000
+
+typedef struct {
+    uint64_t state;
+} BenchRng;
+
+static inline uint64_t bench_rng_next(BenchRng* rng) {
+    rng->state += 0x9e3779b97f4a7c15ULL;
+    uint64_t z = rng->state;
+    z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+    z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+    return z ^ (z >> 31);
+}
+
+// Uniform in [0, n), by multiply-shift like Rng::below.
+static inline uint64_t bench_rng_below(BenchRng* rng, uint64_t n) {
+    return (uint64_t)(((unsigned __int128)bench_rng_next(rng) * n) >> 64);
+}
+
+// Uniform in [low, high), like Rng::range_i32.
+static inline int bench_rng_range(BenchRng* rng, int low, int high) {
+    return (int)((int64_t)low + (int64_t)bench_rng_below(rng, (uint64_t)((int64_t)high - low)));
+}
+
+// Removes `--seed N` from the arguments, wherever it is, and stores N in
+// `seed`, which keeps its value without the option. Returns 0, or -1
+// after printing an error.
+static inline int bench_take_seed(int* argc, char** argv, uint64_t* seed) {
+    for (int i = 1; i < *argc; i++) {
+        if (strcmp(argv[i], "--seed") != 0) {
+            continue;
+        }
+        const char* value = i + 1 < *argc ? argv[i + 1] : "";
+        char* end;
+        errno = 0;
+        unsigned long long parsed = strtoull(value, &end, 10);
+        if (value[0] < '0' || value[0] > '9' || *end != '\0' || errno != 0) {
+            printf("Error: --seed needs a non-negative integer, got '%s'.\n", value);
+            return -1;
+        }
+        *seed = parsed;
+        memmove(&argv[i], &argv[i + 2], (*argc - i - 1) * sizeof(char*));
+        *argc -= 2;
+        i--;
+    }
+    return 0;
+}
+
+#endif
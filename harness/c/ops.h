@@ -0,0 +1,37 @@
+#ifndef OPS_H
+#define OPS_H
+
+// Operation counts for the C twins of the sorts, the counterpart of
+// harness/src/ops.rs. They are only kept when compiled with -DINSTRUMENT,
+// which the build scripts pass with the `instrument` feature; otherwise the
+// macros expand to nothing and their arguments need not exist.
+
+#include <stdint.h>
+
+typedef struct {
+    uint64_t comparisons;
+    uint64_t swaps;
+    uint64_t calls;
+    uint64_t max_depth;
+} OpCounts;
+
+#ifdef INSTRUMENT
+#define OPS_COMPARE(ops) ((ops).comparisons++)
+#define OPS_SWAP(ops) ((ops).swaps++)
+// Counts a call, one level deeper than the current `depth`.
+#define OPS_ENTER(ops, depth)                \
+    do {                                     \
+        (ops).calls++;                       \
+        if (++(depth) > (ops).max_depth) {   \
+            (ops).max_depth = (depth);       \
+        }                                    \
+    } while (0)
+#define OPS_LEAVE(depth) ((depth)--)
+#else
+#define OPS_COMPARE(ops) ((void)0)
+#define OPS_SWAP(ops) ((void)0)
+#define OPS_ENTER(ops, depth) ((void)0)
+#define OPS_LEAVE(depth) ((void)0)
+#endif
+
+#endif
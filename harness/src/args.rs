@@ -0,0 +1,152 @@
+//! Minimal command-line parsing shared by the benchmark binaries.
+//!
+//! The runner and each benchmark take the options they know about out of
+//! `Args`; anything left over at the end is reported as an error.
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+pub struct Args {
+    program: String,
+    rest: Vec<String>,
+}
+
+impl Args {
+    pub fn from_env() -> Self {
+        let mut all = env::args();
+        let program = all.next().unwrap_or_default();
+        Args::new(program, all.collect())
+    }
+
+    pub fn new(program: String, rest: Vec<String>) -> Self {
+        Args { program, rest }
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Removes `--name` and reports whether it was present.
+    pub fn flag(&mut self, name: &str) -> bool {
+        match self.rest.iter().position(|a| a == name) {
+            Some(i) => {
+                self.rest.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes `--name value` or `--name=value` and returns the value.
+    pub fn value(&mut self, name: &str) -> Result<Option<String>, String> {
+        let prefix = format!("{name}=");
+        for i in 0..self.rest.len() {
+            if self.rest[i] == name {
+                if i + 1 >= self.rest.len() {
+                    return Err(format!("{name} requires a value"));
+                }
+                let value = self.rest.remove(i + 1);
+                self.rest.remove(i);
+                return Ok(Some(value));
+            }
+            if let Some(value) = self.rest[i].strip_prefix(&prefix) {
+                let value = value.to_string();
+                self.rest.remove(i);
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn parse<T>(&mut self, name: &str) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        match self.value(name)? {
+            Some(raw) => raw
+                .parse()
+                .map(Some)
+                .map_err(|e| format!("invalid value for {name}: {raw} ({e})")),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes the first argument that is not an option.
+    pub fn positional(&mut self) -> Option<String> {
+        let i = self.rest.iter().position(|a| !a.starts_with("--"))?;
+        Some(self.rest.remove(i))
+    }
+
+    /// The arguments not consumed so far, e.g. to pass on to a runner.
+    pub fn remaining(&self) -> &[String] {
+        &self.rest
+    }
+
+    /// Fails if any argument was not consumed.
+    pub fn finish(&self) -> Result<(), String> {
+        match self.rest.first() {
+            Some(arg) => Err(format!("unexpected argument: {arg}")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `key=value` pairs of a spec like `NAME:key=value,...`, such as a graph
+/// generator or an input distribution.
+pub struct Params(Vec<(String, String)>);
+
+impl Params {
+    pub fn parse(raw: &str) -> Result<Params, String> {
+        raw.split(',')
+            .filter(|p| !p.is_empty())
+            .map(|p| match p.split_once('=') {
+                Some((key, value)) => Ok((key.to_string(), value.to_string())),
+                None => Err(format!("expected key=value, got {p}")),
+            })
+            .collect::<Result<_, _>>()
+            .map(Params)
+    }
+
+    /// Removes `key` and parses its value.
+    pub fn get<T: FromStr>(&mut self, key: &str) -> Result<Option<T>, String> {
+        let Some(i) = self.0.iter().position(|(k, _)| k == key) else {
+            return Ok(None);
+        };
+        let (_, value) = self.0.remove(i);
+        value.parse().map(Some).map_err(|_| format!("invalid value for {key}: {value}"))
+    }
+
+    /// Fails if any parameter was not taken by `get`.
+    pub fn finish(&self, name: &str) -> Result<(), String> {
+        match self.0.first() {
+            Some((key, _)) => Err(format!("unknown parameter for {name}: {key}")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_hand_out_every_value_once() {
+        let mut params = Params::parse("min=-5,max=10,name=x").unwrap();
+        assert_eq!(params.get::<i32>("max"), Ok(Some(10)));
+        assert_eq!(params.get::<i32>("max"), Ok(None));
+        assert_eq!(params.get::<i32>("min"), Ok(Some(-5)));
+        assert_eq!(params.finish("test"), Err("unknown parameter for test: name".to_string()));
+        assert_eq!(params.get::<String>("name"), Ok(Some("x".to_string())));
+        assert_eq!(params.finish("test"), Ok(()));
+    }
+
+    #[test]
+    fn params_reject_malformed_values() {
+        assert_eq!(Params::parse("").map(|p| p.finish("test")), Ok(Ok(())));
+        assert_eq!(Params::parse("min").err(), Some("expected key=value, got min".to_string()));
+        let mut params = Params::parse("k=ten").unwrap();
+        assert_eq!(params.get::<usize>("k"), Err("invalid value for k: ten".to_string()));
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::args::Args;
+
+/// Which implementation of an algorithm is being measured. The C ones are
+/// the repository's own C sources, linked in through FFI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    Rust,
+    C,
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Language::Rust => write!(f, "rust"),
+            Language::C => write!(f, "c"),
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rust" => Ok(Language::Rust),
+            "c" => Ok(Language::C),
+            _ => Err(format!("unknown language: {s} (expected rust or c)")),
+        }
+    }
+}
+
+/// One algorithm under test.
+///
+/// The runner calls `setup` to build the input, times `run` (with hardware
+/// counters around it when enabled), and then hands the result to `verify`.
+/// When several runs are requested, `reset` is called between them. Only
+/// `run` is measured.
+///
+/// Benchmarks with a C counterpart switch to it in `set_language`, so both
+/// languages can run on the very same input within one process. Parallel
+/// benchmarks likewise switch thread counts in `set_threads`.
+pub trait Benchmark {
+    type Input;
+    type Output;
+
+    fn name(&self) -> &str;
+
+    /// Benchmark-specific configuration recorded with the results, e.g.
+    /// the graph representation. Empty by default.
+    fn variant(&self) -> String {
+        String::new()
+    }
+
+    /// What the positional size argument means, shown in the usage line.
+    fn size_name(&self) -> &str {
+        "size"
+    }
+
+    /// Size used when none is given on the command line. `None` makes the
+    /// size argument mandatory.
+    fn default_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Seed the input was generated with, or `None` if the input does not
+    /// depend on one.
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+
+    /// Seed for the input generator, from `--seed` or
+    /// [`DEFAULT_SEED`](crate::input::DEFAULT_SEED). Called before `setup`.
+    fn set_seed(&mut self, _seed: u64) {}
+
+    /// The arrays `input` is made of, in the order the C program reads them
+    /// from a `--dump-input` file. `None` if the input cannot be dumped.
+    fn input_arrays(&self, _input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        None
+    }
+
+    /// Implementation that `run` currently calls.
+    fn language(&self) -> Language {
+        Language::Rust
+    }
+
+    /// Switches `run` to another implementation. Returns `false` if there
+    /// is none for `language`.
+    fn set_language(&mut self, language: Language) -> bool {
+        language == Language::Rust
+    }
+
+    /// Worker threads `run` currently uses, or `None` if it is sequential.
+    fn threads(&self) -> Option<usize> {
+        None
+    }
+
+    /// Switches `run` to its parallel version on `threads` threads, or with
+    /// `None` to the sequential baseline the parallel one is compared with.
+    /// Only called for `--threads`. Returns `false` if there is no such
+    /// version.
+    fn set_threads(&mut self, threads: Option<usize>) -> bool {
+        threads.is_none()
+    }
+
+    /// Benchmark-specific options for the usage line, e.g. `[--repr csr|list]`.
+    fn options(&self) -> &str {
+        ""
+    }
+
+    /// Picks up benchmark-specific options before anything runs.
+    fn configure(&mut self, _args: &mut Args) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input;
+
+    fn run(&mut self, input: &mut Self::Input) -> Self::Output;
+
+    /// Brings the input back to its pre-run state before another run.
+    /// The default builds a fresh one with `setup`.
+    fn reset(&mut self, input: &mut Self::Input, size: usize) {
+        *input = self.setup(size);
+    }
+
+    fn verify(&self, _input: &Self::Input, _output: &Self::Output) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Completes "Time taken ...", e.g. "to sort the array of size 1000".
+    fn describe(&self, size: usize) -> String;
+
+    /// Extra detail appended to the timing line.
+    fn summary(&self, _output: &Self::Output) -> Option<String> {
+        None
+    }
+}
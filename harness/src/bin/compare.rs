@@ -0,0 +1,253 @@
+//! Runs the Rust and C versions of one algorithm on the same input sizes
+//! and compares them side by side.
+//!
+//! The Rust binary comes from the workspace (`target/release/<algorithm>`)
+//! and the C binary from the algorithm's Makefile (`<algorithm>/<algorithm>_c`).
+//! Both are built first unless `--no-build` is given. Each run is a fresh
+//! process, so both sides pay the same startup cost.
+//!
+//! The input is generated once by the Rust binary from `--seed` and dumped
+//! to a file the C binary loads, so both sides sort or search exactly the
+//! same data.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+
+use harness::perf_log::PerfLog;
+use harness::record::Record;
+use harness::report;
+use harness::input::DEFAULT_SEED;
+use harness::Args;
+
+const ALGORITHMS: [&str; 5] = ["bfs", "dfs", "quicksort", "selection_sort", "binary_search"];
+const METRICS: [&str; 5] = ["time (s)", "cycles", "instructions", "cache-references", "cache-misses"];
+
+/// Wall time followed by the four hardware counters, when available.
+type Sample = [Option<f64>; 5];
+
+struct Options {
+    root: PathBuf,
+    algorithm: String,
+    size: usize,
+    runs: usize,
+    seed: u64,
+    counters: bool,
+    build: bool,
+    cc: Option<String>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut args = Args::from_env();
+    let default_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf();
+    let root = args.value("--root")?.map(PathBuf::from).unwrap_or(default_root);
+    let runs = args.parse("--runs")?.unwrap_or(10);
+    let seed = args.parse("--seed")?.unwrap_or(DEFAULT_SEED);
+    let counters = args.flag("--counters");
+    let build = !args.flag("--no-build");
+    let cc = args.value("--cc")?;
+
+    let algorithm = args.positional().ok_or("missing algorithm")?;
+    if !ALGORITHMS.contains(&algorithm.as_str()) {
+        return Err(format!("unknown algorithm {} (expected one of {})", algorithm, ALGORITHMS.join(", ")));
+    }
+    let size = match args.positional().map(|s| s.parse()) {
+        Some(Ok(n)) if n > 0 => n,
+        Some(_) => return Err("size must be a positive integer".to_string()),
+        None => return Err("missing size".to_string()),
+    };
+    if runs == 0 {
+        return Err("--runs must be at least 1".to_string());
+    }
+    args.finish()?;
+
+    Ok(Options {
+        root,
+        algorithm,
+        size,
+        runs,
+        seed,
+        counters,
+        build,
+        cc,
+    })
+}
+
+fn run_command(command: &mut Command) -> Result<String, String> {
+    let output = command.output().map_err(|e| format!("failed to run {:?}: {}", command, e))?;
+    if !output.status.success() {
+        // The C programs report their errors on stdout.
+        let message = if output.stderr.is_empty() { &output.stdout } else { &output.stderr };
+        return Err(format!(
+            "{:?} failed with {}:\n{}",
+            command,
+            output.status,
+            String::from_utf8_lossy(message)
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Builds both binaries and returns their paths (Rust, C).
+fn locate(options: &Options) -> Result<(PathBuf, PathBuf), String> {
+    let suffix = if options.counters { "_perf" } else { "" };
+    let rust_name = format!("{}{}", options.algorithm, suffix);
+    let c_dir = options.root.join(&options.algorithm);
+    let c_name = format!("{}{}_c", options.algorithm, suffix);
+
+    if options.build {
+        run_command(
+            Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+                .arg("build")
+                .arg("--release")
+                .arg("--bin")
+                .arg(&rust_name)
+                .arg("--manifest-path")
+                .arg(options.root.join("Cargo.toml")),
+        )?;
+        let mut make = Command::new("make");
+        make.arg("-C").arg(&c_dir).arg(&c_name);
+        if let Some(cc) = &options.cc {
+            make.arg(format!("CC={}", cc));
+        }
+        run_command(&mut make)?;
+    }
+
+    let rust = options.root.join("target").join("release").join(&rust_name);
+    let c = c_dir.join(&c_name);
+    for path in [&rust, &c] {
+        if !path.exists() {
+            return Err(format!("{} not found (build it or drop --no-build)", path.display()));
+        }
+    }
+    Ok((rust, c))
+}
+
+/// Has the Rust binary write its input for `seed` to `path`.
+fn dump_input(binary: &Path, size: usize, seed: u64, path: &Path) -> Result<(), String> {
+    run_command(
+        Command::new(binary)
+            .arg(size.to_string())
+            .args(["--seed", &seed.to_string()])
+            .arg("--dump-input")
+            .arg(path),
+    )
+    .map(|_| ())
+}
+
+fn run_rust(binary: &Path, size: usize, seed: u64) -> Result<Sample, String> {
+    let stdout = run_command(
+        Command::new(binary)
+            .arg(size.to_string())
+            .args(["--seed", &seed.to_string()])
+            .args(["--format", "json"]),
+    )?;
+    let line = stdout.lines().next().ok_or("no record printed")?;
+    let record: Record = serde_json::from_str(line).map_err(|e| format!("bad record {:?}: {}", line, e))?;
+    let counter = |v: Option<u64>| v.map(|v| v as f64);
+    Ok([
+        record.elapsed_seconds,
+        counter(record.cycles),
+        counter(record.instructions),
+        counter(record.cache_references),
+        counter(record.cache_misses),
+    ])
+}
+
+/// The C programs print their own `Time taken` line and, for the perf
+/// variants, the counters in the layout of a `perf stat` block. Those are
+/// read in-process around the measured call, as on the Rust side.
+fn run_c(binary: &Path, size: usize, input: &Path, workdir: &Path) -> Result<Sample, String> {
+    let stdout = run_command(Command::new(binary).arg(size.to_string()).arg(input).current_dir(workdir))?;
+    let log = PerfLog::parse(&stdout);
+    let timing = log.timings.first().ok_or("no \"Time taken\" line printed")?;
+    let stat = log.stats.first();
+    let counter = |name| stat.and_then(|s| s.total(name));
+    Ok([
+        Some(timing.seconds),
+        counter("cycles"),
+        counter("instructions"),
+        counter("cache-references"),
+        counter("cache-misses"),
+    ])
+}
+
+fn column(samples: &[Sample], metric: usize) -> Vec<f64> {
+    samples.iter().filter_map(|s| s[metric]).collect()
+}
+
+fn print_table(options: &Options, rust: &[Sample], c: &[Sample]) {
+    println!(
+        "\n[ {} of size {} (seed {}): Rust vs C, {} runs each ]",
+        options.algorithm, options.size, options.seed, options.runs
+    );
+    println!(
+        "{:<18}{:>16}{:>16}{:>10}{:>12}",
+        "", "rust median", "c median", "rust/c", "p-value"
+    );
+    for (i, metric) in METRICS.iter().enumerate() {
+        let precision = if i == 0 { 6 } else { 0 };
+        report::print_comparison_row(metric, &column(rust, i), &column(c, i), precision);
+    }
+    println!("\n* difference is significant at the 5% level (two-sided Mann-Whitney U)");
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            eprintln!(
+                "Usage: compare <{}> <size> [--runs N] [--seed N] [--counters] [--no-build] [--cc COMPILER] [--root DIR]",
+                ALGORITHMS.join("|")
+            );
+            exit(1);
+        }
+    };
+
+    let (rust_binary, c_binary) = match locate(&options) {
+        Ok(paths) => paths,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+    };
+
+    // Scratch directory for the input file, also the working directory of
+    // the C programs.
+    let workdir = env::temp_dir().join(format!("rust_c_compare_{}", std::process::id()));
+    if let Err(err) = fs::create_dir_all(&workdir) {
+        eprintln!("Failed to create {}: {}", workdir.display(), err);
+        exit(1);
+    }
+
+    let input = workdir.join("input.bin");
+    if let Err(err) = dump_input(&rust_binary, options.size, options.seed, &input) {
+        eprintln!("Error: {}", err);
+        let _ = fs::remove_dir_all(&workdir);
+        exit(1);
+    }
+
+    let mut rust = Vec::with_capacity(options.runs);
+    let mut c = Vec::with_capacity(options.runs);
+    for _ in 0..options.runs {
+        // Alternate the two so drift in machine state hits both equally.
+        let result = run_rust(&rust_binary, options.size, options.seed)
+            .and_then(|r| run_c(&c_binary, options.size, &input, &workdir).map(|c| (r, c)));
+        match result {
+            Ok((r, s)) => {
+                rust.push(r);
+                c.push(s);
+            }
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                let _ = fs::remove_dir_all(&workdir);
+                exit(1);
+            }
+        }
+    }
+    let _ = fs::remove_dir_all(&workdir);
+
+    print_table(&options, &rust, &c);
+}
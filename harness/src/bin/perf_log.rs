@@ -0,0 +1,77 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::exit;
+
+use harness::perf_log::{describe_file, to_records, PerfLog, Value};
+use harness::record::{write_records, Format};
+use harness::Args;
+
+fn print_log(path: &Path, log: &PerfLog) {
+    let (algorithm, language, tag) = describe_file(path);
+    println!("== {} ({}, {}{})", path.display(), algorithm, language, tag.map(|t| format!(", {}", t)).unwrap_or_default());
+
+    for timing in &log.timings {
+        println!("Time taken {}: {:.6} seconds", timing.description.trim_start_matches("Time taken "), timing.seconds);
+    }
+    for stat in &log.stats {
+        println!("\n[ {} ]", stat.target);
+        for event in &stat.events {
+            let name = match &event.pmu {
+                Some(pmu) => format!("{}/{}", pmu, event.name),
+                None => event.name.clone(),
+            };
+            let value = match event.value {
+                Value::Counted(v) => format!("{:.0}", v),
+                Value::NotCounted => "<not counted>".to_string(),
+                Value::NotSupported => "<not supported>".to_string(),
+            };
+            let running = event.running_percent.map(|p| format!("  ({:.2}% running)", p)).unwrap_or_default();
+            println!("{:>20}  {}{}", value, name, running);
+        }
+        if let Some(elapsed) = stat.elapsed_seconds {
+            println!("{:>20.9}  seconds time elapsed", elapsed);
+        }
+    }
+    println!();
+}
+
+fn main() {
+    let mut args = Args::from_env();
+    let program = args.program().to_string();
+    let format: Format = match args.parse("--format") {
+        Ok(format) => format.unwrap_or(Format::Text),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            exit(1);
+        }
+    };
+
+    let mut paths = Vec::new();
+    while let Some(path) = args.positional() {
+        paths.push(path);
+    }
+    if paths.is_empty() || args.finish().is_err() {
+        eprintln!("Usage: {} [--format text|json|csv] <perf_output.log>...", program);
+        exit(1);
+    }
+
+    let mut records = Vec::new();
+    for path in &paths {
+        let path = Path::new(path);
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("Failed to read {}: {}", path.display(), err);
+                exit(1);
+            }
+        };
+        let log = PerfLog::parse(&text);
+        match format {
+            Format::Text => print_log(path, &log),
+            _ => records.extend(to_records(path, &log)),
+        }
+    }
+
+    let _ = write_records(&mut io::stdout().lock(), format, &records, true);
+}
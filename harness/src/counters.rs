@@ -0,0 +1,271 @@
+//! Hardware counters read in-process through `perf_event_open(2)`.
+//!
+//! The counters are opened on the calling thread and inherited by every
+//! thread it starts afterwards, so parallel benchmarks count their workers
+//! too. They are only enabled for the duration of the measured closure, so
+//! setup work (input generation, graph construction, printing) never shows
+//! up in the numbers.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::fd::{AsRawFd, FromRawFd};
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+
+const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+const PERF_COUNT_HW_CACHE_REFERENCES: u64 = 2;
+const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+
+const ATTR_FLAG_DISABLED: u64 = 1 << 0;
+const ATTR_FLAG_INHERIT: u64 = 1 << 1;
+const ATTR_FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+const ATTR_FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+const EVENTS: [u64; 4] = [
+    PERF_COUNT_HW_CPU_CYCLES,
+    PERF_COUNT_HW_INSTRUCTIONS,
+    PERF_COUNT_HW_CACHE_REFERENCES,
+    PERF_COUNT_HW_CACHE_MISSES,
+];
+
+/// `struct perf_event_attr` up to `PERF_ATTR_SIZE_VER5`.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    reserved: u16,
+}
+
+/// Counter values collected around one measured call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    pub cycles: u64,
+    pub instructions: u64,
+    pub cache_references: u64,
+    pub cache_misses: u64,
+}
+
+impl Counters {
+    pub fn instructions_per_cycle(&self) -> f64 {
+        if self.cycles == 0 {
+            return 0.0;
+        }
+        self.instructions as f64 / self.cycles as f64
+    }
+
+    pub fn cache_miss_rate(&self) -> f64 {
+        if self.cache_references == 0 {
+            return 0.0;
+        }
+        self.cache_misses as f64 / self.cache_references as f64
+    }
+}
+
+impl fmt::Display for Counters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>20}      cycles", group_thousands(self.cycles))?;
+        writeln!(
+            f,
+            "{:>20}      instructions          #  {:.2} insn per cycle",
+            group_thousands(self.instructions),
+            self.instructions_per_cycle()
+        )?;
+        writeln!(f, "{:>20}      cache-references", group_thousands(self.cache_references))?;
+        write!(
+            f,
+            "{:>20}      cache-misses          #  {:.2}% of all cache refs",
+            group_thousands(self.cache_misses),
+            self.cache_miss_rate() * 100.0
+        )
+    }
+}
+
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Why the counters could not be read, e.g. `perf_event_paranoid` or a
+/// container seccomp profile forbidding `perf_event_open`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unavailable {
+    pub reason: String,
+}
+
+impl fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "counters unavailable: {}", self.reason)
+    }
+}
+
+impl std::error::Error for Unavailable {}
+
+impl Unavailable {
+    fn from_open_error(err: io::Error) -> Self {
+        let hint = match err.raw_os_error() {
+            Some(libc::EACCES) | Some(libc::EPERM) => {
+                let paranoid = std::fs::read_to_string("/proc/sys/kernel/perf_event_paranoid")
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_else(|_| "?".to_string());
+                format!(" (perf_event_paranoid = {paranoid}, or blocked by the container)")
+            }
+            Some(libc::ENOENT) | Some(libc::ENODEV) | Some(libc::EOPNOTSUPP) => {
+                " (no hardware PMU exposed, e.g. inside a VM)".to_string()
+            }
+            Some(libc::ENOSYS) => " (perf_event_open not supported by this kernel)".to_string(),
+            _ => String::new(),
+        };
+        Unavailable {
+            reason: format!("perf_event_open failed: {err}{hint}"),
+        }
+    }
+}
+
+/// The four hardware counters, opened on the current thread and inherited
+/// by the threads it starts afterwards. They are separate events rather
+/// than a perf group, which the kernel cannot read with `inherit`.
+pub struct CounterGroup {
+    counters: Vec<File>,
+}
+
+impl CounterGroup {
+    pub fn open() -> Result<Self, Unavailable> {
+        let mut counters = Vec::with_capacity(EVENTS.len());
+        for &event in &EVENTS {
+            counters.push(open_counter(event).map_err(Unavailable::from_open_error)?);
+        }
+        Ok(CounterGroup { counters })
+    }
+
+    /// Runs `f` with the counters enabled only for its duration.
+    pub fn measure<R>(&mut self, f: impl FnOnce() -> R) -> (R, Result<Counters, Unavailable>) {
+        if let Err(err) = self.ioctl_all(PERF_EVENT_IOC_RESET).and_then(|_| self.ioctl_all(PERF_EVENT_IOC_ENABLE)) {
+            let result = f();
+            return (result, Err(Unavailable { reason: format!("failed to enable counters: {err}") }));
+        }
+        let result = f();
+        if let Err(err) = self.ioctl_all(PERF_EVENT_IOC_DISABLE) {
+            return (result, Err(Unavailable { reason: format!("failed to disable counters: {err}") }));
+        }
+        (result, self.read())
+    }
+
+    /// Applies `request` to every counter, and with it to the copies the
+    /// threads started since have inherited.
+    fn ioctl_all(&self, request: libc::c_ulong) -> io::Result<()> {
+        for counter in &self.counters {
+            if unsafe { libc::ioctl(counter.as_raw_fd(), request as _, 0) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<Counters, Unavailable> {
+        let mut values = [0; EVENTS.len()];
+        for (counter, value) in self.counters.iter_mut().zip(&mut values) {
+            *value = read_counter(counter)?;
+        }
+        Ok(Counters {
+            cycles: values[0],
+            instructions: values[1],
+            cache_references: values[2],
+            cache_misses: values[3],
+        })
+    }
+}
+
+/// Reads one counter, summed over the threads that inherited it.
+fn read_counter(counter: &mut File) -> Result<u64, Unavailable> {
+    // struct read_format { value, time_enabled, time_running }
+    let mut buf = [0u8; 8 * 3];
+    if let Err(err) = counter.read_exact(&mut buf) {
+        return Err(Unavailable { reason: format!("failed to read counters: {err}") });
+    }
+    let word = |i: usize| u64::from_ne_bytes(buf[i * 8..i * 8 + 8].try_into().unwrap());
+
+    let (value, time_enabled, time_running) = (word(0), word(1), word(2));
+    if time_running == 0 {
+        return Err(Unavailable {
+            reason: "counters were never scheduled on the PMU".to_string(),
+        });
+    }
+    // Scale the value up if the kernel had to multiplex the counters.
+    if time_running < time_enabled {
+        Ok((value as f64 * time_enabled as f64 / time_running as f64) as u64)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Opens a counter group and measures `f`. If the counters cannot be opened,
+/// `f` is still run and the reason is returned in place of the values.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Result<Counters, Unavailable>) {
+    match CounterGroup::open() {
+        Ok(mut group) => group.measure(f),
+        Err(err) => (f(), Err(err)),
+    }
+}
+
+fn open_counter(config: u64) -> io::Result<File> {
+    let attr = PerfEventAttr {
+        type_: PERF_TYPE_HARDWARE,
+        size: std::mem::size_of::<PerfEventAttr>() as u32,
+        config,
+        read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+        flags: ATTR_FLAG_DISABLED | ATTR_FLAG_INHERIT | ATTR_FLAG_EXCLUDE_KERNEL | ATTR_FLAG_EXCLUDE_HV,
+        ..Default::default()
+    };
+
+    // pid = 0, cpu = -1: count the calling thread, and the threads it
+    // starts from now on, on whichever CPU they run.
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            &attr as *const PerfEventAttr,
+            0 as libc::pid_t,
+            -1 as libc::c_int,
+            -1 as libc::c_int,
+            PERF_FLAG_FD_CLOEXEC,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { File::from_raw_fd(fd as libc::c_int) })
+}
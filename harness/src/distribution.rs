@@ -0,0 +1,219 @@
+//! Named distributions of `i32` arrays for the sorting benchmarks.
+//!
+//! A distribution is written as `NAME[:param=value,...]`, e.g. `uniform`,
+//! `uniform:max=10000`, `few-unique:k=8` or `nearly-sorted:swaps=100`, and
+//! its spec is recorded with the results so a run says what it sorted.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::args::Params;
+use crate::input::Rng;
+
+/// Zipf draws look their rank up in a table of this many entries at most.
+const ZIPF_MAX_VALUES: usize = 1 << 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// Uniform in `min..max`. The default, `0..i32::MAX`, is about the
+    /// range of libc `rand()`.
+    Uniform { min: i32, max: i32 },
+    /// `k` distinct values drawn uniformly, repeated in random order.
+    FewUnique { k: usize },
+    /// `0, 1, 2, ...`.
+    Sorted,
+    /// `n - 1, n - 2, ..., 0`.
+    Reversed,
+    /// `teeth` ascending runs of equal length.
+    Sawtooth { teeth: usize },
+    /// Ascending to the middle, then descending.
+    OrganPipe,
+    /// [`Sorted`](Distribution::Sorted) with `swaps` random pairs swapped.
+    NearlySorted { swaps: usize },
+    /// Every element 0.
+    AllEqual,
+    /// Values `0..k`, where value `r` is drawn with probability
+    /// proportional to `1 / (r + 1)^s`.
+    Zipf { s: f64, k: usize },
+    /// Musser's median-of-3 killer: a permutation of `1..=n` on which
+    /// picking the median of the first, middle and last element as pivot
+    /// splits off only two elements at a time.
+    Killer,
+}
+
+impl Default for Distribution {
+    fn default() -> Self {
+        Distribution::Uniform { min: 0, max: i32::MAX }
+    }
+}
+
+impl Distribution {
+    /// `size` values, random ones drawn from a generator seeded with `seed`.
+    pub fn generate(&self, size: usize, seed: u64) -> Vec<i32> {
+        let mut rng = Rng::new(seed);
+        match *self {
+            Distribution::Uniform { min, max } => (0..size).map(|_| rng.range_i32(min, max)).collect(),
+            Distribution::FewUnique { k } => {
+                let values: Vec<i32> = (0..k).map(|_| rng.range_i32(0, i32::MAX)).collect();
+                (0..size).map(|_| values[rng.below(k as u64) as usize]).collect()
+            }
+            Distribution::Sorted => (0..size as i32).collect(),
+            Distribution::Reversed => (0..size as i32).rev().collect(),
+            Distribution::Sawtooth { teeth } => {
+                let width = size.div_ceil(teeth).max(1);
+                (0..size).map(|i| (i % width) as i32).collect()
+            }
+            Distribution::OrganPipe => (0..size).map(|i| i.min(size - 1 - i) as i32).collect(),
+            Distribution::NearlySorted { swaps } => {
+                let mut arr: Vec<i32> = (0..size as i32).collect();
+                if size > 0 {
+                    for _ in 0..swaps {
+                        let (a, b) = (rng.below(size as u64), rng.below(size as u64));
+                        arr.swap(a as usize, b as usize);
+                    }
+                }
+                arr
+            }
+            Distribution::AllEqual => vec![0; size],
+            Distribution::Zipf { s, k } => {
+                let mut cumulative = Vec::with_capacity(k);
+                let mut total = 0.0;
+                for r in 0..k {
+                    total += 1.0 / ((r + 1) as f64).powf(s);
+                    cumulative.push(total);
+                }
+                (0..size)
+                    .map(|_| {
+                        let x = rng.next_f64() * total;
+                        cumulative.partition_point(|&c| c <= x).min(k - 1) as i32
+                    })
+                    .collect()
+            }
+            Distribution::Killer => median_of_3_killer(size),
+        }
+    }
+}
+
+/// Musser's sequence for `n = 2k` with even `k`: the odd numbers up to `k`
+/// in the odd places of the first half, `k + 1, k + 3, ...` in its even
+/// places, and `2, 4, ..., 2k` in the second half. For `n = 20`:
+/// `1 11 3 13 5 15 7 17 9 19 2 4 6 8 10 12 14 16 18 20`. Other lengths
+/// get the sequence for the next multiple of 4 below, followed by the
+/// remaining values in order.
+fn median_of_3_killer(n: usize) -> Vec<i32> {
+    let k = n / 4 * 2;
+    let mut arr: Vec<i32> = (1..=n as i32).collect();
+    for i in 1..=k {
+        arr[i - 1] = if i % 2 == 1 { i } else { k + i - 1 } as i32;
+        arr[k + i - 1] = (2 * i) as i32;
+    }
+    arr
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Distribution::Uniform { min, max } => write!(f, "uniform:min={},max={}", min, max),
+            Distribution::FewUnique { k } => write!(f, "few-unique:k={}", k),
+            Distribution::Sorted => write!(f, "sorted"),
+            Distribution::Reversed => write!(f, "reversed"),
+            Distribution::Sawtooth { teeth } => write!(f, "sawtooth:teeth={}", teeth),
+            Distribution::OrganPipe => write!(f, "organ-pipe"),
+            Distribution::NearlySorted { swaps } => write!(f, "nearly-sorted:swaps={}", swaps),
+            Distribution::AllEqual => write!(f, "all-equal"),
+            Distribution::Zipf { s, k } => write!(f, "zipf:s={},k={}", s, k),
+            Distribution::Killer => write!(f, "killer"),
+        }
+    }
+}
+
+impl FromStr for Distribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let mut params = Params::parse(params)?;
+        let distribution = match name {
+            "uniform" => {
+                let min = params.get("min")?.unwrap_or(0);
+                let max = params.get("max")?.unwrap_or(i32::MAX);
+                if min >= max {
+                    return Err(format!("need min < max, got min={} and max={}", min, max));
+                }
+                Distribution::Uniform { min, max }
+            }
+            "few-unique" => match params.get("k")?.unwrap_or(16) {
+                0 => return Err("k must be at least 1".to_string()),
+                k => Distribution::FewUnique { k },
+            },
+            "sorted" => Distribution::Sorted,
+            "reversed" => Distribution::Reversed,
+            "sawtooth" => match params.get("teeth")?.unwrap_or(16) {
+                0 => return Err("teeth must be at least 1".to_string()),
+                teeth => Distribution::Sawtooth { teeth },
+            },
+            "organ-pipe" => Distribution::OrganPipe,
+            "nearly-sorted" => Distribution::NearlySorted {
+                swaps: params.get("swaps")?.unwrap_or(100),
+            },
+            "all-equal" => Distribution::AllEqual,
+            "zipf" => {
+                let s: f64 = params.get("s")?.unwrap_or(1.0);
+                let k = params.get("k")?.unwrap_or(10000);
+                if s.is_nan() || s < 0.0 {
+                    return Err(format!("s must not be negative, got {}", s));
+                }
+                if k == 0 || k > ZIPF_MAX_VALUES {
+                    return Err(format!("k must be between 1 and {}, got {}", ZIPF_MAX_VALUES, k));
+                }
+                Distribution::Zipf { s, k }
+            }
+            "killer" => Distribution::Killer,
+            _ => {
+                return Err(format!(
+                    "unknown distribution: {name} (expected uniform, few-unique, sorted, reversed, sawtooth, \
+                     organ-pipe, nearly-sorted, all-equal, zipf or killer)"
+                ))
+            }
+        };
+        params.finish(name)?;
+        Ok(distribution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_round_trip() {
+        for distribution in [
+            Distribution::Uniform { min: -7, max: 10000 },
+            Distribution::FewUnique { k: 3 },
+            Distribution::Sorted,
+            Distribution::Reversed,
+            Distribution::Sawtooth { teeth: 5 },
+            Distribution::OrganPipe,
+            Distribution::NearlySorted { swaps: 0 },
+            Distribution::AllEqual,
+            Distribution::Zipf { s: 1.25, k: 100 },
+            Distribution::Killer,
+        ] {
+            let spec = distribution.to_string();
+            assert_eq!(spec.parse(), Ok(distribution), "{spec}");
+        }
+    }
+
+    #[test]
+    fn specs_fill_in_defaults() {
+        assert_eq!("uniform".parse(), Ok(Distribution::Uniform { min: 0, max: i32::MAX }));
+        assert_eq!("zipf:k=50".parse(), Ok(Distribution::Zipf { s: 1.0, k: 50 }));
+    }
+
+    #[test]
+    fn specs_reject_bad_parameters() {
+        for spec in ["uniform:min=5,max=5", "few-unique:k=0", "zipf:s=-1", "zipf:k=0", "sorted:n=1", "gaussian"] {
+            assert!(spec.parse::<Distribution>().is_err(), "{spec}");
+        }
+    }
+}
@@ -0,0 +1,15 @@
+pub mod args;
+pub mod benchmark;
+pub mod counters;
+pub mod distribution;
+pub mod input;
+pub mod ops;
+pub mod perf_log;
+pub mod record;
+pub mod report;
+pub mod runner;
+pub mod stats;
+
+pub use args::Args;
+pub use benchmark::{Benchmark, Language};
+pub use runner::Runner;
@@ -0,0 +1,114 @@
+//! Operation counts for the sorts: comparisons, swaps, recursive calls and
+//! the deepest recursion.
+//!
+//! The counts are only kept with the `instrument` feature. Without it the
+//! counting functions are empty and compile away, so the timed code is the
+//! same as uninstrumented code, and [`take`] returns `None`. The C twins
+//! count the same way with the macros in `harness/c/ops.h`.
+//!
+//! Counts are kept per thread, so a sort run on the benchmark thread is
+//! counted there.
+
+use std::fmt;
+
+#[cfg(feature = "instrument")]
+use std::cell::Cell;
+
+/// Whether this build counts operations.
+pub const ENABLED: bool = cfg!(feature = "instrument");
+
+/// Laid out like `OpCounts` in `harness/c/ops.h`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub comparisons: u64,
+    pub swaps: u64,
+    pub calls: u64,
+    pub max_depth: u64,
+}
+
+impl fmt::Display for OpCounts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} comparisons, {} swaps", self.comparisons, self.swaps)?;
+        if self.calls > 0 {
+            write!(f, ", {} calls, max depth {}", self.calls, self.max_depth)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "instrument")]
+thread_local! {
+    static COUNTS: Cell<OpCounts> = const {
+        Cell::new(OpCounts { comparisons: 0, swaps: 0, calls: 0, max_depth: 0 })
+    };
+    static DEPTH: Cell<u64> = const { Cell::new(0) };
+}
+
+#[cfg(feature = "instrument")]
+fn update(f: impl FnOnce(&mut OpCounts)) {
+    COUNTS.with(|counts| {
+        let mut c = counts.get();
+        f(&mut c);
+        counts.set(c);
+    });
+}
+
+/// Counts a comparison.
+#[inline(always)]
+pub fn compare() {
+    #[cfg(feature = "instrument")]
+    update(|c| c.comparisons += 1);
+}
+
+/// Counts a swap.
+#[inline(always)]
+pub fn swap() {
+    #[cfg(feature = "instrument")]
+    update(|c| c.swaps += 1);
+}
+
+/// Counts a call, which is one level deeper until the guard is dropped.
+#[inline(always)]
+pub fn call() -> Call {
+    #[cfg(feature = "instrument")]
+    {
+        let depth = DEPTH.with(|d| {
+            d.set(d.get() + 1);
+            d.get()
+        });
+        update(|c| {
+            c.calls += 1;
+            c.max_depth = c.max_depth.max(depth);
+        });
+    }
+    Call { _private: () }
+}
+
+/// Returned by [`call`]; leaves the call when dropped.
+#[must_use]
+pub struct Call {
+    _private: (),
+}
+
+#[cfg(feature = "instrument")]
+impl Drop for Call {
+    fn drop(&mut self) {
+        DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// Starts counting from 0.
+pub fn reset() {
+    #[cfg(feature = "instrument")]
+    COUNTS.with(|counts| counts.set(OpCounts::default()));
+}
+
+/// The counts since the last [`reset`], or `None` without the
+/// `instrument` feature.
+pub fn take() -> Option<OpCounts> {
+    #[cfg(feature = "instrument")]
+    return Some(COUNTS.with(|counts| counts.take()));
+    #[cfg(not(feature = "instrument"))]
+    None
+}
@@ -0,0 +1,400 @@
+//! Parser for the `perf stat` logs written by the old fork-and-shell
+//! benchmarks (`*_perf_output*.log`), so historical runs can be turned into
+//! [`Record`]s.
+//!
+//! A log may hold several `Performance counter stats for ...` blocks (one
+//! attached with `-p`, one for the whole command) as well as the
+//! benchmark's own `Time taken ... of size N: X seconds` lines. On hybrid
+//! CPUs every event appears once per core PMU (`cpu_atom/cycles/`,
+//! `cpu_core/cycles/`); [`PerfStat::total`] adds them up.
+
+use std::path::Path;
+
+use crate::record::Record;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Counted(f64),
+    NotCounted,
+    NotSupported,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Event name without the PMU, e.g. `cycles`.
+    pub name: String,
+    /// Core PMU on hybrid CPUs, e.g. `cpu_core`.
+    pub pmu: Option<String>,
+    pub value: Value,
+    /// Unit printed before the name, e.g. `msec` for `task-clock`.
+    pub unit: Option<String>,
+    /// perf's own derived metric after `#`, e.g. `1.27  insn per cycle`.
+    pub comment: Option<String>,
+    /// Share of the time the event was scheduled when perf had to
+    /// multiplex; perf has already scaled the value.
+    pub running_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PerfStat {
+    /// What perf was attached to, e.g. `process id '34184'`.
+    pub target: String,
+    pub events: Vec<Event>,
+    pub elapsed_seconds: Option<f64>,
+    pub user_seconds: Option<f64>,
+    pub sys_seconds: Option<f64>,
+    /// Index in [`PerfLog::timings`] of the `Time taken` line of the run
+    /// this block attached to, if any; see [`PerfStat::is_attached`].
+    pub timing: Option<usize>,
+}
+
+impl PerfStat {
+    /// Sum of all counted values of `name` across PMUs, or `None` if the
+    /// event was never counted.
+    pub fn total(&self, name: &str) -> Option<f64> {
+        let mut total = None;
+        for event in self.events.iter().filter(|e| e.name == name) {
+            if let Value::Counted(v) = event.value {
+                *total.get_or_insert(0.0) += v;
+            }
+        }
+        total
+    }
+
+    /// Whether perf was attached to a running process with `-p`, as the
+    /// old programs did to their own process before the measured call.
+    /// They printed the block and then their `Time taken` line, so that
+    /// line belongs to the block. Otherwise perf traced a whole command,
+    /// whose counts and elapsed time cover all of it, setup included.
+    pub fn is_attached(&self) -> bool {
+        self.target.starts_with("process id")
+    }
+
+    /// Size argument of the traced command, e.g. `80000000` for
+    /// `'./binary_search_perf_c 80000000'`. `None` when perf was attached
+    /// to a process id.
+    pub fn command_size(&self) -> Option<usize> {
+        if self.is_attached() {
+            return None;
+        }
+        self.target
+            .trim_matches('\'')
+            .split_whitespace()
+            .filter_map(|arg| arg.trim_matches('\'').parse().ok())
+            .next_back()
+    }
+}
+
+/// A `Time taken to ...: X seconds` line printed by the benchmark itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub description: String,
+    pub size: Option<usize>,
+    pub seconds: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PerfLog {
+    pub stats: Vec<PerfStat>,
+    pub timings: Vec<Timing>,
+}
+
+impl PerfLog {
+    pub fn parse(text: &str) -> PerfLog {
+        let mut log = PerfLog::default();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if let Some(target) = trimmed.strip_prefix("Performance counter stats for ") {
+                log.stats.push(PerfStat {
+                    target: target.trim_end_matches(':').to_string(),
+                    ..PerfStat::default()
+                });
+            } else if trimmed.starts_with("Time taken") {
+                if let Some(timing) = parse_timing(trimmed) {
+                    if let Some(stat) = log.stats.last_mut().filter(|s| s.is_attached() && s.timing.is_none()) {
+                        stat.timing = Some(log.timings.len());
+                    }
+                    log.timings.push(timing);
+                }
+            } else if let Some(stat) = log.stats.last_mut() {
+                parse_stat_line(stat, trimmed);
+            }
+        }
+        log
+    }
+}
+
+fn parse_stat_line(stat: &mut PerfStat, line: &str) {
+    if let Some(seconds) = line.strip_suffix("seconds time elapsed") {
+        stat.elapsed_seconds = parse_number(seconds.trim());
+    } else if let Some(seconds) = line.strip_suffix("seconds user") {
+        stat.user_seconds = parse_number(seconds.trim());
+    } else if let Some(seconds) = line.strip_suffix("seconds sys") {
+        stat.sys_seconds = parse_number(seconds.trim());
+    } else if let Some(event) = parse_event(line) {
+        stat.events.push(event);
+    }
+}
+
+fn parse_event(line: &str) -> Option<Event> {
+    let (value, rest) = if let Some(rest) = line.strip_prefix("<not counted>") {
+        (Value::NotCounted, rest)
+    } else if let Some(rest) = line.strip_prefix("<not supported>") {
+        (Value::NotSupported, rest)
+    } else {
+        let (number, rest) = line.split_once(char::is_whitespace)?;
+        (Value::Counted(parse_number(number)?), rest)
+    };
+
+    let (rest, running_percent) = match rest.trim_end().strip_suffix("%)") {
+        Some(head) => {
+            let open = head.rfind('(')?;
+            (&head[..open], head[open + 1..].trim().parse().ok())
+        }
+        None => (rest, None),
+    };
+    let (rest, comment) = match rest.split_once('#') {
+        Some((head, comment)) => (head, Some(comment.trim().to_string()).filter(|c| !c.is_empty())),
+        None => (rest, None),
+    };
+
+    let mut words = rest.split_whitespace();
+    let mut event = words.next()?;
+    let mut unit = None;
+    if let Some(next) = words.next() {
+        unit = Some(event.to_string());
+        event = next;
+    }
+
+    let (pmu, name) = match event.split('/').collect::<Vec<_>>()[..] {
+        [pmu, name, ..] if !name.is_empty() => (Some(pmu.to_string()), name.to_string()),
+        _ => (None, event.to_string()),
+    };
+
+    Some(Event {
+        name,
+        pmu,
+        value,
+        unit,
+        comment,
+        running_percent,
+    })
+}
+
+fn parse_timing(line: &str) -> Option<Timing> {
+    let (description, rest) = line.rsplit_once(':')?;
+    let seconds = parse_number(rest.split_whitespace().next()?)?;
+    let size = description
+        .split_once("of size ")
+        .and_then(|(_, size)| size.split_whitespace().next())
+        .and_then(|size| size.parse().ok());
+    Some(Timing {
+        description: description.to_string(),
+        size,
+        seconds,
+    })
+}
+
+/// Parses perf's comma-grouped numbers, e.g. `1,127,199,738,363` or
+/// `39,253.75`.
+fn parse_number(raw: &str) -> Option<f64> {
+    raw.replace(',', "").parse().ok()
+}
+
+/// Algorithm, language and machine tag taken from a log file name such as
+/// `bfs_c_perf_output_EC.log` -> (`bfs`, `c`, `EC`).
+pub fn describe_file(path: &Path) -> (String, String, Option<String>) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let (head, tag) = match stem.split_once("_perf_output") {
+        Some((head, tail)) => (head, tail.strip_prefix('_').map(str::to_string)),
+        None => (stem, None),
+    };
+    for (suffix, language) in [("_rs", "rust"), ("_c", "c")] {
+        if let Some(algorithm) = head.strip_suffix(suffix) {
+            return (algorithm.to_string(), language.to_string(), tag);
+        }
+    }
+    // e.g. quicksort/perf_output.log: fall back to the directory name.
+    let algorithm = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or(head);
+    (algorithm.to_string(), "unknown".to_string(), tag)
+}
+
+/// Turns every counter block of a log into one record, with the time and
+/// size of the same run. A block attached with `-p` takes both from its
+/// run's `Time taken` line, and has neither without one, as perf's elapsed
+/// time includes the old sleep before the measured call. A block for a
+/// whole command takes perf's elapsed time and the command's size
+/// argument, since its counts cover the whole process too.
+pub fn to_records(path: &Path, log: &PerfLog) -> Vec<Record> {
+    let (algorithm, language, tag) = describe_file(path);
+    log.stats
+        .iter()
+        .enumerate()
+        .map(|(i, stat)| {
+            let (elapsed_seconds, size) = if stat.is_attached() {
+                let timing = stat.timing.map(|t| &log.timings[t]);
+                (timing.map(|t| t.seconds), timing.and_then(|t| t.size))
+            } else {
+                (stat.elapsed_seconds, stat.command_size())
+            };
+            let counter = |name| stat.total(name).map(|v| v as u64);
+            Record {
+                algorithm: algorithm.clone(),
+                variant: String::new(),
+                language: language.clone(),
+                threads: None,
+                size,
+                seed: None,
+                run: i + 1,
+                warmup: 0,
+                elapsed_seconds,
+                cycles: counter("cycles"),
+                instructions: counter("instructions"),
+                cache_references: counter("cache-references"),
+                cache_misses: counter("cache-misses"),
+                git_commit: "unknown".to_string(),
+                compiler: "unknown".to_string(),
+                compiler_flags: String::new(),
+                host_cpu: tag.clone().unwrap_or_else(|| "unknown".to_string()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HYBRID: &str = "
+[ Perf Stat Output ]
+
+Time taken to search graph of size 25000: 1.951195 seconds
+
+ Performance counter stats for 'cargo run --bin bfs_perf --release -- 25000':
+
+          4,502.49 msec task-clock                       #    0.821 CPUs utilized
+    16,689,494,829      cpu_atom/cycles/                 #    3.707 GHz                         (0.11%)
+    22,256,404,747      cpu_core/cycles/                 #    4.943 GHz                         (99.81%)
+     <not counted>      cpu_atom/cache-misses/                                                  (0.00%)
+               690      cpu_core/cache-misses/           #   31.89% of all cache refs
+   <not supported>      cache-references
+
+       5.484423341 seconds time elapsed
+
+       3.828688000 seconds user
+       0.668366000 seconds sys
+";
+
+    #[test]
+    fn parses_events_across_pmus() {
+        let log = PerfLog::parse(HYBRID);
+        assert_eq!(log.stats.len(), 1);
+        let stat = &log.stats[0];
+        assert_eq!(stat.target, "'cargo run --bin bfs_perf --release -- 25000'");
+        assert_eq!(stat.total("cycles"), Some(16_689_494_829.0 + 22_256_404_747.0));
+        assert_eq!(stat.total("cache-misses"), Some(690.0));
+        assert_eq!(stat.total("cache-references"), None);
+        assert_eq!(stat.total("instructions"), None);
+        assert_eq!(stat.elapsed_seconds, Some(5.484423341));
+        assert_eq!((stat.user_seconds, stat.sys_seconds), (Some(3.828688), Some(0.668366)));
+        assert_eq!(stat.command_size(), Some(25000));
+
+        let clock = &stat.events[0];
+        assert_eq!((clock.name.as_str(), clock.unit.as_deref()), ("task-clock", Some("msec")));
+        assert_eq!(clock.value, Value::Counted(4502.49));
+        assert_eq!(clock.comment.as_deref(), Some("0.821 CPUs utilized"));
+        let atom = &stat.events[1];
+        assert_eq!((atom.pmu.as_deref(), atom.running_percent), (Some("cpu_atom"), Some(0.11)));
+        assert_eq!(stat.events[3].value, Value::NotCounted);
+        assert_eq!(stat.events[5].value, Value::NotSupported);
+    }
+
+    #[test]
+    fn parses_timing_lines() {
+        let log = PerfLog::parse("Time taken to search graph of size 2000: 0.025530 seconds, 2000 nodes reached\n");
+        assert_eq!(
+            log.timings,
+            vec![Timing {
+                description: "Time taken to search graph of size 2000".to_string(),
+                size: Some(2000),
+                seconds: 0.02553,
+            }]
+        );
+        assert_eq!(PerfLog::parse("Time taken: 1.5 seconds").timings[0].size, None);
+    }
+
+    #[test]
+    fn pairs_attached_blocks_with_their_own_run() {
+        let text = " Performance counter stats for process id '1':\n\n  100  cycles\n\n  2.0 seconds time elapsed\n\
+                    Time taken to sort the array of size 10: 0.5 seconds\n\
+                    \n Performance counter stats for process id '2':\n\n  200  cycles\n\n  3.0 seconds time elapsed\n";
+        let log = PerfLog::parse(text);
+        assert_eq!(log.stats.iter().map(|s| s.timing).collect::<Vec<_>>(), vec![Some(0), None]);
+        assert_eq!(log.stats[0].command_size(), None);
+
+        let records = to_records(Path::new("quicksort/quicksort_c_perf_output_EC.log"), &log);
+        assert_eq!((records[0].elapsed_seconds, records[0].size, records[0].cycles), (Some(0.5), Some(10), Some(100)));
+        // Without its own Time taken line, perf's elapsed time would include
+        // the sleep, so the second run has no time and no size.
+        assert_eq!((records[1].elapsed_seconds, records[1].size, records[1].cycles), (None, None, Some(200)));
+        assert_eq!((records[1].algorithm.as_str(), records[1].language.as_str()), ("quicksort", "c"));
+        assert_eq!(records[1].host_cpu, "EC");
+    }
+
+    #[test]
+    fn reads_a_committed_c_log() {
+        // The C program attached perf to itself and was run under perf stat
+        // as a whole, so the log holds both kinds of block.
+        let path = Path::new("../bfs/bfs_c_perf_output_EC.log");
+        let log = PerfLog::parse(include_str!("../../bfs/bfs_c_perf_output_EC.log"));
+        let records = to_records(path, &log);
+        assert_eq!(records.len(), 2);
+
+        let attached = &records[0];
+        assert_eq!((attached.elapsed_seconds, attached.size), (Some(0.000003), Some(25000)));
+        assert_eq!((attached.cycles, attached.instructions), (Some(43_711), Some(17_052)));
+        assert_eq!((attached.cache_references, attached.cache_misses), (Some(3_989), Some(891)));
+
+        let whole = &records[1];
+        assert_eq!((whole.elapsed_seconds, whole.size), (Some(5.228182609), Some(25000)));
+        assert_eq!(whole.cycles, Some(14_669_088_688 + 20_914_344_315));
+        assert_eq!(whole.run, 2);
+    }
+
+    #[test]
+    fn reads_a_committed_rust_log() {
+        // Only the whole cargo run was traced; its Time taken line covers
+        // the measured call alone and must not be paired with the block.
+        let path = Path::new("../bfs/bfs_rs_perf_output_EC.log");
+        let log = PerfLog::parse(include_str!("../../bfs/bfs_rs_perf_output_EC.log"));
+        assert_eq!(log.timings[0].seconds, 1.951195);
+        let records = to_records(path, &log);
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].elapsed_seconds, records[0].size), (Some(5.484423341), Some(25000)));
+        assert_eq!((records[0].algorithm.as_str(), records[0].language.as_str()), ("bfs", "rust"));
+    }
+
+    #[test]
+    fn leaves_unattributed_committed_logs_without_time() {
+        let log = PerfLog::parse(include_str!("../../bfs/bfs_c_perf_output.log"));
+        let records = to_records(Path::new("bfs/bfs_c_perf_output.log"), &log);
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].elapsed_seconds, records[0].size), (None, None));
+        assert!(records[0].cycles.is_some());
+    }
+
+    #[test]
+    fn describes_log_file_names() {
+        let describe = |path| describe_file(Path::new(path));
+        assert_eq!(describe("bfs/bfs_rs_perf_output.log"), ("bfs".to_string(), "rust".to_string(), None));
+        assert_eq!(
+            describe("selection_sort/selection_sort_c_perf_output_YT.log"),
+            ("selection_sort".to_string(), "c".to_string(), Some("YT".to_string()))
+        );
+        assert_eq!(describe("quicksort/perf_output.log"), ("quicksort".to_string(), "unknown".to_string(), None));
+    }
+}
@@ -0,0 +1,173 @@
+//! Machine-readable result records, written as JSON lines or CSV.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// One measured run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub algorithm: String,
+    /// Benchmark-specific configuration, e.g. the graph representation.
+    /// Empty when the benchmark has none.
+    #[serde(default)]
+    pub variant: String,
+    pub language: String,
+    /// Worker threads of a parallel implementation; `None` when it is
+    /// sequential.
+    #[serde(default)]
+    pub threads: Option<usize>,
+    /// `None` for blocks of old perf logs that cannot be tied to a size.
+    pub size: Option<usize>,
+    pub seed: Option<u64>,
+    /// 1-based index among the measured runs.
+    pub run: usize,
+    pub warmup: usize,
+    /// `None` for blocks of old perf logs that cannot be tied to a time.
+    pub elapsed_seconds: Option<f64>,
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+    pub cache_references: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub git_commit: String,
+    pub compiler: String,
+    pub compiler_flags: String,
+    pub host_cpu: String,
+}
+
+const CSV_HEADER: &str = "algorithm,variant,language,threads,size,seed,run,warmup,elapsed_seconds,cycles,instructions,\
+cache_references,cache_misses,git_commit,compiler,compiler_flags,host_cpu";
+
+impl Record {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("record serializes")
+    }
+
+    pub fn to_csv(&self) -> String {
+        fn opt<T: ToString>(v: Option<T>) -> String {
+            v.map(|v| v.to_string()).unwrap_or_default()
+        }
+        [
+            csv_field(&self.algorithm),
+            csv_field(&self.variant),
+            csv_field(&self.language),
+            opt(self.threads),
+            opt(self.size),
+            opt(self.seed),
+            self.run.to_string(),
+            self.warmup.to_string(),
+            opt(self.elapsed_seconds),
+            opt(self.cycles),
+            opt(self.instructions),
+            opt(self.cache_references),
+            opt(self.cache_misses),
+            csv_field(&self.git_commit),
+            csv_field(&self.compiler),
+            csv_field(&self.compiler_flags),
+            csv_field(&self.host_cpu),
+        ]
+        .join(",")
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Facts about the build and the machine shared by every record.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub git_commit: String,
+    pub compiler: String,
+    pub compiler_flags: String,
+    pub host_cpu: String,
+}
+
+impl Environment {
+    /// The build facts are captured by the harness build script, so they
+    /// describe how the workspace was compiled.
+    pub fn current() -> Self {
+        Environment {
+            git_commit: env!("HARNESS_GIT_COMMIT").to_string(),
+            compiler: env!("HARNESS_COMPILER").to_string(),
+            compiler_flags: env!("HARNESS_COMPILER_FLAGS").to_string(),
+            host_cpu: host_cpu(),
+        }
+    }
+}
+
+fn host_cpu() -> String {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|l| l.starts_with("model name"))
+                .and_then(|l| l.split_once(':'))
+                .map(|(_, name)| name.trim().to_string())
+        })
+        .unwrap_or_else(|| std::env::consts::ARCH.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" | "jsonl" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format: {s} (expected text, json or csv)")),
+        }
+    }
+}
+
+impl Format {
+    /// Guesses the format of a results file from its extension.
+    pub fn from_path(path: &Path) -> Format {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Format::Csv,
+            _ => Format::Json,
+        }
+    }
+}
+
+pub fn write_records<W: Write>(out: &mut W, format: Format, records: &[Record], header: bool) -> io::Result<()> {
+    match format {
+        Format::Text => {}
+        Format::Json => {
+            for record in records {
+                writeln!(out, "{}", record.to_json())?;
+            }
+        }
+        Format::Csv => {
+            if header {
+                writeln!(out, "{}", CSV_HEADER)?;
+            }
+            for record in records {
+                writeln!(out, "{}", record.to_csv())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Appends records to `path`, writing the CSV header only to a new file.
+pub fn append_records(path: &Path, format: Format, records: &[Record]) -> io::Result<()> {
+    let is_new = fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true);
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    write_records(&mut file, format, records, is_new)
+}
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use crate::benchmark::Language;
+use crate::counters::{Counters, Unavailable};
+use crate::stats::{mann_whitney_u, Summary};
+
+/// Wall time and (optionally) hardware counters for one measured run.
+pub struct Measurement {
+    pub elapsed: Duration,
+    /// `None` when counters were not requested.
+    pub counters: Option<Result<Counters, Unavailable>>,
+}
+
+const COUNTER_NAMES: [&str; 4] = ["cycles", "instructions", "cache-references", "cache-misses"];
+
+fn counter_values(c: &Counters) -> [u64; 4] {
+    [c.cycles, c.instructions, c.cache_references, c.cache_misses]
+}
+
+pub fn print_text(description: &str, summary: Option<&str>, measurements: &[Measurement], warmup: usize) {
+    if measurements.len() == 1 {
+        print_single(description, summary, &measurements[0]);
+    } else {
+        print_repeated(description, summary, measurements, warmup);
+    }
+}
+
+fn print_single(description: &str, summary: Option<&str>, measurement: &Measurement) {
+    if let Some(counters) = &measurement.counters {
+        println!("\n[ Hardware Counters ]");
+        match counters {
+            Ok(c) => println!("{}", c),
+            Err(e) => println!("{}", e),
+        }
+    }
+
+    print_time(description, measurement.elapsed.as_secs_f64(), None, summary);
+}
+
+fn print_repeated(description: &str, summary: Option<&str>, measurements: &[Measurement], warmup: usize) {
+    let times: Vec<f64> = measurements.iter().map(|m| m.elapsed.as_secs_f64()).collect();
+    let time_summary = Summary::new(&times).expect("at least one measurement");
+
+    println!("\n[ Statistics over {} runs, {} warmup ]", measurements.len(), warmup);
+    println!(
+        "{:<18}{:>14}{:>14}{:>14}{:>14}{:>14}{:>14}   95% CI of mean",
+        "", "min", "median", "mean", "stddev", "p95", "p99"
+    );
+    print_row("time (s)", &time_summary, 6);
+
+    let mut outliers = Vec::new();
+    if !time_summary.outliers.is_empty() {
+        outliers.push(("time", time_summary.outliers.clone()));
+    }
+
+    match counter_samples(measurements) {
+        Some(Ok(samples)) => {
+            for (name, values) in COUNTER_NAMES.iter().zip(samples.iter()) {
+                let counter_summary = Summary::new(values).expect("at least one measurement");
+                print_row(name, &counter_summary, 0);
+                if !counter_summary.outliers.is_empty() {
+                    outliers.push((name, counter_summary.outliers));
+                }
+            }
+        }
+        Some(Err(e)) => println!("{}", e),
+        None => {}
+    }
+
+    if !outliers.is_empty() {
+        println!("\nNoisy runs (MAD outliers):");
+        for (name, runs) in outliers {
+            let runs: Vec<String> = runs.iter().map(|i| format!("#{}", i + 1)).collect();
+            println!("  {:<18}{}", name, runs.join(", "));
+        }
+    }
+
+    print_time(description, time_summary.median, Some(measurements.len()), summary);
+}
+
+fn print_row(name: &str, s: &Summary, precision: usize) {
+    println!(
+        "{:<18}{:>14.p$}{:>14.p$}{:>14.p$}{:>14.p$}{:>14.p$}{:>14.p$}   [{:.p$}, {:.p$}]",
+        name,
+        s.min,
+        s.median,
+        s.mean,
+        s.stddev,
+        s.p95,
+        s.p99,
+        s.ci95.0,
+        s.ci95.1,
+        p = precision
+    );
+}
+
+fn print_time(description: &str, seconds: f64, median_of: Option<usize>, summary: Option<&str>) {
+    let mut line = format!("\nTime taken {}: {:.6} seconds", description, seconds);
+    if let Some(runs) = median_of {
+        line.push_str(&format!(" (median of {} runs)", runs));
+    }
+    if let Some(summary) = summary {
+        line.push_str(", ");
+        line.push_str(summary);
+    }
+    println!("{}", line);
+}
+
+/// Per-counter samples across all runs, or the first reason the counters
+/// were unavailable.
+fn counter_samples(measurements: &[Measurement]) -> Option<Result<[Vec<f64>; 4], Unavailable>> {
+    let mut samples: [Vec<f64>; 4] = Default::default();
+    for m in measurements {
+        match m.counters.as_ref()? {
+            Ok(c) => {
+                for (values, v) in samples.iter_mut().zip(counter_values(c)) {
+                    values.push(v as f64);
+                }
+            }
+            Err(e) => return Some(Err(e.clone())),
+        }
+    }
+    Some(Ok(samples))
+}
+
+/// Medians of two implementations measured on the same input, their ratio
+/// and a two-sided Mann-Whitney U p-value for the time and every counter.
+pub fn print_comparison(a: (Language, &[Measurement]), b: (Language, &[Measurement])) {
+    let (a_name, b_name) = (a.0.to_string(), b.0.to_string());
+    println!("\n[ {} vs {}, {} runs each ]", a_name, b_name, a.1.len());
+    println!(
+        "{:<18}{:>16}{:>16}{:>10}{:>12}",
+        "",
+        format!("{} median", a_name),
+        format!("{} median", b_name),
+        format!("{}/{}", a_name, b_name),
+        "p-value"
+    );
+
+    let times = |runs: &[Measurement]| runs.iter().map(|m| m.elapsed.as_secs_f64()).collect::<Vec<_>>();
+    print_comparison_row("time (s)", &times(a.1), &times(b.1), 6);
+    if let (Some(Ok(a_counters)), Some(Ok(b_counters))) = (counter_samples(a.1), counter_samples(b.1)) {
+        for (i, name) in COUNTER_NAMES.iter().enumerate() {
+            print_comparison_row(name, &a_counters[i], &b_counters[i], 0);
+        }
+    }
+    println!("\n* difference is significant at the 5% level");
+}
+
+/// One row of a comparison table: both medians with `precision` decimals,
+/// their ratio and the p-value, or `n/a` if either side has no samples.
+pub fn print_comparison_row(name: &str, a: &[f64], b: &[f64], precision: usize) {
+    let (Some(a_summary), Some(b_summary)) = (Summary::new(a), Summary::new(b)) else {
+        println!("{:<18}{:>16}", name, "n/a");
+        return;
+    };
+    let ratio = if b_summary.median != 0.0 {
+        format!("{:.3}", a_summary.median / b_summary.median)
+    } else {
+        "-".to_string()
+    };
+    let significance = match mann_whitney_u(a, b) {
+        Some(test) if test.p_value < 0.05 => format!("{:.4} *", test.p_value),
+        Some(test) => format!("{:.4}  ", test.p_value),
+        None => "-".to_string(),
+    };
+    println!(
+        "{:<18}{:>16.p$}{:>16.p$}{:>10}{:>12}",
+        name,
+        a_summary.median,
+        b_summary.median,
+        ratio,
+        significance,
+        p = precision
+    );
+}
+
+/// Median time per thread count against the sequential baseline in the
+/// first row, with the speedup and the parallel efficiency (speedup per
+/// thread).
+pub fn print_scaling(language: Language, rows: &[(Option<usize>, &[Measurement])]) {
+    let median = |runs: &[Measurement]| {
+        let times: Vec<f64> = runs.iter().map(|m| m.elapsed.as_secs_f64()).collect();
+        Summary::new(&times).map_or(f64::NAN, |s| s.median)
+    };
+    let baseline = median(rows[0].1);
+    println!("\n[ {} scaling, {} runs each ]", language, rows[0].1.len());
+    println!("{:<18}{:>16}{:>10}{:>12}", "threads", "median (s)", "speedup", "efficiency");
+    for &(threads, runs) in rows {
+        let time = median(runs);
+        let speedup = baseline / time;
+        let (label, efficiency) = match threads {
+            Some(threads) => (threads.to_string(), format!("{:.1}%", 100.0 * speedup / threads as f64)),
+            None => ("sequential".to_string(), "-".to_string()),
+        };
+        println!("{:<18}{:>16.6}{:>10.3}{:>12}", label, time, speedup, efficiency);
+    }
+}
@@ -0,0 +1,387 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::Instant;
+
+use crate::args::Args;
+use crate::benchmark::{Benchmark, Language};
+use crate::counters::{CounterGroup, Unavailable};
+use crate::input::{InputFile, DEFAULT_SEED};
+use crate::record::{self, Environment, Format, Record};
+use crate::report::{self, Measurement};
+
+/// Parses the command line, builds the input, measures the requested runs,
+/// verifies each of them and prints the result.
+pub struct Runner {
+    counters: bool,
+    warmup: usize,
+    runs: usize,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Runner {
+            counters: false,
+            warmup: 0,
+            runs: 1,
+        }
+    }
+}
+
+struct Config {
+    size: usize,
+    warmup: usize,
+    runs: usize,
+    format: Format,
+    output: Option<PathBuf>,
+    /// Implementations to measure, alternating within every run.
+    settings: Vec<Setting>,
+    /// `--threads` was given, so every setting picks its thread count.
+    sweep: bool,
+    /// Write the generated input here instead of running.
+    dump_input: Option<PathBuf>,
+}
+
+/// One implementation of the benchmark: a language and, with `--threads`,
+/// a thread count (`None` for the sequential baseline).
+#[derive(Debug, Clone, Copy)]
+struct Setting {
+    language: Language,
+    threads: Option<usize>,
+}
+
+impl Setting {
+    /// Switches `bench` to this setting. `false` if it has no such version.
+    fn apply<B: Benchmark>(&self, bench: &mut B, sweep: bool) -> bool {
+        bench.set_language(self.language) && (!sweep || bench.set_threads(self.threads))
+    }
+
+    fn label(&self, sweep: bool) -> String {
+        match self.threads {
+            _ if !sweep => self.language.to_string(),
+            Some(1) => format!("{}, 1 thread", self.language),
+            Some(threads) => format!("{}, {} threads", self.language, threads),
+            None => format!("{}, sequential", self.language),
+        }
+    }
+}
+
+impl Runner {
+    pub fn new() -> Self {
+        Runner::default()
+    }
+
+    /// Collects hardware counters around every run. Can also be turned on
+    /// from the command line with `--counters`.
+    pub fn counters(mut self, enabled: bool) -> Self {
+        self.counters = enabled;
+        self
+    }
+
+    /// Default number of unmeasured warmup runs (`--warmup`).
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Default number of measured runs (`--runs`).
+    pub fn runs(mut self, runs: usize) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    pub fn run<B: Benchmark>(&self, bench: B) {
+        self.run_with_args(bench, Args::from_env());
+    }
+
+    pub fn run_with_args<B: Benchmark>(&self, mut bench: B, mut args: Args) {
+        let program = args.program().to_string();
+        // Opened before the benchmark is configured, since threads started
+        // before the counters, such as the pools built in `configure`, do
+        // not inherit them.
+        let counters = self.counters | args.flag("--counters");
+        let mut group = if counters {
+            Some(CounterGroup::open())
+        } else {
+            None
+        };
+        let config = match self.configure(&mut bench, &mut args) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                eprintln!("{}", usage(&program, &bench));
+                exit(1);
+            }
+        };
+
+        let mut input = bench.setup(config.size);
+        if let Some(path) = &config.dump_input {
+            dump_input(&bench, &input, config.size, path);
+            return;
+        }
+
+        let mut measurements: Vec<Vec<Measurement>> =
+            config.settings.iter().map(|_| Vec::with_capacity(config.runs)).collect();
+        let mut summaries = vec![None; config.settings.len()];
+        for i in 0..config.warmup + config.runs {
+            // Alternate the settings so drift in machine state hits all equally.
+            for (l, setting) in config.settings.iter().enumerate() {
+                setting.apply(&mut bench, config.sweep);
+                if i > 0 || l > 0 {
+                    bench.reset(&mut input, config.size);
+                }
+                let (output, measurement) = measure(&mut bench, &mut input, group.as_mut());
+
+                if let Err(err) = bench.verify(&input, &output) {
+                    eprintln!(
+                        "{} ({}): verification failed: {}",
+                        bench.name(),
+                        setting.label(config.sweep),
+                        err
+                    );
+                    exit(1);
+                }
+
+                if i >= config.warmup {
+                    summaries[l] = bench.summary(&output);
+                    measurements[l].push(measurement);
+                }
+            }
+        }
+
+        let mut records = Vec::new();
+        for (setting, measurements) in config.settings.iter().zip(&measurements) {
+            // The variant may depend on the setting.
+            setting.apply(&mut bench, config.sweep);
+            records.extend(self::records(&bench, &config, setting.language, measurements));
+        }
+        match &config.output {
+            // Records go to the file; the human-readable report still goes to stdout.
+            Some(path) => {
+                let format = match config.format {
+                    Format::Text => Format::from_path(path),
+                    format => format,
+                };
+                if let Err(err) = record::append_records(path, format, &records) {
+                    eprintln!("Failed to write results to {}: {}", path.display(), err);
+                    exit(1);
+                }
+            }
+            None if config.format != Format::Text => {
+                let _ = record::write_records(&mut io::stdout().lock(), config.format, &records, true);
+                return;
+            }
+            None => {}
+        }
+        let description = bench.describe(config.size);
+        if let [only] = &measurements[..] {
+            report::print_text(&description, summaries[0].as_deref(), only, config.warmup);
+            return;
+        }
+        for ((setting, measurements), summary) in config.settings.iter().zip(&measurements).zip(&summaries) {
+            println!("\n[ {} ]", setting.label(config.sweep));
+            report::print_text(&description, summary.as_deref(), measurements, config.warmup);
+        }
+        if config.sweep {
+            // One curve per language, each starting with its sequential baseline.
+            let mut start = 0;
+            for settings in config.settings.chunk_by(|a, b| a.language == b.language) {
+                let rows: Vec<(Option<usize>, &[Measurement])> = settings
+                    .iter()
+                    .zip(&measurements[start..])
+                    .map(|(setting, m)| (setting.threads, &m[..]))
+                    .collect();
+                report::print_scaling(settings[0].language, &rows);
+                start += settings.len();
+            }
+        } else {
+            report::print_comparison(
+                (config.settings[0].language, &measurements[0]),
+                (config.settings[1].language, &measurements[1]),
+            );
+        }
+    }
+
+    fn configure<B: Benchmark>(&self, bench: &mut B, args: &mut Args) -> Result<Config, String> {
+        let warmup = args.parse("--warmup")?.unwrap_or(self.warmup);
+        let runs = args.parse("--runs")?.unwrap_or(self.runs);
+        if runs == 0 {
+            return Err("--runs must be at least 1".to_string());
+        }
+        let format = args.parse("--format")?.unwrap_or(Format::Text);
+        let output = args.value("--output")?.map(PathBuf::from);
+        let seed = args.parse("--seed")?.unwrap_or(DEFAULT_SEED);
+        let dump_input = args.value("--dump-input")?.map(PathBuf::from);
+        let languages = match args.value("--lang")?.as_deref() {
+            None => vec![bench.language()],
+            Some("both") => vec![Language::Rust, Language::C],
+            Some(raw) => vec![raw.parse()?],
+        };
+        let thread_counts = match args.value("--threads")? {
+            Some(raw) => Some(parse_thread_counts(&raw)?),
+            None => None,
+        };
+        bench.set_seed(seed);
+        bench.configure(args)?;
+
+        let sweep = thread_counts.is_some();
+        let mut settings = Vec::new();
+        for &language in &languages {
+            settings.push(Setting { language, threads: None });
+            for &threads in thread_counts.iter().flatten() {
+                settings.push(Setting {
+                    language,
+                    threads: Some(threads),
+                });
+            }
+        }
+        // After `configure`, since the options may rule out the C version
+        // or the parallel one.
+        for setting in &settings {
+            if !bench.set_language(setting.language) {
+                return Err(format!("{} has no {} implementation", display_name(bench), setting.language));
+            }
+            if sweep && !bench.set_threads(setting.threads) {
+                return Err(match setting.threads {
+                    Some(threads) => format!(
+                        "{} has no {} version that runs on {} threads",
+                        display_name(bench),
+                        setting.language,
+                        threads
+                    ),
+                    None => format!("{} has no sequential {} baseline", display_name(bench), setting.language),
+                });
+            }
+        }
+
+        let size = match (args.positional(), bench.default_size()) {
+            (Some(raw), _) => match raw.parse() {
+                Ok(n) if n > 0 => n,
+                _ => return Err(format!("{} must be a positive integer", bench.size_name())),
+            },
+            (None, Some(default)) => default,
+            (None, None) => return Err(format!("missing {}", bench.size_name())),
+        };
+
+        args.finish()?;
+        Ok(Config {
+            size,
+            warmup,
+            runs,
+            format,
+            output,
+            settings,
+            sweep,
+            dump_input,
+        })
+    }
+}
+
+/// `--threads 1,2,4,8`.
+fn parse_thread_counts(raw: &str) -> Result<Vec<usize>, String> {
+    raw.split(',')
+        .map(|count| match count.trim().parse() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("invalid value for --threads: {raw} (expected positive counts such as 1,2,4,8)")),
+        })
+        .collect()
+}
+
+/// The benchmark name with its variant, for error messages.
+fn display_name<B: Benchmark>(bench: &B) -> String {
+    match bench.variant() {
+        variant if variant.is_empty() => bench.name().to_string(),
+        variant => format!("{} ({})", bench.name(), variant),
+    }
+}
+
+fn measure<B: Benchmark>(
+    bench: &mut B,
+    input: &mut B::Input,
+    group: Option<&mut Result<CounterGroup, Unavailable>>,
+) -> (B::Output, Measurement) {
+    let mut timed = || {
+        let start_time = Instant::now();
+        let output = bench.run(input);
+        (output, start_time.elapsed())
+    };
+
+    match group {
+        Some(Ok(group)) => {
+            let ((output, elapsed), counters) = group.measure(timed);
+            (output, Measurement { elapsed, counters: Some(counters) })
+        }
+        Some(Err(err)) => {
+            let (output, elapsed) = timed();
+            (output, Measurement { elapsed, counters: Some(Err(err.clone())) })
+        }
+        None => {
+            let (output, elapsed) = timed();
+            (output, Measurement { elapsed, counters: None })
+        }
+    }
+}
+
+fn dump_input<B: Benchmark>(bench: &B, input: &B::Input, size: usize, path: &Path) {
+    let Some(arrays) = bench.input_arrays(input) else {
+        eprintln!("Error: {} cannot dump its input", bench.name());
+        exit(1);
+    };
+    let file = InputFile {
+        seed: bench.seed().unwrap_or(0),
+        size: size as u64,
+        arrays,
+    };
+    if let Err(err) = file.write(path) {
+        eprintln!("Failed to write input to {}: {}", path.display(), err);
+        exit(1);
+    }
+    println!("Wrote the input for {} of size {} to {}", bench.name(), size, path.display());
+}
+
+fn records<B: Benchmark>(bench: &B, config: &Config, language: Language, measurements: &[Measurement]) -> Vec<Record> {
+    let env = Environment::current();
+    measurements
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let counters = m.counters.as_ref().and_then(|c| c.as_ref().ok());
+            Record {
+                algorithm: bench.name().to_string(),
+                variant: bench.variant(),
+                language: language.to_string(),
+                threads: bench.threads(),
+                size: Some(config.size),
+                seed: bench.seed(),
+                run: i + 1,
+                warmup: config.warmup,
+                elapsed_seconds: Some(m.elapsed.as_secs_f64()),
+                cycles: counters.map(|c| c.cycles),
+                instructions: counters.map(|c| c.instructions),
+                cache_references: counters.map(|c| c.cache_references),
+                cache_misses: counters.map(|c| c.cache_misses),
+                git_commit: env.git_commit.clone(),
+                compiler: env.compiler.clone(),
+                compiler_flags: env.compiler_flags.clone(),
+                host_cpu: env.host_cpu.clone(),
+            }
+        })
+        .collect()
+}
+
+fn usage<B: Benchmark>(program: &str, bench: &B) -> String {
+    let size = match bench.default_size() {
+        Some(_) => format!("[{}]", bench.size_name()),
+        None => format!("<{}>", bench.size_name()),
+    };
+    let mut usage = format!(
+        "Usage: {} {} [--counters] [--warmup N] [--runs N] [--format text|json|csv] [--output FILE] \
+         [--lang rust|c|both] [--threads N[,N...]] [--seed N] [--dump-input FILE]",
+        program, size
+    );
+    if !bench.options().is_empty() {
+        usage.push(' ');
+        usage.push_str(bench.options());
+    }
+    usage
+}
@@ -0,0 +1,247 @@
+//! Summary statistics over repeated measurements.
+
+/// Modified z-score above which a sample is flagged (Iglewicz and Hoaglin).
+const OUTLIER_THRESHOLD: f64 = 3.5;
+
+/// Two-sided 95% quantiles of Student's t distribution for 1..=30 degrees
+/// of freedom.
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+    2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub samples: usize,
+    pub min: f64,
+    pub max: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub p95: f64,
+    pub p99: f64,
+    /// 95% confidence interval of the mean.
+    pub ci95: (f64, f64),
+    /// Indices of samples whose MAD-based modified z-score exceeds 3.5.
+    pub outliers: Vec<usize>,
+}
+
+impl Summary {
+    /// Returns `None` for an empty sample.
+    pub fn new(samples: &[f64]) -> Option<Summary> {
+        if samples.is_empty() {
+            return None;
+        }
+        let n = samples.len();
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let stddev = if n > 1 {
+            let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+            var.sqrt()
+        } else {
+            0.0
+        };
+        let half_width = if n > 1 {
+            t_quantile_95(n - 1) * stddev / (n as f64).sqrt()
+        } else {
+            0.0
+        };
+        let median = percentile(&sorted, 50.0);
+
+        Some(Summary {
+            samples: n,
+            min: sorted[0],
+            max: sorted[n - 1],
+            median,
+            mean,
+            stddev,
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            ci95: (mean - half_width, mean + half_width),
+            outliers: mad_outliers(samples, median),
+        })
+    }
+}
+
+/// Percentile with linear interpolation between closest ranks. `sorted`
+/// must be non-empty and in ascending order.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+fn t_quantile_95(degrees_of_freedom: usize) -> f64 {
+    match degrees_of_freedom {
+        0 => f64::NAN,
+        1..=30 => T_95[degrees_of_freedom - 1],
+        31..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+fn mad_outliers(samples: &[f64], median: f64) -> Vec<usize> {
+    let mut deviations: Vec<f64> = samples.iter().map(|x| (x - median).abs()).collect();
+    deviations.sort_by(f64::total_cmp);
+    let mad = percentile(&deviations, 50.0);
+    if mad == 0.0 {
+        return Vec::new();
+    }
+    samples
+        .iter()
+        .enumerate()
+        .filter(|(_, &x)| 0.6745 * (x - median).abs() / mad > OUTLIER_THRESHOLD)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Result of a two-sided Mann-Whitney U test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MannWhitney {
+    /// U statistic of the first sample.
+    pub u: f64,
+    pub z: f64,
+    pub p_value: f64,
+}
+
+/// Two-sided Mann-Whitney U test using the normal approximation with tie
+/// and continuity correction. The approximation is rough below about eight
+/// samples per side. Returns `None` if either sample is empty.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> Option<MannWhitney> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+
+    let mut pooled: Vec<(f64, bool)> = a.iter().map(|&x| (x, true)).chain(b.iter().map(|&x| (x, false))).collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Average ranks over ties, remembering the tie sizes for the variance.
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < pooled.len() {
+        let mut j = i;
+        while j + 1 < pooled.len() && pooled[j + 1].0 == pooled[i].0 {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        let ties = (j - i + 1) as f64;
+        tie_term += ties.powi(3) - ties;
+        rank_sum_a += rank * pooled[i..=j].iter().filter(|(_, from_a)| *from_a).count() as f64;
+        i = j + 1;
+    }
+
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return Some(MannWhitney { u, z: 0.0, p_value: 1.0 });
+    }
+    let diff = u - mean;
+    let corrected = diff.abs() - 0.5;
+    let z = corrected.max(0.0).copysign(diff) / variance.sqrt();
+    let p_value = (2.0 * (1.0 - normal_cdf(z.abs()))).min(1.0);
+    Some(MannWhitney { u, z, p_value })
+}
+
+/// Standard normal CDF via the Abramowitz and Stegun 7.1.26 erf
+/// approximation (absolute error below 1.5e-7).
+fn normal_cdf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs() / std::f64::consts::SQRT_2);
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-(x * x) / 2.0).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{actual} is not within {tolerance} of {expected}");
+    }
+
+    #[test]
+    fn t_quantile_matches_the_table() {
+        assert_eq!(t_quantile_95(1), 12.706);
+        assert_eq!(t_quantile_95(4), 2.776);
+        assert_eq!(t_quantile_95(30), 2.042);
+        assert_eq!(t_quantile_95(1000), 1.960);
+        assert!(t_quantile_95(0).is_nan());
+    }
+
+    #[test]
+    fn summary_of_one_to_five() {
+        let summary = Summary::new(&[3.0, 1.0, 5.0, 2.0, 4.0]).unwrap();
+        assert_eq!(summary.samples, 5);
+        assert_eq!((summary.min, summary.max, summary.median, summary.mean), (1.0, 5.0, 3.0, 3.0));
+        assert_close(summary.stddev, 2.5f64.sqrt(), 1e-12);
+        // 3 +- t(0.975, df=4) * sqrt(2.5) / sqrt(5)
+        assert_close(summary.ci95.0, 3.0 - 1.962_928_424_573_856, 1e-12);
+        assert_close(summary.ci95.1, 3.0 + 1.962_928_424_573_856, 1e-12);
+        assert_close(summary.p95, 4.8, 1e-12);
+        assert!(summary.outliers.is_empty());
+    }
+
+    #[test]
+    fn summary_of_one_sample_has_no_spread() {
+        let summary = Summary::new(&[7.0]).unwrap();
+        assert_eq!((summary.median, summary.stddev, summary.ci95), (7.0, 0.0, (7.0, 7.0)));
+        assert_eq!(Summary::new(&[]), None);
+    }
+
+    #[test]
+    fn percentile_interpolates_between_ranks() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 50.0), 2.5);
+        assert_close(percentile(&sorted, 95.0), 3.85, 1e-12);
+        assert_eq!(percentile(&sorted, 100.0), 4.0);
+    }
+
+    #[test]
+    fn mad_flags_only_the_far_sample() {
+        // Median 10.025 and MAD 0.075, so 20 scores 0.6745 * 9.975 / 0.075.
+        let summary = Summary::new(&[10.0, 10.1, 9.9, 10.05, 9.95, 20.0]).unwrap();
+        assert_eq!(summary.outliers, vec![5]);
+        // A MAD of zero flags nothing rather than everything off the median.
+        assert!(Summary::new(&[1.0, 1.0, 1.0, 2.0]).unwrap().outliers.is_empty());
+    }
+
+    #[test]
+    fn mann_whitney_matches_scipy() {
+        // The example of scipy.stats.mannwhitneyu(males, females,
+        // method="asymptotic"): statistic=17.0, pvalue=0.11134688653314041.
+        let test = mann_whitney_u(&[19.0, 22.0, 16.0, 29.0, 24.0], &[20.0, 11.0, 17.0, 12.0]).unwrap();
+        assert_eq!(test.u, 17.0);
+        assert_close(test.p_value, 0.111_346_886_533_140_41, 1e-6);
+    }
+
+    #[test]
+    fn mann_whitney_corrects_for_ties() {
+        // Tie-corrected normal approximation with continuity correction,
+        // evaluated with an exact erfc.
+        let test = mann_whitney_u(&[1.0, 2.0, 2.0, 3.0, 4.0], &[2.0, 3.0, 5.0, 6.0, 6.0, 7.0]).unwrap();
+        assert_eq!(test.u, 4.5);
+        assert!(test.z < 0.0);
+        assert_close(test.p_value, 0.064_146_618_734_404_43, 1e-6);
+    }
+
+    #[test]
+    fn mann_whitney_of_identical_samples() {
+        let test = mann_whitney_u(&[1.0, 1.0], &[1.0, 1.0, 1.0]).unwrap();
+        assert_eq!(test.p_value, 1.0);
+        assert_eq!(mann_whitney_u(&[], &[1.0]), None);
+    }
+}
@@ -0,0 +1,39 @@
+// Compiles the C implementations, quicksort_algo.c shared with the
+// Makefile and the sorting suites, so the benchmarks can call them through
+// FFI.
+fn main() {
+    println!("cargo:rerun-if-changed=quicksort_algo.c");
+    println!("cargo:rerun-if-changed=quicksort_algo.h");
+    println!("cargo:rerun-if-changed=quicksort_variants_algo.c");
+    println!("cargo:rerun-if-changed=quicksort_variants_algo.h");
+    println!("cargo:rerun-if-changed=parallel_sort_algo.c");
+    println!("cargo:rerun-if-changed=parallel_sort_algo.h");
+    println!("cargo:rerun-if-changed=sort_algo.c");
+    println!("cargo:rerun-if-changed=sort_algo.h");
+    println!("cargo:rerun-if-changed=typed_sort_algo.c");
+    println!("cargo:rerun-if-changed=typed_sort_algo.h");
+    println!("cargo:rerun-if-changed=../harness/c/ops.h");
+    let mut build = cc::Build::new();
+    if std::env::var_os("CARGO_FEATURE_INSTRUMENT").is_some() {
+        build.define("INSTRUMENT", None);
+    }
+    // OpenMP for the task-parallel quicksort. Compilers without it ignore
+    // the pragmas and run the tasks in order.
+    let compiler = build.get_compiler();
+    if compiler.is_like_gnu() {
+        build.flag("-fopenmp");
+        println!("cargo:rustc-link-lib=gomp");
+    } else if compiler.is_like_clang() && build.is_flag_supported("-fopenmp").unwrap_or(false) {
+        build.flag("-fopenmp");
+        println!("cargo:rustc-link-lib=omp");
+    }
+    build
+        .include("../harness/c")
+        .file("quicksort_algo.c")
+        .file("quicksort_variants_algo.c")
+        .file("parallel_sort_algo.c")
+        .file("sort_algo.c")
+        .file("typed_sort_algo.c")
+        .opt_level(3)
+        .compile("quicksort_algo");
+}
@@ -0,0 +1,280 @@
+#include <pthread.h>
+#include <stdlib.h>
+#include <string.h>
+#include "parallel_sort_algo.h"
+#include "sort_algo.h"
+
+// Samples per bucket that sample sort draws to pick its splitters.
+#define OVERSAMPLE 64
+
+typedef void* (*Task)(void* arg);
+
+// Runs task on count argument structs of argSize bytes each: all but the
+// first on new threads, the first on this one, then waits for them all.
+static void runTasks(Task task, void* args, size_t argSize, int count) {
+    pthread_t* ids = (pthread_t*)malloc(count * sizeof(pthread_t));
+    char* started = (char*)calloc(count, 1);
+    for (int i = 1; i < count; i++) {
+        if (ids && started && pthread_create(&ids[i], NULL, task, (char*)args + i * argSize) == 0) {
+            started[i] = 1;
+        } else {
+            task((char*)args + i * argSize);
+        }
+    }
+    task(args);
+    for (int i = 1; i < count; i++) {
+        if (started && started[i]) {
+            pthread_join(ids[i], NULL);
+        }
+    }
+    free(ids);
+    free(started);
+}
+
+static int depthLimit(int n) {
+    int bits = 0;
+    while ((n >> bits) > 0) {
+        bits++;
+    }
+    return 2 * bits;
+}
+
+static void quickSortTask(int* arr, int n, int depth) {
+    if (n < PARALLEL_CUTOFF || depth == 0) {
+        introsort(arr, n);
+        return;
+    }
+    int split = partition_median_of_3(arr, n) + 1;
+#pragma omp task
+    quickSortTask(arr, split, depth - 1);
+    quickSortTask(arr + split, n - split, depth - 1);
+}
+
+int quick_sort_omp(int* arr, int n, int threads) {
+#pragma omp parallel num_threads(threads)
+#pragma omp single
+    quickSortTask(arr, n, depthLimit(n));
+    return 0;
+}
+
+typedef struct {
+    int* arr;
+    int n;
+    int threads;
+} QuickSortArgs;
+
+static void* quickSortThreads(void* p) {
+    QuickSortArgs* a = (QuickSortArgs*)p;
+    if (a->threads <= 1 || a->n < PARALLEL_CUTOFF) {
+        introsort(a->arr, a->n);
+        return NULL;
+    }
+    int split = partition_median_of_3(a->arr, a->n) + 1;
+    QuickSortArgs halves[2] = {
+        {a->arr + split, a->n - split, a->threads - a->threads / 2},
+        {a->arr, split, a->threads / 2},
+    };
+    runTasks(quickSortThreads, halves, sizeof(QuickSortArgs), 2);
+    return NULL;
+}
+
+int quick_sort_pthreads(int* arr, int n, int threads) {
+    QuickSortArgs args = {arr, n, threads};
+    quickSortThreads(&args);
+    return 0;
+}
+
+typedef struct {
+    const int* left;
+    int leftLen;
+    const int* right;
+    int rightLen;
+    int* out;
+    int threads;
+} MergeArgs;
+
+// First index of sorted arr[0..n) whose element is not below value, or
+// with orEqual, above it.
+static int boundOf(const int* arr, int n, int value, int orEqual) {
+    int lo = 0, hi = n;
+    while (lo < hi) {
+        int mid = lo + (hi - lo) / 2;
+        if (arr[mid] < value || (orEqual && arr[mid] == value)) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    return lo;
+}
+
+static void* mergeThreads(void* p) {
+    MergeArgs* a = (MergeArgs*)p;
+    if (a->threads <= 1 || a->leftLen + a->rightLen < PARALLEL_CUTOFF) {
+        merge(a->left, a->leftLen, a->right, a->rightLen, a->out);
+        return NULL;
+    }
+    int i, j;
+    if (a->leftLen >= a->rightLen) {
+        i = a->leftLen / 2;
+        j = boundOf(a->right, a->rightLen, a->left[i], 0);
+    } else {
+        j = a->rightLen / 2;
+        i = boundOf(a->left, a->leftLen, a->right[j], 1);
+    }
+    MergeArgs halves[2] = {
+        {a->left + i, a->leftLen - i, a->right + j, a->rightLen - j, a->out + i + j, a->threads - a->threads / 2},
+        {a->left, i, a->right, j, a->out, a->threads / 2},
+    };
+    runTasks(mergeThreads, halves, sizeof(MergeArgs), 2);
+    return NULL;
+}
+
+typedef struct {
+    int* source;
+    int* target;
+    int n;
+    int threads;
+} SplitMergeArgs;
+
+// Sorts the n elements of source, which target also holds, into target.
+static void* splitMergeThreads(void* p) {
+    SplitMergeArgs* a = (SplitMergeArgs*)p;
+    if (a->threads <= 1 || a->n < PARALLEL_CUTOFF) {
+        split_merge(a->source, a->target, a->n);
+        return NULL;
+    }
+    int mid = a->n / 2;
+    SplitMergeArgs halves[2] = {
+        {a->target + mid, a->source + mid, a->n - mid, a->threads - a->threads / 2},
+        {a->target, a->source, mid, a->threads / 2},
+    };
+    runTasks(splitMergeThreads, halves, sizeof(SplitMergeArgs), 2);
+    MergeArgs merged = {a->source, mid, a->source + mid, a->n - mid, a->target, a->threads};
+    mergeThreads(&merged);
+    return NULL;
+}
+
+int merge_sort_pthreads(int* arr, int n, int threads) {
+    int* buffer = (int*)malloc((n > 0 ? n : 1) * sizeof(int));
+    if (!buffer) {
+        return -1;
+    }
+    memcpy(buffer, arr, n * sizeof(int));
+    SplitMergeArgs args = {buffer, arr, n, threads};
+    splitMergeThreads(&args);
+    free(buffer);
+    return 0;
+}
+
+typedef struct {
+    const int* chunk;
+    int len;
+    const int* splitters;
+    int buckets;
+    // counts[b] is how many elements of the chunk go to bucket b, and
+    // offsets[b] where in the buffer the next one goes.
+    int* counts;
+    int* offsets;
+    int* buffer;
+} ChunkArgs;
+
+// Bucket of value: the number of splitters not above it.
+static inline int bucketOf(const int* splitters, int buckets, int value) {
+    return boundOf(splitters, buckets - 1, value, 1);
+}
+
+static void* countChunk(void* p) {
+    ChunkArgs* a = (ChunkArgs*)p;
+    for (int i = 0; i < a->len; i++) {
+        a->counts[bucketOf(a->splitters, a->buckets, a->chunk[i])]++;
+    }
+    return NULL;
+}
+
+static void* scatterChunk(void* p) {
+    ChunkArgs* a = (ChunkArgs*)p;
+    for (int i = 0; i < a->len; i++) {
+        int b = bucketOf(a->splitters, a->buckets, a->chunk[i]);
+        a->buffer[a->offsets[b]++] = a->chunk[i];
+    }
+    return NULL;
+}
+
+typedef struct {
+    int* source;
+    int* target;
+    int n;
+} BucketArgs;
+
+static void* sortBucket(void* p) {
+    BucketArgs* a = (BucketArgs*)p;
+    introsort(a->source, a->n);
+    memcpy(a->target, a->source, a->n * sizeof(int));
+    return NULL;
+}
+
+int sample_sort_pthreads(int* arr, int n, int threads) {
+    if (threads <= 1 || n < PARALLEL_CUTOFF) {
+        return introsort(arr, n);
+    }
+    int buckets = threads;
+    int sampleCount = buckets * OVERSAMPLE;
+    int chunk = (n + threads - 1) / threads;
+    int chunks = (n + chunk - 1) / chunk;
+    int* samples = (int*)malloc(sampleCount * sizeof(int));
+    int* splitters = (int*)malloc(buckets * sizeof(int));
+    int* counts = (int*)calloc((size_t)chunks * buckets, sizeof(int));
+    int* offsets = (int*)malloc((size_t)chunks * buckets * sizeof(int));
+    int* buffer = (int*)malloc(n * sizeof(int));
+    ChunkArgs* chunkArgs = (ChunkArgs*)malloc(chunks * sizeof(ChunkArgs));
+    BucketArgs* bucketArgs = (BucketArgs*)malloc(buckets * sizeof(BucketArgs));
+    if (!samples || !splitters || !counts || !offsets || !buffer || !chunkArgs || !bucketArgs) {
+        free(samples);
+        free(splitters);
+        free(counts);
+        free(offsets);
+        free(buffer);
+        free(chunkArgs);
+        free(bucketArgs);
+        return -1;
+    }
+
+    for (int i = 0; i < sampleCount; i++) {
+        samples[i] = arr[(long long)i * n / sampleCount];
+    }
+    introsort(samples, sampleCount);
+    for (int b = 1; b < buckets; b++) {
+        splitters[b - 1] = samples[b * OVERSAMPLE];
+    }
+
+    for (int c = 0; c < chunks; c++) {
+        int start = c * chunk;
+        int len = n - start < chunk ? n - start : chunk;
+        ChunkArgs args = {arr + start, len, splitters, buckets, counts + c * buckets, offsets + c * buckets, buffer};
+        chunkArgs[c] = args;
+    }
+    runTasks(countChunk, chunkArgs, sizeof(ChunkArgs), chunks);
+
+    // Bucket by bucket, each chunk gets the next stretch of the buffer.
+    int offset = 0;
+    for (int b = 0; b < buckets; b++) {
+        BucketArgs args = {buffer + offset, arr + offset, 0};
+        for (int c = 0; c < chunks; c++) {
+            offsets[c * buckets + b] = offset;
+            offset += counts[c * buckets + b];
+            args.n += counts[c * buckets + b];
+        }
+        bucketArgs[b] = args;
+    }
+    runTasks(scatterChunk, chunkArgs, sizeof(ChunkArgs), chunks);
+    runTasks(sortBucket, bucketArgs, sizeof(BucketArgs), buckets);
+    free(samples);
+    free(splitters);
+    free(counts);
+    free(offsets);
+    free(buffer);
+    free(chunkArgs);
+    free(bucketArgs);
+    return 0;
+}
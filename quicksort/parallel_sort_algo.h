@@ -0,0 +1,23 @@
+#ifndef PARALLEL_SORT_ALGO_H
+#define PARALLEL_SORT_ALGO_H
+
+// Parallel sorts, the counterparts of src/parallel.rs: each sorts the n
+// ints of arr on up to `threads` threads, handing slices shorter than
+// PARALLEL_CUTOFF to the sequential sorts of sort_algo.c. They return -1 if
+// they cannot allocate a buffer and 0 on success. A thread that cannot be
+// started is replaced by doing its work on the calling thread.
+
+#define PARALLEL_CUTOFF (1 << 13)
+
+// Quicksort with OpenMP tasks, like the rayon quicksort.
+int quick_sort_omp(int* arr, int n, int threads);
+// Quicksort handing the left side and half of the threads to a new pthread
+// after every partition.
+int quick_sort_pthreads(int* arr, int n, int threads);
+// Top-down merge sort sorting both halves and splitting every merge across
+// pthreads.
+int merge_sort_pthreads(int* arr, int n, int threads);
+// Sample sort with one bucket per pthread.
+int sample_sort_pthreads(int* arr, int n, int threads);
+
+#endif
@@ -1,38 +1,9 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <time.h>
-
-void swap(int *a, int *b) {
-    int temp = *a;
-    *a = *b;
-    *b = temp;
-}
-
-int partition(int arr[], int low, int high) {
-    int pivot = arr[high];
-    int i = low;
-
-    for (int j = low; j < high; j++) {
-        if (arr[j] <= pivot) {
-            swap(&arr[i], &arr[j]);
-            i++;
-        }
-    }
-
-    swap(&arr[i], &arr[high]);
-    return i;
-}
-
-int count = 0;
-
-void quickSort(int arr[], int low, int high) {
-    count++;
-    if (low < high) {
-        int pi = partition(arr, low, high);
-        quickSort(arr, low, pi - 1);
-        quickSort(arr, pi + 1, high);
-    }
-}
+#include "quicksort_algo.h"
+#include "bench_input.h"
+#include "bench_rng.h"
 
 double diff_timespec(struct timespec *time1, struct timespec *time0) {
     return (time1->tv_sec - time0->tv_sec)
@@ -40,8 +11,12 @@ double diff_timespec(struct timespec *time1, struct timespec *time0) {
 }
 
 int main(int argc, char *argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <array_size>\n", argv[0]);
+    uint64_t seed = BENCH_DEFAULT_SEED;
+    if (bench_take_seed(&argc, argv, &seed) != 0) {
+        return 1;
+    }
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <array_size> [input_file] [--seed N]\n", argv[0]);
         return 1;
     }
 
@@ -58,9 +33,18 @@ int main(int argc, char *argv[]) {
         return 1;
     }
 
-    srand(10000);
-    for (int i = 0; i < size; i++) {
-        arr[i] = rand();
+    if (argc == 3) {
+        free(arr);
+        arr = bench_input_load(argv[2], size, 0, size);
+        if (!arr) {
+            return 1;
+        }
+    } else {
+        // Like the Rust default, uniform:min=0,max=2147483647
+        BenchRng rng = {seed};
+        for (int i = 0; i < size; i++) {
+            arr[i] = bench_rng_range(&rng, 0, INT32_MAX);
+        }
     }
 
     clock_gettime(CLOCK_MONOTONIC, &start_time);
@@ -68,7 +52,13 @@ int main(int argc, char *argv[]) {
     clock_gettime(CLOCK_MONOTONIC, &end_time);
 
     double time_elapsed = diff_timespec(&end_time, &start_time);
-    printf("\nTime taken to sort the array of size %d: %f seconds, %d recursive calls\n", size, time_elapsed, count);
+#ifdef INSTRUMENT
+    printf("\nTime taken to sort the array of size %d: %f seconds, with %llu comparisons, %llu swaps, %llu calls, max depth %llu\n",
+           size, time_elapsed, (unsigned long long)quickSortOps.comparisons, (unsigned long long)quickSortOps.swaps,
+           (unsigned long long)quickSortOps.calls, (unsigned long long)quickSortOps.max_depth);
+#else
+    printf("\nTime taken to sort the array of size %d: %f seconds\n", size, time_elapsed);
+#endif
 
     free(arr);
     return 0;
@@ -0,0 +1,48 @@
+#include "quicksort_algo.h"
+
+OpCounts quickSortOps;
+
+#ifdef INSTRUMENT
+static uint64_t quickSortDepth;
+#endif
+
+void swap(int *a, int *b) {
+    int temp = *a;
+    *a = *b;
+    *b = temp;
+}
+
+int partition(int arr[], int low, int high) {
+    int pivot = arr[high];
+    int i = low;
+
+    for (int j = low; j < high; j++) {
+        OPS_COMPARE(quickSortOps);
+        if (arr[j] <= pivot) {
+            OPS_SWAP(quickSortOps);
+            swap(&arr[i], &arr[j]);
+            i++;
+        }
+    }
+
+    OPS_SWAP(quickSortOps);
+    swap(&arr[i], &arr[high]);
+    return i;
+}
+
+void quickSort(int arr[], int low, int high) {
+    OPS_ENTER(quickSortOps, quickSortDepth);
+    // Recurse into the smaller side and loop on the larger one, so sorted
+    // input does not overflow the stack
+    while (low < high) {
+        int pi = partition(arr, low, high);
+        if (pi - low < high - pi) {
+            quickSort(arr, low, pi - 1);
+            low = pi + 1;
+        } else {
+            quickSort(arr, pi + 1, high);
+            high = pi - 1;
+        }
+    }
+    OPS_LEAVE(quickSortDepth);
+}
@@ -0,0 +1,14 @@
+#ifndef QUICKSORT_ALGO_H
+#define QUICKSORT_ALGO_H
+
+#include "ops.h"
+
+// Operations of quickSort since they were last reset to 0. Only counted
+// when compiled with -DINSTRUMENT.
+extern OpCounts quickSortOps;
+
+void swap(int *a, int *b);
+int partition(int arr[], int low, int high);
+void quickSort(int arr[], int low, int high);
+
+#endif
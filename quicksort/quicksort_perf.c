@@ -1,43 +1,18 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <time.h>
-#include <unistd.h>
-#include <sys/types.h>
-#include <sys/wait.h>
-#include <signal.h>
-
-void swap(int *a, int *b) {
-    int temp = *a;
-    *a = *b;
-    *b = temp;
-}
-
-int partition(int arr[], int low, int high) {
-    int pivot = arr[high];
-    int i = low;
-
-    for (int j = low; j < high; j++) {
-        if (arr[j] <= pivot) {
-            swap(&arr[i], &arr[j]);
-            i++;
-        }
-    }
-
-    swap(&arr[i], &arr[high]);
-    return i;
-}
-
-void quickSort(int arr[], int low, int high) {
-    if (low < high) {
-        int pi = partition(arr, low, high);
-        quickSort(arr, low, pi - 1);
-        quickSort(arr, pi + 1, high);
-    }
-}
+#include "quicksort_algo.h"
+#include "bench_input.h"
+#include "bench_rng.h"
+#include "bench_counters.h"
 
 int main(int argc, char *argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <array_size>\n", argv[0]);
+    uint64_t seed = BENCH_DEFAULT_SEED;
+    if (bench_take_seed(&argc, argv, &seed) != 0) {
+        return 1;
+    }
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <array_size> [input_file] [--seed N]\n", argv[0]);
         return 1;
     }
 
@@ -53,51 +28,37 @@ int main(int argc, char *argv[]) {
         return 1;
     }
 
-    srand(time(NULL));
-    for (int i = 0; i < size; i++) {
-        arr[i] = rand();
+    if (argc == 3) {
+        free(arr);
+        arr = bench_input_load(argv[2], size, 0, size);
+        if (!arr) {
+            return 1;
+        }
+    } else {
+        // Like the Rust default, uniform:min=0,max=2147483647
+        BenchRng rng = {seed};
+        for (int i = 0; i < size; i++) {
+            arr[i] = bench_rng_range(&rng, 0, INT32_MAX);
+        }
     }
 
-    int pid = getpid();
-    int cpid = fork(); 
-
-
-    if (cpid == 0) { 
-        char buf[300];
-        snprintf(buf, sizeof(buf), "perf stat -e cycles,instructions,cache-references,cache-misses -p %d > quicksort_c_perf_output.log 2>&1", pid);
-        execl("/bin/sh", "sh", "-c", buf, NULL);
-        perror("execl failed");
-        exit(1);
-    } else { 
-        setpgid(cpid, 0); 
-        sleep(1);  
+    // Counters opened in-process and enabled only around the sort
+    BenchCounters counters;
+    bench_counters_open(&counters);
 
-        clock_t start_time = clock();
-        quickSort(arr, 0, size - 1);
-        clock_t end_time = clock();
+    bench_counters_start(&counters);
+    double start_time = bench_now();
+    quickSort(arr, 0, size - 1);
+    double end_time = bench_now();
+    bench_counters_stop(&counters);
 
-        kill(-cpid, SIGINT);
-        waitpid(cpid, NULL, 0);  
+    bench_counters_print(&counters, argv[0]);
+    bench_counters_close(&counters);
 
+    double time_elapsed = end_time - start_time;
+    printf("\nTime taken to sort the array of size %d: %f seconds\n", size, time_elapsed);
 
-
-        FILE *perf_file = fopen("quicksort_c_perf_output.log", "r");
-        if (perf_file) {
-            char line[256];
-            printf("\n[ Perf Stat Output ]\n");
-            while (fgets(line, sizeof(line), perf_file)) {
-                printf("%s", line);
-            }
-            fclose(perf_file);
-        } else {
-            perror("Failed to read perf stat log");
-        }
-
-        double time_elapsed = ((double)(end_time - start_time)) / CLOCKS_PER_SEC;
-        printf("\nTime taken to sort the array of size %d: %f seconds\n", size, time_elapsed);
-
-        free(arr);
-    }
+    free(arr);
 
     return 0;
 }
@@ -0,0 +1,218 @@
+#include "quicksort_variants_algo.h"
+
+#define BLOCK 64
+#define NINTHER_THRESHOLD 40
+
+typedef struct {
+    int pivot;
+    int partition;
+    int cutoff;
+    uint64_t state;
+    int64_t partitions;
+} Variant;
+
+static uint64_t nextU64(Variant* v) {
+    uint64_t z = (v->state += 0x9e3779b97f4a7c15ULL);
+    z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+    z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+    return z ^ (z >> 31);
+}
+
+static int below(Variant* v, int n) {
+    return (int)(((unsigned __int128)nextU64(v) * (uint64_t)n) >> 64);
+}
+
+static inline void swapInts(int* a, int* b) {
+    int t = *a;
+    *a = *b;
+    *b = t;
+}
+
+static inline int medianOf3(const int* arr, int a, int b, int c) {
+    if (arr[a] < arr[b]) {
+        return arr[b] < arr[c] ? b : arr[a] < arr[c] ? c : a;
+    }
+    return arr[a] < arr[c] ? a : arr[b] < arr[c] ? c : b;
+}
+
+static int choosePivot(Variant* v, const int* arr, int n) {
+    switch (v->pivot) {
+    case PIVOT_LAST:
+        return n - 1;
+    case PIVOT_RANDOM:
+        return below(v, n);
+    case PIVOT_NINTHER:
+        if (n >= NINTHER_THRESHOLD) {
+            int step = n / 8, mid = n / 2;
+            int a = medianOf3(arr, 0, step, 2 * step);
+            int b = medianOf3(arr, mid - step, mid, mid + step);
+            int c = medianOf3(arr, n - 1 - 2 * step, n - 1 - step, n - 1);
+            return medianOf3(arr, a, b, c);
+        }
+        // fall through
+    default:
+        return medianOf3(arr, 0, n / 2, n - 1);
+    }
+}
+
+static void insertionSort(int* arr, int n) {
+    for (int i = 1; i < n; i++) {
+        int key = arr[i];
+        int j = i;
+        while (j > 0 && arr[j - 1] > key) {
+            arr[j] = arr[j - 1];
+            j--;
+        }
+        arr[j] = key;
+    }
+}
+
+// Pivot at arr[n - 1]; returns where it ends up.
+static int partitionLomuto(int* arr, int n) {
+    int pivot = arr[n - 1];
+    int i = 0;
+    for (int j = 0; j < n - 1; j++) {
+        if (arr[j] <= pivot) {
+            swapInts(&arr[i], &arr[j]);
+            i++;
+        }
+    }
+    swapInts(&arr[i], &arr[n - 1]);
+    return i;
+}
+
+// Pivot at arr[0]; returns j < n - 1 with arr[0..j] <= pivot <= arr[j+1..].
+static int partitionHoare(int* arr, int n) {
+    int pivot = arr[0];
+    int i = 0, j = n - 1;
+    for (;;) {
+        while (arr[i] < pivot) {
+            i++;
+        }
+        while (arr[j] > pivot) {
+            j--;
+        }
+        if (i >= j) {
+            return j;
+        }
+        swapInts(&arr[i], &arr[j]);
+        i++;
+        j--;
+    }
+}
+
+// Leaves the elements below pivot in arr[0..lt), the equal ones in
+// arr[lt..gt) and the greater ones in arr[gt..n).
+static void partitionThreeWay(int* arr, int n, int pivot, int* ltOut, int* gtOut) {
+    int lt = 0, i = 0, gt = n;
+    while (i < gt) {
+        if (arr[i] < pivot) {
+            swapInts(&arr[lt], &arr[i]);
+            lt++;
+            i++;
+        } else if (arr[i] > pivot) {
+            gt--;
+            swapInts(&arr[i], &arr[gt]);
+        } else {
+            i++;
+        }
+    }
+    *ltOut = lt;
+    *gtOut = gt;
+}
+
+// Pivot at arr[0]; returns where it ends up.
+static int partitionBlock(int* arr, int n) {
+    int pivot = arr[0];
+    // arr[1..l) is below the pivot and arr[r..n) is not; arr[l..r) is left.
+    int l = 1, r = n;
+    unsigned char offsetsL[BLOCK], offsetsR[BLOCK];
+    int startL = 0, numL = 0, startR = 0, numR = 0;
+
+    while (r - l > 2 * BLOCK) {
+        if (numL == 0) {
+            startL = 0;
+            for (int i = 0; i < BLOCK; i++) {
+                offsetsL[numL] = (unsigned char)i;
+                numL += arr[l + i] >= pivot;
+            }
+        }
+        if (numR == 0) {
+            startR = 0;
+            for (int i = 0; i < BLOCK; i++) {
+                offsetsR[numR] = (unsigned char)i;
+                numR += arr[r - 1 - i] < pivot;
+            }
+        }
+        int num = numL < numR ? numL : numR;
+        for (int k = 0; k < num; k++) {
+            swapInts(&arr[l + offsetsL[startL + k]], &arr[r - 1 - offsetsR[startR + k]]);
+        }
+        numL -= num;
+        numR -= num;
+        startL += num;
+        startR += num;
+        if (numL == 0) {
+            l += BLOCK;
+        }
+        if (numR == 0) {
+            r -= BLOCK;
+        }
+    }
+
+    while (l < r) {
+        if (arr[l] < pivot) {
+            l++;
+        } else {
+            r--;
+            swapInts(&arr[l], &arr[r]);
+        }
+    }
+    swapInts(&arr[0], &arr[l - 1]);
+    return l - 1;
+}
+
+// Recurses into the smaller side and loops on the larger one.
+static void sortVariant(Variant* v, int* arr, int n) {
+    int limit = v->cutoff > 1 ? v->cutoff : 1;
+    while (n > limit) {
+        v->partitions++;
+        int p = choosePivot(v, arr, n);
+        int leftEnd, rightStart;
+        switch (v->partition) {
+        case PARTITION_HOARE:
+            swapInts(&arr[p], &arr[0]);
+            leftEnd = rightStart = partitionHoare(arr, n) + 1;
+            break;
+        case PARTITION_THREE_WAY:
+            partitionThreeWay(arr, n, arr[p], &leftEnd, &rightStart);
+            break;
+        case PARTITION_BLOCK:
+            swapInts(&arr[p], &arr[0]);
+            leftEnd = partitionBlock(arr, n);
+            rightStart = leftEnd + 1;
+            break;
+        default:
+            swapInts(&arr[p], &arr[n - 1]);
+            leftEnd = partitionLomuto(arr, n);
+            rightStart = leftEnd + 1;
+            break;
+        }
+        int rightLen = n - rightStart;
+        if (leftEnd < rightLen) {
+            sortVariant(v, arr, leftEnd);
+            arr += rightStart;
+            n = rightLen;
+        } else {
+            sortVariant(v, arr + rightStart, rightLen);
+            n = leftEnd;
+        }
+    }
+    insertionSort(arr, n);
+}
+
+int64_t quick_sort_variant(int* arr, int n, int pivot, int partition, int cutoff, uint64_t seed) {
+    Variant v = {pivot, partition, cutoff, seed, 0};
+    sortVariant(&v, arr, n);
+    return v.partitions;
+}
@@ -0,0 +1,17 @@
+#ifndef QUICKSORT_VARIANTS_ALGO_H
+#define QUICKSORT_VARIANTS_ALGO_H
+
+#include <stdint.h>
+
+// The quicksort variants of src/variants.rs: a pivot strategy, a partition
+// scheme and an insertion-sort cutoff, numbered as on the Rust side. Random
+// pivots come from the same SplitMix64 generator, so both languages split
+// the array the same way for a given seed.
+
+enum { PIVOT_LAST, PIVOT_RANDOM, PIVOT_MEDIAN_OF_3, PIVOT_NINTHER };
+enum { PARTITION_LOMUTO, PARTITION_HOARE, PARTITION_THREE_WAY, PARTITION_BLOCK };
+
+// Sorts n ints and returns the number of partitions.
+int64_t quick_sort_variant(int* arr, int n, int pivot, int partition, int cutoff, uint64_t seed);
+
+#endif
@@ -0,0 +1,421 @@
+#include <stdlib.h>
+#include <string.h>
+#include "sort_algo.h"
+
+#define INSERTION_CUTOFF 16
+#define MIN_RUN 32
+
+static void insertionSortFrom(int* arr, int n, int sorted) {
+    for (int i = sorted > 1 ? sorted : 1; i < n; i++) {
+        int value = arr[i];
+        int j = i;
+        while (j > 0 && arr[j - 1] > value) {
+            arr[j] = arr[j - 1];
+            j--;
+        }
+        arr[j] = value;
+    }
+}
+
+int insertion_sort(int* arr, int n) {
+    insertionSortFrom(arr, n, 1);
+    return 0;
+}
+
+// Merges left[0..leftLen] and right[0..rightLen] into out, taking from
+// left on ties.
+void merge(const int* left, int leftLen, const int* right, int rightLen, int* out) {
+    int i = 0, j = 0, k = 0;
+    while (i < leftLen && j < rightLen) {
+        if (left[i] <= right[j]) {
+            out[k++] = left[i++];
+        } else {
+            out[k++] = right[j++];
+        }
+    }
+    while (i < leftLen) {
+        out[k++] = left[i++];
+    }
+    while (j < rightLen) {
+        out[k++] = right[j++];
+    }
+}
+
+// Sorts the n elements of source, which target also holds, into target.
+void split_merge(int* source, int* target, int n) {
+    if (n < 2) {
+        return;
+    }
+    int mid = n / 2;
+    split_merge(target, source, mid);
+    split_merge(target + mid, source + mid, n - mid);
+    merge(source, mid, source + mid, n - mid, target);
+}
+
+int merge_sort_top_down(int* arr, int n) {
+    int* buffer = (int*)malloc((n > 0 ? n : 1) * sizeof(int));
+    if (!buffer) {
+        return -1;
+    }
+    memcpy(buffer, arr, n * sizeof(int));
+    split_merge(buffer, arr, n);
+    free(buffer);
+    return 0;
+}
+
+int merge_sort_bottom_up(int* arr, int n) {
+    int* buffer = (int*)malloc((n > 0 ? n : 1) * sizeof(int));
+    if (!buffer) {
+        return -1;
+    }
+    int* source = arr;
+    int* target = buffer;
+    for (int width = 1; width < n; width *= 2) {
+        for (int start = 0; start < n; start += 2 * width) {
+            int mid = start + width < n ? start + width : n;
+            int end = start + 2 * width < n ? start + 2 * width : n;
+            merge(source + start, mid - start, source + mid, end - mid, target + start);
+        }
+        int* t = source;
+        source = target;
+        target = t;
+    }
+    if (source != arr) {
+        memcpy(arr, source, n * sizeof(int));
+    }
+    free(buffer);
+    return 0;
+}
+
+static void siftDown(int* arr, int n, int root) {
+    for (;;) {
+        int child = 2 * root + 1;
+        if (child >= n) {
+            break;
+        }
+        if (child + 1 < n && arr[child + 1] > arr[child]) {
+            child++;
+        }
+        if (arr[root] >= arr[child]) {
+            break;
+        }
+        int t = arr[root];
+        arr[root] = arr[child];
+        arr[child] = t;
+        root = child;
+    }
+}
+
+int heap_sort(int* arr, int n) {
+    for (int root = n / 2 - 1; root >= 0; root--) {
+        siftDown(arr, n, root);
+    }
+    for (int end = n - 1; end > 0; end--) {
+        int t = arr[0];
+        arr[0] = arr[end];
+        arr[end] = t;
+        siftDown(arr, end, 0);
+    }
+    return 0;
+}
+
+static void swapInts(int* a, int* b) {
+    int t = *a;
+    *a = *b;
+    *b = t;
+}
+
+// Hoare partition around the median of the first, middle and last
+// elements. Returns j such that arr[0..j] holds no element above the pivot
+// and arr[j + 1..n - 1] none below it.
+int partition_median_of_3(int* arr, int n) {
+    int mid = n / 2, last = n - 1;
+    if (arr[mid] < arr[0]) {
+        swapInts(&arr[mid], &arr[0]);
+    }
+    if (arr[last] < arr[0]) {
+        swapInts(&arr[last], &arr[0]);
+    }
+    if (arr[last] < arr[mid]) {
+        swapInts(&arr[last], &arr[mid]);
+    }
+    int pivot = arr[mid];
+    int i = 0, j = last;
+    for (;;) {
+        while (arr[i] < pivot) {
+            i++;
+        }
+        while (arr[j] > pivot) {
+            j--;
+        }
+        if (i >= j) {
+            return j;
+        }
+        swapInts(&arr[i], &arr[j]);
+        i++;
+        j--;
+    }
+}
+
+static void introsortLoop(int* arr, int n, int depth) {
+    while (n > INSERTION_CUTOFF) {
+        if (depth == 0) {
+            heap_sort(arr, n);
+            return;
+        }
+        depth--;
+        int split = partition_median_of_3(arr, n) + 1;
+        if (split < n - split) {
+            introsortLoop(arr, split, depth);
+            arr += split;
+            n -= split;
+        } else {
+            introsortLoop(arr + split, n - split, depth);
+            n = split;
+        }
+    }
+    insertion_sort(arr, n);
+}
+
+int introsort(int* arr, int n) {
+    int bits = 0;
+    while ((n >> bits) > 0) {
+        bits++;
+    }
+    introsortLoop(arr, n, 2 * bits);
+    return 0;
+}
+
+int shell_sort(int* arr, int n) {
+    // Ciura's gaps, continued by factors of 2.25. 2.25^24 passes 2^31.
+    long gaps[40] = {1, 4, 10, 23, 57, 132, 301, 701};
+    int count = 8;
+    while (gaps[count - 1] < n) {
+        gaps[count] = gaps[count - 1] * 9 / 4;
+        count++;
+    }
+    for (int g = count - 1; g >= 0; g--) {
+        int gap = (int)gaps[g];
+        if (gaps[g] >= n) {
+            continue;
+        }
+        for (int i = gap; i < n; i++) {
+            int value = arr[i];
+            int j = i;
+            while (j >= gap && arr[j - gap] > value) {
+                arr[j] = arr[j - gap];
+                j -= gap;
+            }
+            arr[j] = value;
+        }
+    }
+    return 0;
+}
+
+// Byte shift / 8 of value with the sign bit flipped, so that negative
+// numbers sort first.
+static inline unsigned digit(int value, int shift) {
+    return (((unsigned)value ^ 0x80000000u) >> shift) & 0xff;
+}
+
+int radix_sort_lsd(int* arr, int n) {
+    int* buffer = (int*)malloc((n > 0 ? n : 1) * sizeof(int));
+    if (!buffer) {
+        return -1;
+    }
+    int* source = arr;
+    int* target = buffer;
+    for (int shift = 0; shift < 32; shift += 8) {
+        size_t starts[256] = {0};
+        for (int i = 0; i < n; i++) {
+            starts[digit(source[i], shift)]++;
+        }
+        size_t total = 0;
+        for (int d = 0; d < 256; d++) {
+            size_t count = starts[d];
+            starts[d] = total;
+            total += count;
+        }
+        for (int i = 0; i < n; i++) {
+            target[starts[digit(source[i], shift)]++] = source[i];
+        }
+        int* t = source;
+        source = target;
+        target = t;
+    }
+    // An even number of passes ends in arr itself.
+    free(buffer);
+    return 0;
+}
+
+static void msdPass(int* arr, int* buffer, int n, int shift) {
+    if (n <= INSERTION_CUTOFF) {
+        insertion_sort(arr, n);
+        return;
+    }
+    int bounds[257] = {0};
+    for (int i = 0; i < n; i++) {
+        bounds[digit(arr[i], shift) + 1]++;
+    }
+    for (int d = 0; d < 256; d++) {
+        bounds[d + 1] += bounds[d];
+    }
+    int next[256];
+    memcpy(next, bounds, sizeof(next));
+    for (int i = 0; i < n; i++) {
+        buffer[next[digit(arr[i], shift)]++] = arr[i];
+    }
+    memcpy(arr, buffer, n * sizeof(int));
+    if (shift == 0) {
+        return;
+    }
+    for (int d = 0; d < 256; d++) {
+        int start = bounds[d], end = bounds[d + 1];
+        if (end - start > 1) {
+            msdPass(arr + start, buffer + start, end - start, shift - 8);
+        }
+    }
+}
+
+int radix_sort_msd(int* arr, int n) {
+    int* buffer = (int*)malloc((n > 0 ? n : 1) * sizeof(int));
+    if (!buffer) {
+        return -1;
+    }
+    msdPass(arr, buffer, n, 24);
+    free(buffer);
+    return 0;
+}
+
+int counting_sort(int* arr, int n) {
+    if (n == 0) {
+        return 0;
+    }
+    int min = arr[0], max = arr[0];
+    for (int i = 1; i < n; i++) {
+        if (arr[i] < min) {
+            min = arr[i];
+        }
+        if (arr[i] > max) {
+            max = arr[i];
+        }
+    }
+    size_t range = (size_t)((long long)max - min + 1);
+    unsigned* counts = (unsigned*)calloc(range, sizeof(unsigned));
+    if (!counts) {
+        return -1;
+    }
+    for (int i = 0; i < n; i++) {
+        counts[(long long)arr[i] - min]++;
+    }
+    int k = 0;
+    for (size_t offset = 0; offset < range; offset++) {
+        int value = (int)((long long)min + (long long)offset);
+        for (unsigned c = counts[offset]; c > 0; c--) {
+            arr[k++] = value;
+        }
+    }
+    free(counts);
+    return 0;
+}
+
+typedef struct {
+    int start;
+    int length;
+} Run;
+
+// Merges run at with the run after it, copying only the first one into
+// buffer, and removes the second from the stack.
+static void mergeRunsAt(int* arr, Run* runs, int* runCount, int at, int* buffer) {
+    int start = runs[at].start;
+    int leftLen = runs[at].length;
+    int rightLen = runs[at + 1].length;
+    runs[at].length += rightLen;
+    memmove(&runs[at + 1], &runs[at + 2], (*runCount - at - 2) * sizeof(Run));
+    (*runCount)--;
+
+    int* base = arr + start;
+    int total = leftLen + rightLen;
+    memcpy(buffer, base, leftLen * sizeof(int));
+    int i = 0, j = leftLen, k = 0;
+    while (i < leftLen && j < total) {
+        if (base[j] < buffer[i]) {
+            base[k++] = base[j++];
+        } else {
+            base[k++] = buffer[i++];
+        }
+    }
+    memcpy(base + k, buffer + i, (leftLen - i) * sizeof(int));
+}
+
+// Merges runs until, from the top of the stack down, every run is longer
+// than the next two together and than the next one.
+static void collapseRuns(int* arr, Run* runs, int* runCount, int* buffer) {
+    for (;;) {
+        int k = *runCount;
+        if ((k >= 3 && runs[k - 3].length <= runs[k - 2].length + runs[k - 1].length) ||
+            (k >= 4 && runs[k - 4].length <= runs[k - 3].length + runs[k - 2].length)) {
+            int at = runs[k - 3].length < runs[k - 1].length ? k - 3 : k - 2;
+            mergeRunsAt(arr, runs, runCount, at, buffer);
+        } else if (k >= 2 && runs[k - 2].length <= runs[k - 1].length) {
+            mergeRunsAt(arr, runs, runCount, k - 2, buffer);
+        } else {
+            break;
+        }
+    }
+}
+
+int natural_merge_sort(int* arr, int n) {
+    // The invariants keep run lengths growing faster than the Fibonacci
+    // numbers, so 64 runs cover any int length.
+    Run runs[64];
+    int runCount = 0;
+    // A merge copies out its left run, which in the final merges can be
+    // most of the array.
+    int* buffer = (int*)malloc((n > 0 ? n : 1) * sizeof(int));
+    if (!buffer) {
+        return -1;
+    }
+    int start = 0;
+    while (start < n) {
+        int end = start + 1;
+        if (end < n && arr[end] < arr[start]) {
+            while (end < n && arr[end] < arr[end - 1]) {
+                end++;
+            }
+            for (int i = start, j = end - 1; i < j; i++, j--) {
+                swapInts(&arr[i], &arr[j]);
+            }
+        } else {
+            while (end < n && arr[end] >= arr[end - 1]) {
+                end++;
+            }
+        }
+        int forced = start + MIN_RUN < n ? start + MIN_RUN : n;
+        if (end < forced) {
+            insertionSortFrom(arr + start, forced - start, end - start);
+            end = forced;
+        }
+        runs[runCount].start = start;
+        runs[runCount].length = end - start;
+        runCount++;
+        collapseRuns(arr, runs, &runCount, buffer);
+        start = end;
+    }
+    while (runCount > 1) {
+        mergeRunsAt(arr, runs, &runCount, runCount - 2, buffer);
+    }
+    free(buffer);
+    return 0;
+}
+
+static int compareInts(const void* a, const void* b) {
+    int x = *(const int*)a;
+    int y = *(const int*)b;
+    return (x > y) - (x < y);
+}
+
+int libc_qsort(int* arr, int n) {
+    qsort(arr, n, sizeof(int), compareInts);
+    return 0;
+}
@@ -0,0 +1,39 @@
+#ifndef SORT_ALGO_H
+#define SORT_ALGO_H
+
+// The sorting suite: each function sorts the n ints of arr in ascending
+// order, the same way as its namesake in src/sorts.rs. The ones that need
+// a buffer return -1 if they cannot allocate it, and all return 0 on
+// success.
+
+int insertion_sort(int* arr, int n);
+int merge_sort_top_down(int* arr, int n);
+int merge_sort_bottom_up(int* arr, int n);
+int heap_sort(int* arr, int n);
+int introsort(int* arr, int n);
+int shell_sort(int* arr, int n);
+int radix_sort_lsd(int* arr, int n);
+int radix_sort_msd(int* arr, int n);
+
+// Needs a counter for every value between the smallest and the largest.
+int counting_sort(int* arr, int n);
+
+// Timsort without galloping: merges the runs already in the array.
+int natural_merge_sort(int* arr, int n);
+
+// libc qsort with a comparison function, the reference for both slice
+// sorts of the Rust side.
+int libc_qsort(int* arr, int n);
+
+// Building blocks shared with parallel_sort_algo.c.
+
+// Merges the sorted left and right into out, taking from left on ties.
+void merge(const int* left, int leftLen, const int* right, int rightLen, int* out);
+// Sorts the n elements of source, which target also holds, into target.
+void split_merge(int* source, int* target, int n);
+// Hoare partition around the median of the first, middle and last
+// elements. Returns j such that arr[0..j] holds no element above the pivot
+// and arr[j + 1..n - 1] none below it. Needs at least 3 elements.
+int partition_median_of_3(int* arr, int n);
+
+#endif
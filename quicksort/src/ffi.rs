@@ -0,0 +1,194 @@
+//! Bindings to the C implementations in `quicksort_algo.c`,
+//! `quicksort_variants_algo.c`, `sort_algo.c`, `parallel_sort_algo.c` and
+//! `typed_sort_algo.c`.
+
+use std::os::raw::{c_int, c_void};
+
+use harness::ops::{self, OpCounts};
+
+use crate::variants::Variant;
+
+extern "C" {
+    #[link_name = "quickSortOps"]
+    static mut QUICK_SORT_OPS: OpCounts;
+
+    #[link_name = "quickSort"]
+    fn c_quick_sort(arr: *mut c_int, low: c_int, high: c_int);
+}
+
+/// Sorts `arr` with the C `quickSort` and returns what it counted, which
+/// is only kept with the `instrument` feature.
+pub fn quick_sort(arr: &mut [i32]) -> Option<OpCounts> {
+    let high = c_int::try_from(arr.len()).expect("array too large for the C implementation") - 1;
+    // SAFETY: `arr` holds high + 1 ints. The counts are only touched from
+    // the benchmark thread.
+    let counts = unsafe {
+        let ops = std::ptr::addr_of_mut!(QUICK_SORT_OPS);
+        ops.write(OpCounts::default());
+        c_quick_sort(arr.as_mut_ptr(), 0, high);
+        ops.read()
+    };
+    ops::ENABLED.then_some(counts)
+}
+
+extern "C" {
+    #[link_name = "quick_sort_variant"]
+    fn c_quick_sort_variant(arr: *mut c_int, n: c_int, pivot: c_int, partition: c_int, cutoff: c_int, seed: u64)
+        -> i64;
+}
+
+/// Sorts `arr` with the C twin of
+/// [`quick_sort_variant`](crate::variants::quick_sort_variant) and returns
+/// the number of partitions.
+pub fn quick_sort_variant(arr: &mut [i32], variant: &Variant, seed: u64) -> u64 {
+    let n = c_int::try_from(arr.len()).expect("array too large for the C implementation");
+    let cutoff = c_int::try_from(variant.cutoff).unwrap_or(c_int::MAX);
+    // SAFETY: `arr` holds n ints.
+    let partitions = unsafe {
+        c_quick_sort_variant(
+            arr.as_mut_ptr(),
+            n,
+            variant.pivot as c_int,
+            variant.partition as c_int,
+            cutoff,
+            seed,
+        )
+    };
+    partitions as u64
+}
+
+/// One of the sorts in `sort_algo.c`, which sort `n` ints in place and
+/// return -1 if they cannot allocate a buffer.
+pub type CSort = unsafe extern "C" fn(arr: *mut c_int, n: c_int) -> c_int;
+
+extern "C" {
+    #[link_name = "insertion_sort"]
+    fn c_insertion_sort(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "merge_sort_top_down"]
+    fn c_merge_sort_top_down(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "merge_sort_bottom_up"]
+    fn c_merge_sort_bottom_up(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "heap_sort"]
+    fn c_heap_sort(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "introsort"]
+    fn c_introsort(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "shell_sort"]
+    fn c_shell_sort(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "radix_sort_lsd"]
+    fn c_radix_sort_lsd(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "radix_sort_msd"]
+    fn c_radix_sort_msd(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "counting_sort"]
+    fn c_counting_sort(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "natural_merge_sort"]
+    fn c_natural_merge_sort(arr: *mut c_int, n: c_int) -> c_int;
+    #[link_name = "libc_qsort"]
+    fn c_libc_qsort(arr: *mut c_int, n: c_int) -> c_int;
+}
+
+pub const INSERTION_SORT: CSort = c_insertion_sort;
+pub const MERGE_SORT_TOP_DOWN: CSort = c_merge_sort_top_down;
+pub const MERGE_SORT_BOTTOM_UP: CSort = c_merge_sort_bottom_up;
+pub const HEAP_SORT: CSort = c_heap_sort;
+pub const INTROSORT: CSort = c_introsort;
+pub const SHELL_SORT: CSort = c_shell_sort;
+pub const RADIX_SORT_LSD: CSort = c_radix_sort_lsd;
+pub const RADIX_SORT_MSD: CSort = c_radix_sort_msd;
+pub const COUNTING_SORT: CSort = c_counting_sort;
+pub const NATURAL_MERGE_SORT: CSort = c_natural_merge_sort;
+pub const LIBC_QSORT: CSort = c_libc_qsort;
+
+/// Sorts `arr` with one of the C sorts.
+pub fn sort(sort_fn: CSort, arr: &mut [i32]) {
+    let n = c_int::try_from(arr.len()).expect("array too large for the C implementation");
+    // SAFETY: `arr` holds n ints.
+    let result = unsafe { sort_fn(arr.as_mut_ptr(), n) };
+    assert!(result >= 0, "the C sort could not allocate its buffer");
+}
+
+/// One of the sorts in `parallel_sort_algo.c`, which sort `n` ints on up
+/// to `threads` threads and return -1 if they cannot allocate a buffer.
+pub type CParallelSort = unsafe extern "C" fn(arr: *mut c_int, n: c_int, threads: c_int) -> c_int;
+
+extern "C" {
+    #[link_name = "quick_sort_omp"]
+    fn c_quick_sort_omp(arr: *mut c_int, n: c_int, threads: c_int) -> c_int;
+    #[link_name = "quick_sort_pthreads"]
+    fn c_quick_sort_pthreads(arr: *mut c_int, n: c_int, threads: c_int) -> c_int;
+    #[link_name = "merge_sort_pthreads"]
+    fn c_merge_sort_pthreads(arr: *mut c_int, n: c_int, threads: c_int) -> c_int;
+    #[link_name = "sample_sort_pthreads"]
+    fn c_sample_sort_pthreads(arr: *mut c_int, n: c_int, threads: c_int) -> c_int;
+}
+
+pub const QUICK_SORT_OMP: CParallelSort = c_quick_sort_omp;
+pub const QUICK_SORT_PTHREADS: CParallelSort = c_quick_sort_pthreads;
+pub const MERGE_SORT_PTHREADS: CParallelSort = c_merge_sort_pthreads;
+pub const SAMPLE_SORT_PTHREADS: CParallelSort = c_sample_sort_pthreads;
+
+/// Sorts `arr` with one of the parallel C sorts.
+pub fn sort_parallel(sort_fn: CParallelSort, arr: &mut [i32], threads: usize) {
+    let n = c_int::try_from(arr.len()).expect("array too large for the C implementation");
+    let threads = c_int::try_from(threads).unwrap_or(c_int::MAX);
+    // SAFETY: `arr` holds n ints, which the threads of the sort only touch
+    // in disjoint parts and have all finished with when it returns.
+    let result = unsafe { sort_fn(arr.as_mut_ptr(), n, threads) };
+    assert!(result >= 0, "the C sort could not allocate its buffer");
+}
+
+/// A qsort comparator.
+pub type CCompare = unsafe extern "C" fn(a: *const c_void, b: *const c_void) -> c_int;
+
+/// One of the sorts in `typed_sort_algo.c`, which take their array like
+/// qsort: `n` elements of `size` bytes, ordered by a comparator.
+pub type CTypedSort = unsafe extern "C" fn(base: *mut c_void, n: c_int, size: usize, compare: CCompare);
+
+extern "C" {
+    #[link_name = "quick_sort_typed"]
+    fn c_quick_sort_typed(base: *mut c_void, n: c_int, size: usize, compare: CCompare);
+    #[link_name = "selection_sort_typed"]
+    fn c_selection_sort_typed(base: *mut c_void, n: c_int, size: usize, compare: CCompare);
+    #[link_name = "heap_sort_typed"]
+    fn c_heap_sort_typed(base: *mut c_void, n: c_int, size: usize, compare: CCompare);
+    #[link_name = "qsort_typed"]
+    fn c_qsort_typed(base: *mut c_void, n: c_int, size: usize, compare: CCompare);
+
+    #[link_name = "compare_i32"]
+    fn c_compare_i32(a: *const c_void, b: *const c_void) -> c_int;
+    #[link_name = "compare_u8"]
+    fn c_compare_u8(a: *const c_void, b: *const c_void) -> c_int;
+    #[link_name = "compare_u64"]
+    fn c_compare_u64(a: *const c_void, b: *const c_void) -> c_int;
+    #[link_name = "compare_f64_total"]
+    fn c_compare_f64_total(a: *const c_void, b: *const c_void) -> c_int;
+    #[link_name = "compare_key"]
+    fn c_compare_key(a: *const c_void, b: *const c_void) -> c_int;
+    #[link_name = "compare_string"]
+    fn c_compare_string(a: *const c_void, b: *const c_void) -> c_int;
+}
+
+pub const QUICK_SORT_TYPED: CTypedSort = c_quick_sort_typed;
+pub const SELECTION_SORT_TYPED: CTypedSort = c_selection_sort_typed;
+pub const HEAP_SORT_TYPED: CTypedSort = c_heap_sort_typed;
+pub const QSORT_TYPED: CTypedSort = c_qsort_typed;
+
+pub const COMPARE_I32: CCompare = c_compare_i32;
+pub const COMPARE_U8: CCompare = c_compare_u8;
+pub const COMPARE_U64: CCompare = c_compare_u64;
+pub const COMPARE_F64_TOTAL: CCompare = c_compare_f64_total;
+pub const COMPARE_KEY: CCompare = c_compare_key;
+pub const COMPARE_STRING: CCompare = c_compare_string;
+
+/// Sorts `arr` with one of the typed C sorts.
+///
+/// # Safety
+///
+/// `compare` must read `T`s: `T` must have the layout the comparator
+/// expects, e.g. a leading `u64` key for [`COMPARE_KEY`].
+pub unsafe fn sort_typed<T>(sort_fn: CTypedSort, arr: &mut [T], compare: CCompare) {
+    let n = c_int::try_from(arr.len()).expect("array too large for the C implementation");
+    // SAFETY: `arr` holds n elements of size_of::<T>() bytes, and the
+    // caller vouches for the comparator. The sorts only swap whole
+    // elements, so every `T` stays intact.
+    unsafe { sort_fn(arr.as_mut_ptr().cast(), n, size_of::<T>(), compare) }
+}
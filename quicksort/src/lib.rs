@@ -0,0 +1,279 @@
+use std::cmp::Ordering;
+
+use harness::distribution::Distribution;
+use harness::input::DEFAULT_SEED;
+use harness::ops::{self, OpCounts};
+use harness::{Args, Benchmark, Language};
+
+pub mod ffi;
+pub mod parallel;
+pub mod sorts;
+pub mod suite;
+pub mod typed;
+pub mod variants;
+
+pub use parallel::ParallelSort;
+pub use suite::{Algorithm, Sort};
+pub use typed::TypedSort;
+pub use variants::Variant;
+
+fn partition<T, F>(arr: &mut [T], low: usize, high: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering + ?Sized,
+{
+    let mut i = low;
+
+    for j in low..high {
+        ops::compare();
+        if compare(&arr[j], &arr[high]) != Ordering::Greater {
+            ops::swap();
+            arr.swap(i, j);
+            i += 1;
+        }
+    }
+
+    ops::swap();
+    arr.swap(i, high);
+    i
+}
+
+/// Sorts `arr[low..=high]` with the last element as pivot. It recurses
+/// into the smaller side only and loops on the larger one, so sorted or
+/// all-equal input is quadratic but does not overflow the stack.
+pub fn quick_sort<T: Ord>(arr: &mut [T], low: usize, high: usize) {
+    quick_sort_by(arr, low, high, &mut T::cmp);
+}
+
+/// [`quick_sort`] with a comparator, which may also be a `dyn FnMut`.
+pub fn quick_sort_by<T, F>(arr: &mut [T], mut low: usize, mut high: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering + ?Sized,
+{
+    let _call = ops::call();
+    while low < high {
+        let pi = partition(arr, low, high, compare);
+        if pi - low < high - pi {
+            if pi > 0 {
+                quick_sort_by(arr, low, pi - 1, compare);
+            }
+            low = pi + 1;
+        } else {
+            quick_sort_by(arr, pi + 1, high, compare);
+            if pi == 0 {
+                break;
+            }
+            high = pi - 1;
+        }
+    }
+}
+
+/// The array to sort and a copy of it, so every run and language can be
+/// handed exactly the same values, and those values sorted by
+/// `slice::sort_unstable` to check every run against.
+pub struct SortInput {
+    pub original: Vec<i32>,
+    pub arr: Vec<i32>,
+    pub expected: Vec<i32>,
+}
+
+/// What a run of [`QuickSort`] counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counts {
+    /// Operations of [`quick_sort`], kept with the `instrument` feature.
+    Ops(Option<OpCounts>),
+    /// Partitions of a [`Variant`].
+    Partitions(u64),
+}
+
+pub struct QuickSort {
+    /// Distribution of the array, unless `--dist` picks another.
+    pub distribution: Distribution,
+    /// Set by `--pivot`, `--partition` or `--cutoff`, which switch from
+    /// [`quick_sort`] to [`variants::quick_sort_variant`].
+    pub variant: Option<Variant>,
+    language: Language,
+    seed: u64,
+}
+
+impl QuickSort {
+    pub fn new(distribution: Distribution) -> Self {
+        QuickSort {
+            distribution,
+            variant: None,
+            language: Language::Rust,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+impl Benchmark for QuickSort {
+    type Input = SortInput;
+    type Output = Counts;
+
+    fn name(&self) -> &str {
+        "quicksort"
+    }
+
+    fn variant(&self) -> String {
+        match self.variant {
+            Some(variant) => format!("{}/{}", self.distribution, variant),
+            None => self.distribution.to_string(),
+        }
+    }
+
+    fn size_name(&self) -> &str {
+        "array_size"
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--dist NAME[:param=value,...]] [--pivot last|random|median3|ninther] \
+         [--partition lomuto|hoare|three-way|block] [--cutoff N]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.distribution = args.parse("--dist")?.unwrap_or(self.distribution);
+        let pivot = args.parse("--pivot")?;
+        let partition = args.parse("--partition")?;
+        let cutoff = args.parse("--cutoff")?;
+        if pivot.is_some() || partition.is_some() || cutoff.is_some() {
+            let base = self.variant.unwrap_or_default();
+            self.variant = Some(Variant {
+                pivot: pivot.unwrap_or(base.pivot),
+                partition: partition.unwrap_or(base.partition),
+                cutoff: cutoff.unwrap_or(base.cutoff),
+            });
+        }
+        Ok(())
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let arr = self.distribution.generate(size, self.seed);
+        let mut expected = arr.clone();
+        expected.sort_unstable();
+        SortInput {
+            original: arr.clone(),
+            arr,
+            expected,
+        }
+    }
+
+    fn input_arrays(&self, input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        Some(vec![input.original.clone()])
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.arr.copy_from_slice(&input.original);
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> Counts {
+        let arr = &mut input.arr;
+        if let Some(variant) = &self.variant {
+            let partitions = match self.language {
+                Language::Rust => variants::quick_sort_variant(arr, variant, self.seed),
+                Language::C => ffi::quick_sort_variant(arr, variant, self.seed),
+            };
+            return Counts::Partitions(partitions);
+        }
+        Counts::Ops(match self.language {
+            Language::Rust => {
+                ops::reset();
+                let high = arr.len() - 1;
+                quick_sort(arr, 0, high);
+                ops::take()
+            }
+            Language::C => ffi::quick_sort(arr),
+        })
+    }
+
+    fn verify(&self, input: &Self::Input, _counts: &Counts) -> Result<(), String> {
+        match input.arr.iter().zip(&input.expected).position(|(a, b)| a != b) {
+            Some(i) => Err(format!(
+                "index {} holds {}, expected {}",
+                i, input.arr[i], input.expected[i]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to sort the array of size {}", size)
+    }
+
+    fn summary(&self, counts: &Counts) -> Option<String> {
+        match counts {
+            Counts::Ops(ops) => ops.map(|ops| format!("with {}", ops)),
+            Counts::Partitions(partitions) => Some(format!("with {} partitions", partitions)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Inputs that tend to break a sort, by name: random values over a wide
+    /// and a narrow range and over all of `i32`, few distinct values, runs,
+    /// sorted, reversed, all equal, and the median-of-3 killer.
+    pub(crate) fn inputs(size: usize) -> Vec<(&'static str, Vec<i32>)> {
+        [
+            "uniform",
+            "uniform:min=-2147483648,max=2147483647",
+            "uniform:min=-50,max=50",
+            "few-unique:k=3",
+            "sawtooth:teeth=4",
+            "organ-pipe",
+            "sorted",
+            "reversed",
+            "all-equal",
+            "killer",
+        ]
+        .into_iter()
+        .map(|spec| (spec, spec.parse::<Distribution>().unwrap().generate(size, 1)))
+        .collect()
+    }
+
+    /// Checks that `sort` sorts every input of every length like
+    /// `sort_unstable`.
+    pub(crate) fn check_sort(name: &str, lengths: &[usize], mut sort: impl FnMut(&mut [i32])) {
+        for &size in lengths {
+            for (spec, input) in inputs(size) {
+                let mut expected = input.clone();
+                expected.sort_unstable();
+                let mut arr = input;
+                sort(&mut arr);
+                assert!(arr == expected, "{name} missorts {size} {spec} values");
+            }
+        }
+    }
+
+    #[test]
+    fn quick_sort_sorts() {
+        let lengths = [1, 2, 3, 10, 100, 1000];
+        check_sort("quick_sort", &lengths, |arr| quick_sort(arr, 0, arr.len() - 1));
+        check_sort("quick_sort_by", &lengths, |arr| quick_sort_by(arr, 0, arr.len() - 1, &mut |a, b| a.cmp(b)));
+        // Descending, through a comparator.
+        let mut arr = inputs(100).remove(0).1;
+        quick_sort_by(&mut arr, 0, 99, &mut |a: &i32, b: &i32| b.cmp(a));
+        assert!(arr.windows(2).all(|w| w[0] >= w[1]));
+        check_sort("C quickSort", &lengths, |arr| {
+            ffi::quick_sort(arr);
+        });
+    }
+}
@@ -0,0 +1,470 @@
+//! The `sort_parallel` benchmark: quicksort, merge sort and sample sort on
+//! several threads, each against a C counterpart in
+//! `parallel_sort_algo.c`.
+//!
+//! The sorts hand slices shorter than [`PARALLEL_CUTOFF`] to the sequential
+//! sorts of [`crate::sorts`], and so do the C versions with their twins, so
+//! the languages differ only in how they share the work.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+
+use harness::distribution::Distribution;
+use harness::{Args, Benchmark, Language};
+
+use crate::ffi::{self, CParallelSort};
+use crate::sorts::{self, introsort, merge, partition_median_of_3, split_merge};
+use crate::suite::SuiteInput;
+
+/// Slices shorter than this are sorted sequentially.
+pub const PARALLEL_CUTOFF: usize = 1 << 13;
+
+/// Samples per bucket that sample sort draws to pick its splitters.
+const OVERSAMPLE: usize = 64;
+
+/// Quicksort with `rayon::join`, on the current rayon pool. It partitions
+/// like [`introsort`] and falls back to it for short slices and, as
+/// introsort falls back to heap sort, once the recursion gets deeper than
+/// twice the logarithm of the length.
+pub fn quick_sort_rayon(arr: &mut [i32]) {
+    let depth = 2 * (usize::BITS - arr.len().leading_zeros());
+    quick_sort_rayon_at(arr, depth);
+}
+
+fn quick_sort_rayon_at(arr: &mut [i32], depth: u32) {
+    if arr.len() < PARALLEL_CUTOFF || depth == 0 {
+        introsort(arr);
+        return;
+    }
+    let split = partition_median_of_3(arr) + 1;
+    let (left, right) = arr.split_at_mut(split);
+    rayon::join(
+        || quick_sort_rayon_at(left, depth - 1),
+        || quick_sort_rayon_at(right, depth - 1),
+    );
+}
+
+/// Quicksort on scoped threads: after every partition a new thread takes
+/// the left side and half of the threads, until each thread sorts its
+/// slice with [`introsort`].
+pub fn quick_sort_threads(arr: &mut [i32], threads: usize) {
+    if threads <= 1 || arr.len() < PARALLEL_CUTOFF {
+        introsort(arr);
+        return;
+    }
+    let split = partition_median_of_3(arr) + 1;
+    let (left, right) = arr.split_at_mut(split);
+    thread::scope(|s| {
+        s.spawn(|| quick_sort_threads(left, threads / 2));
+        quick_sort_threads(right, threads - threads / 2);
+    });
+}
+
+/// [`merge_sort_top_down`](sorts::merge_sort_top_down) on scoped threads:
+/// both halves are sorted on threads of their own, and the merges split
+/// their output among the threads as well.
+pub fn merge_sort_parallel(arr: &mut [i32], threads: usize) {
+    let mut buffer = arr.to_vec();
+    split_merge_parallel(&mut buffer, arr, threads);
+}
+
+fn split_merge_parallel(source: &mut [i32], target: &mut [i32], threads: usize) {
+    let n = target.len();
+    if threads <= 1 || n < PARALLEL_CUTOFF {
+        split_merge(source, target);
+        return;
+    }
+    let mid = n / 2;
+    let (source_left, source_right) = source.split_at_mut(mid);
+    let (target_left, target_right) = target.split_at_mut(mid);
+    thread::scope(|s| {
+        s.spawn(|| split_merge_parallel(target_left, source_left, threads / 2));
+        split_merge_parallel(target_right, source_right, threads - threads / 2);
+    });
+    merge_parallel(source_left, source_right, target, threads);
+}
+
+/// Splits the longer input at its middle and the other one where that
+/// element belongs, then merges both pairs side by side. Stable, like
+/// [`merge`].
+fn merge_parallel(left: &[i32], right: &[i32], out: &mut [i32], threads: usize) {
+    if threads <= 1 || out.len() < PARALLEL_CUTOFF {
+        merge(left, right, out);
+        return;
+    }
+    let (i, j) = if left.len() >= right.len() {
+        let i = left.len() / 2;
+        (i, right.partition_point(|&x| x < left[i]))
+    } else {
+        let j = right.len() / 2;
+        (left.partition_point(|&x| x <= right[j]), j)
+    };
+    let (out_left, out_right) = out.split_at_mut(i + j);
+    thread::scope(|s| {
+        s.spawn(|| merge_parallel(&left[..i], &right[..j], out_left, threads / 2));
+        merge_parallel(&left[i..], &right[j..], out_right, threads - threads / 2);
+    });
+}
+
+/// Sample sort on scoped threads, with one bucket per thread. The
+/// splitters come from evenly spaced samples. Each thread counts how many
+/// elements of its chunk go to each bucket, scatters them into its own
+/// part of every bucket, and finally sorts one bucket with [`introsort`]
+/// and copies it back. Duplicates of a splitter all land in one bucket, so
+/// inputs with few distinct values leave some threads idle.
+pub fn sample_sort(arr: &mut [i32], threads: usize) {
+    let n = arr.len();
+    if threads <= 1 || n < PARALLEL_CUTOFF {
+        introsort(arr);
+        return;
+    }
+    let buckets = threads;
+    let mut samples: Vec<i32> = (0..buckets * OVERSAMPLE)
+        .map(|i| arr[i * n / (buckets * OVERSAMPLE)])
+        .collect();
+    introsort(&mut samples);
+    let splitters: Vec<i32> = (1..buckets).map(|b| samples[b * OVERSAMPLE]).collect();
+    let bucket = |x: i32| splitters.partition_point(|&s| s <= x);
+
+    let chunk = n.div_ceil(threads);
+    let counts: Vec<Vec<usize>> = thread::scope(|s| {
+        let handles: Vec<_> = arr
+            .chunks(chunk)
+            .map(|part| {
+                s.spawn(move || {
+                    let mut counts = vec![0; buckets];
+                    for &x in part {
+                        counts[bucket(x)] += 1;
+                    }
+                    counts
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    // Bucket by bucket, each chunk gets the next stretch of the buffer.
+    let mut buffer = vec![0; n];
+    let mut targets: Vec<Vec<&mut [i32]>> = counts.iter().map(|_| Vec::with_capacity(buckets)).collect();
+    let mut rest = &mut buffer[..];
+    for b in 0..buckets {
+        for (c, chunk_counts) in counts.iter().enumerate() {
+            let (target, tail) = std::mem::take(&mut rest).split_at_mut(chunk_counts[b]);
+            targets[c].push(target);
+            rest = tail;
+        }
+    }
+    thread::scope(|s| {
+        for (part, mut targets) in arr.chunks(chunk).zip(targets) {
+            s.spawn(move || {
+                let mut filled = vec![0; buckets];
+                for &x in part {
+                    let b = bucket(x);
+                    targets[b][filled[b]] = x;
+                    filled[b] += 1;
+                }
+            });
+        }
+    });
+
+    let mut sources = &mut buffer[..];
+    let mut targets = &mut arr[..];
+    thread::scope(|s| {
+        for b in 0..buckets {
+            let size = counts.iter().map(|chunk_counts| chunk_counts[b]).sum();
+            let (source, source_tail) = std::mem::take(&mut sources).split_at_mut(size);
+            let (target, target_tail) = std::mem::take(&mut targets).split_at_mut(size);
+            (sources, targets) = (source_tail, target_tail);
+            s.spawn(move || {
+                introsort(source);
+                target.copy_from_slice(source);
+            });
+        }
+    });
+}
+
+/// `--algo`: which parallel sort to run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// [`quick_sort_rayon`], against OpenMP tasks.
+    #[default]
+    QuicksortRayon,
+    /// [`quick_sort_threads`], against pthreads.
+    QuicksortThreads,
+    /// [`merge_sort_parallel`], against pthreads.
+    Merge,
+    /// [`sample_sort`], against pthreads.
+    Sample,
+}
+
+impl Algorithm {
+    /// The sequential sort that runs without threads: the one the parallel
+    /// sort falls back to on short slices.
+    fn sequential(self) -> (fn(&mut [i32]), ffi::CSort) {
+        match self {
+            Algorithm::Merge => (sorts::merge_sort_top_down, ffi::MERGE_SORT_TOP_DOWN),
+            _ => (introsort, ffi::INTROSORT),
+        }
+    }
+
+    fn c(self) -> CParallelSort {
+        match self {
+            Algorithm::QuicksortRayon => ffi::QUICK_SORT_OMP,
+            Algorithm::QuicksortThreads => ffi::QUICK_SORT_PTHREADS,
+            Algorithm::Merge => ffi::MERGE_SORT_PTHREADS,
+            Algorithm::Sample => ffi::SAMPLE_SORT_PTHREADS,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::QuicksortRayon => write!(f, "quicksort-rayon"),
+            Algorithm::QuicksortThreads => write!(f, "quicksort-threads"),
+            Algorithm::Merge => write!(f, "merge"),
+            Algorithm::Sample => write!(f, "sample"),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quicksort-rayon" => Ok(Algorithm::QuicksortRayon),
+            "quicksort-threads" => Ok(Algorithm::QuicksortThreads),
+            "merge" => Ok(Algorithm::Merge),
+            "sample" => Ok(Algorithm::Sample),
+            _ => Err(format!(
+                "unknown algorithm: {s} (expected quicksort-rayon, quicksort-threads, merge or sample)"
+            )),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ParallelSort {
+    algorithm: Algorithm,
+    distribution: Distribution,
+    language: Language,
+    seed: u64,
+    /// `None` runs the sequential sort instead.
+    threads: Option<usize>,
+    /// Rayon pools by thread count, built once for the whole sweep.
+    pools: HashMap<usize, rayon::ThreadPool>,
+}
+
+impl Benchmark for ParallelSort {
+    type Input = SuiteInput;
+    type Output = ();
+
+    fn name(&self) -> &str {
+        "sort_parallel"
+    }
+
+    fn variant(&self) -> String {
+        format!("{}/{}", self.algorithm, self.distribution)
+    }
+
+    fn size_name(&self) -> &str {
+        "array_size"
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn input_arrays(&self, input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        Some(vec![input.original.clone()])
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn threads(&self) -> Option<usize> {
+        self.threads
+    }
+
+    fn set_threads(&mut self, threads: Option<usize>) -> bool {
+        if let (Algorithm::QuicksortRayon, Some(n)) = (self.algorithm, threads) {
+            if let Entry::Vacant(entry) = self.pools.entry(n) {
+                match rayon::ThreadPoolBuilder::new().num_threads(n).build() {
+                    Ok(pool) => {
+                        entry.insert(pool);
+                    }
+                    Err(_) => return false,
+                }
+            }
+        }
+        self.threads = threads;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--algo quicksort-rayon|quicksort-threads|merge|sample] [--dist NAME[:param=value,...]]"
+    }
+
+    /// Without `--threads`, runs on every core.
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.algorithm = args.parse("--algo")?.unwrap_or_default();
+        self.distribution = args.parse("--dist")?.unwrap_or_default();
+        let cores = thread::available_parallelism().map_or(1, |n| n.get());
+        if !self.set_threads(Some(cores)) {
+            return Err(format!("failed to start {} threads", cores));
+        }
+        Ok(())
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let arr = self.distribution.generate(size, self.seed);
+        let mut expected = arr.clone();
+        expected.sort_unstable();
+        SuiteInput {
+            original: arr.clone(),
+            arr,
+            expected,
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) {
+        let arr = &mut input.arr;
+        let (rust, c) = self.algorithm.sequential();
+        match (self.language, self.threads) {
+            (Language::Rust, None) => rust(arr),
+            (Language::C, None) => ffi::sort(c, arr),
+            (Language::Rust, Some(n)) => match self.algorithm {
+                Algorithm::QuicksortRayon => self.pools[&n].install(|| quick_sort_rayon(arr)),
+                Algorithm::QuicksortThreads => quick_sort_threads(arr, n),
+                Algorithm::Merge => merge_sort_parallel(arr, n),
+                Algorithm::Sample => sample_sort(arr, n),
+            },
+            (Language::C, Some(n)) => ffi::sort_parallel(self.algorithm.c(), arr, n),
+        }
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.arr.copy_from_slice(&input.original);
+    }
+
+    fn verify(&self, input: &Self::Input, _output: &()) -> Result<(), String> {
+        match input.arr.iter().zip(&input.expected).position(|(a, b)| a != b) {
+            Some(i) => Err(format!(
+                "index {} holds {}, expected {}",
+                i, input.arr[i], input.expected[i]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to sort the array of size {} with {}", size, self.algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{check_sort, inputs};
+
+    const THREADS: [usize; 5] = [1, 2, 3, 4, 8];
+
+    /// Lengths around one and two [`PARALLEL_CUTOFF`]s, below which the
+    /// sorts stop splitting.
+    const LENGTHS: [usize; 7] = [
+        0,
+        1,
+        2,
+        PARALLEL_CUTOFF - 1,
+        PARALLEL_CUTOFF,
+        PARALLEL_CUTOFF + 1,
+        2 * PARALLEL_CUTOFF + 1,
+    ];
+
+    const ALGORITHMS: [Algorithm; 4] = [
+        Algorithm::QuicksortRayon,
+        Algorithm::QuicksortThreads,
+        Algorithm::Merge,
+        Algorithm::Sample,
+    ];
+
+    #[test]
+    fn rust_sorts_sort_on_any_number_of_threads() {
+        for threads in THREADS {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            let what = |name: &str| format!("{name} on {threads} threads");
+            check_sort(&what("quick_sort_rayon"), &LENGTHS, |arr| pool.install(|| quick_sort_rayon(arr)));
+            check_sort(&what("quick_sort_threads"), &LENGTHS, |arr| quick_sort_threads(arr, threads));
+            check_sort(&what("merge_sort_parallel"), &LENGTHS, |arr| merge_sort_parallel(arr, threads));
+            check_sort(&what("sample_sort"), &LENGTHS, |arr| sample_sort(arr, threads));
+        }
+    }
+
+    #[test]
+    fn c_sorts_sort_on_any_number_of_threads() {
+        for threads in THREADS {
+            for algorithm in ALGORITHMS {
+                check_sort(&format!("C {algorithm} on {threads} threads"), &LENGTHS, |arr| {
+                    ffi::sort_parallel(algorithm.c(), arr, threads)
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn merge_parallel_splits_uneven_halves() {
+        let long = 3 * PARALLEL_CUTOFF + 5;
+        for (long_spec, mut long_half) in inputs(long) {
+            long_half.sort_unstable();
+            for (short_spec, mut short_half) in inputs(7).into_iter().chain([("empty", Vec::new())]) {
+                short_half.sort_unstable();
+                for (left, right) in [(&long_half, &short_half), (&short_half, &long_half)] {
+                    let mut expected = vec![0; left.len() + right.len()];
+                    merge(left, right, &mut expected);
+                    for threads in THREADS {
+                        let mut out = vec![0; expected.len()];
+                        merge_parallel(left, right, &mut out, threads);
+                        assert!(
+                            out == expected,
+                            "{threads} threads merge {long_spec} and {short_spec} values wrongly"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sample_sort_puts_equal_splitters_in_one_bucket() {
+        // Every splitter is 0, so all but the last bucket stay empty.
+        let mut arr = vec![0; 2 * PARALLEL_CUTOFF + 1];
+        arr[PARALLEL_CUTOFF] = -1;
+        arr[1] = 1;
+        sample_sort(&mut arr, 8);
+        assert_eq!(arr[..2], [-1, 0]);
+        assert_eq!(arr[arr.len() - 2..], [0, 1]);
+        assert!(arr[1..arr.len() - 1].iter().all(|&x| x == 0));
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for algorithm in ALGORITHMS {
+            assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+        }
+        assert!("quicksort".parse::<Algorithm>().is_err());
+    }
+}
@@ -0,0 +1,6 @@
+use harness::Runner;
+use quicksort::Sort;
+
+fn main() {
+    Runner::new().run(Sort::default());
+}
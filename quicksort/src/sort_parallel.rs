@@ -0,0 +1,6 @@
+use harness::Runner;
+use quicksort::ParallelSort;
+
+fn main() {
+    Runner::new().run(ParallelSort::default());
+}
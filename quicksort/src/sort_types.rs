@@ -0,0 +1,6 @@
+use harness::Runner;
+use quicksort::TypedSort;
+
+fn main() {
+    Runner::new().run(TypedSort::default());
+}
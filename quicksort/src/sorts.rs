@@ -0,0 +1,426 @@
+//! The algorithms of the sorting suite. Each has a C twin in
+//! `sort_algo.c` that follows it step for step.
+
+use std::cmp::Ordering;
+
+/// Below this length introsort and MSD radix sort hand a slice to
+/// insertion sort.
+const INSERTION_CUTOFF: usize = 16;
+
+/// Runs shorter than this are extended with insertion sort before the
+/// natural merge sort merges them.
+const MIN_RUN: usize = 32;
+
+/// Ciura's gap sequence for shell sort, continued by factors of 2.25 for
+/// larger arrays.
+const CIURA_GAPS: [usize; 8] = [1, 4, 10, 23, 57, 132, 301, 701];
+
+/// Sorting more than one counter per value between the smallest and
+/// largest costs more than it saves, so counting sort is only offered
+/// below this range.
+pub const COUNTING_RANGE_LIMIT: u64 = 1 << 26;
+
+pub fn insertion_sort(arr: &mut [i32]) {
+    insertion_sort_from(arr, 1);
+}
+
+/// Insertion sort of `arr` whose first `sorted` elements are in order.
+fn insertion_sort_from(arr: &mut [i32], sorted: usize) {
+    for i in sorted.max(1)..arr.len() {
+        let value = arr[i];
+        let mut j = i;
+        while j > 0 && arr[j - 1] > value {
+            arr[j] = arr[j - 1];
+            j -= 1;
+        }
+        arr[j] = value;
+    }
+}
+
+/// Merges the sorted `left` and `right` into `out`, taking from `left` on
+/// ties so the sort stays stable.
+pub(crate) fn merge(left: &[i32], right: &[i32], out: &mut [i32]) {
+    let (mut i, mut j) = (0, 0);
+    for slot in out.iter_mut() {
+        if j == right.len() || (i < left.len() && left[i] <= right[j]) {
+            *slot = left[i];
+            i += 1;
+        } else {
+            *slot = right[j];
+            j += 1;
+        }
+    }
+}
+
+/// Recursive merge sort. The array and a copy of it swap roles at every
+/// level, so each merge writes straight into the other one without
+/// copying back.
+pub fn merge_sort_top_down(arr: &mut [i32]) {
+    let mut buffer = arr.to_vec();
+    split_merge(&mut buffer, arr);
+}
+
+/// Sorts the elements of `source`, which `target` also holds, into
+/// `target`.
+pub(crate) fn split_merge(source: &mut [i32], target: &mut [i32]) {
+    let n = target.len();
+    if n < 2 {
+        return;
+    }
+    let mid = n / 2;
+    split_merge(&mut target[..mid], &mut source[..mid]);
+    split_merge(&mut target[mid..], &mut source[mid..]);
+    merge(&source[..mid], &source[mid..], target);
+}
+
+/// Iterative merge sort: merges runs of 1, 2, 4, ... elements, back and
+/// forth between the array and a buffer.
+pub fn merge_sort_bottom_up(arr: &mut [i32]) {
+    let n = arr.len();
+    let mut buffer = vec![0; n];
+    let mut in_buffer = false;
+    let mut width = 1;
+    while width < n {
+        let (source, target) = if in_buffer {
+            (&buffer[..], &mut arr[..])
+        } else {
+            (&arr[..], &mut buffer[..])
+        };
+        for start in (0..n).step_by(2 * width) {
+            let mid = (start + width).min(n);
+            let end = (start + 2 * width).min(n);
+            merge(&source[start..mid], &source[mid..end], &mut target[start..end]);
+        }
+        in_buffer = !in_buffer;
+        width *= 2;
+    }
+    if in_buffer {
+        arr.copy_from_slice(&buffer);
+    }
+}
+
+/// Moves `arr[root]` down the max-heap `arr` until both children are
+/// smaller.
+fn sift_down<T, F>(arr: &mut [T], mut root: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering + ?Sized,
+{
+    let n = arr.len();
+    loop {
+        let mut child = 2 * root + 1;
+        if child >= n {
+            break;
+        }
+        if child + 1 < n && compare(&arr[child + 1], &arr[child]) == Ordering::Greater {
+            child += 1;
+        }
+        if compare(&arr[root], &arr[child]) != Ordering::Less {
+            break;
+        }
+        arr.swap(root, child);
+        root = child;
+    }
+}
+
+pub fn heap_sort<T: Ord>(arr: &mut [T]) {
+    heap_sort_by(arr, &mut T::cmp);
+}
+
+/// [`heap_sort`] with a comparator, which may also be a `dyn FnMut`.
+pub fn heap_sort_by<T, F>(arr: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering + ?Sized,
+{
+    let n = arr.len();
+    for root in (0..n / 2).rev() {
+        sift_down(arr, root, compare);
+    }
+    for end in (1..n).rev() {
+        arr.swap(0, end);
+        sift_down(&mut arr[..end], 0, compare);
+    }
+}
+
+/// Introsort as in C++ standard libraries: quicksort with a median-of-3
+/// pivot and Hoare partitioning, heap sort once the recursion gets deeper
+/// than twice the logarithm of the length, and insertion sort for short
+/// slices.
+pub fn introsort(arr: &mut [i32]) {
+    let depth = 2 * (usize::BITS - arr.len().leading_zeros());
+    introsort_loop(arr, depth);
+}
+
+fn introsort_loop(mut arr: &mut [i32], mut depth: u32) {
+    while arr.len() > INSERTION_CUTOFF {
+        if depth == 0 {
+            heap_sort(arr);
+            return;
+        }
+        depth -= 1;
+        let split = partition_median_of_3(arr) + 1;
+        // Recurse into the smaller side and loop on the larger one, so the
+        // stack stays logarithmic.
+        let (left, right) = std::mem::take(&mut arr).split_at_mut(split);
+        if left.len() < right.len() {
+            introsort_loop(left, depth);
+            arr = right;
+        } else {
+            introsort_loop(right, depth);
+            arr = left;
+        }
+    }
+    insertion_sort(arr);
+}
+
+/// Hoare partition around the median of the first, middle and last
+/// elements. Returns `j` such that `arr[..=j]` holds no element above the
+/// pivot and `arr[j + 1..]` none below it. Needs at least 3 elements.
+pub(crate) fn partition_median_of_3(arr: &mut [i32]) -> usize {
+    let (mid, last) = (arr.len() / 2, arr.len() - 1);
+    if arr[mid] < arr[0] {
+        arr.swap(mid, 0);
+    }
+    if arr[last] < arr[0] {
+        arr.swap(last, 0);
+    }
+    if arr[last] < arr[mid] {
+        arr.swap(last, mid);
+    }
+    let pivot = arr[mid];
+    let (mut i, mut j) = (0, last);
+    loop {
+        while arr[i] < pivot {
+            i += 1;
+        }
+        while arr[j] > pivot {
+            j -= 1;
+        }
+        if i >= j {
+            return j;
+        }
+        arr.swap(i, j);
+        i += 1;
+        j -= 1;
+    }
+}
+
+/// Shell sort with [`CIURA_GAPS`].
+pub fn shell_sort(arr: &mut [i32]) {
+    let n = arr.len();
+    let mut gaps = CIURA_GAPS.to_vec();
+    while let Some(&gap) = gaps.last().filter(|&&gap| gap < n) {
+        gaps.push(gap * 9 / 4);
+    }
+    for &gap in gaps.iter().rev().filter(|&&gap| gap < n) {
+        for i in gap..n {
+            let value = arr[i];
+            let mut j = i;
+            while j >= gap && arr[j - gap] > value {
+                arr[j] = arr[j - gap];
+                j -= gap;
+            }
+            arr[j] = value;
+        }
+    }
+}
+
+/// Byte `shift / 8` of `value` with the sign bit flipped, so that the
+/// bytes of negative numbers sort before those of positive ones.
+#[inline]
+fn digit(value: i32, shift: u32) -> usize {
+    (((value as u32) ^ 0x8000_0000) >> shift) as usize & 0xff
+}
+
+/// LSD radix sort: four stable counting passes over the bytes, from the
+/// lowest, back and forth between the array and a buffer.
+pub fn radix_sort_lsd(arr: &mut [i32]) {
+    let mut buffer = vec![0; arr.len()];
+    let (mut source, mut target) = (arr, &mut buffer[..]);
+    for shift in (0..32).step_by(8) {
+        let mut starts = [0usize; 256];
+        for &value in source.iter() {
+            starts[digit(value, shift)] += 1;
+        }
+        let mut total = 0;
+        for start in starts.iter_mut() {
+            (*start, total) = (total, total + *start);
+        }
+        for &value in source.iter() {
+            let d = digit(value, shift);
+            target[starts[d]] = value;
+            starts[d] += 1;
+        }
+        (source, target) = (target, source);
+    }
+    // An even number of passes ends in the array itself.
+}
+
+/// MSD radix sort: distributes by the highest byte through a buffer, then
+/// sorts every bucket by the next byte. Buckets of up to
+/// [`INSERTION_CUTOFF`] elements are left to insertion sort.
+pub fn radix_sort_msd(arr: &mut [i32]) {
+    let mut buffer = vec![0; arr.len()];
+    msd_pass(arr, &mut buffer, 24);
+}
+
+fn msd_pass(arr: &mut [i32], buffer: &mut [i32], shift: u32) {
+    if arr.len() <= INSERTION_CUTOFF {
+        insertion_sort(arr);
+        return;
+    }
+    let mut bounds = [0usize; 257];
+    for &value in arr.iter() {
+        bounds[digit(value, shift) + 1] += 1;
+    }
+    for d in 0..256 {
+        bounds[d + 1] += bounds[d];
+    }
+    let mut next = bounds;
+    for &value in arr.iter() {
+        let d = digit(value, shift);
+        buffer[next[d]] = value;
+        next[d] += 1;
+    }
+    arr.copy_from_slice(buffer);
+    if shift == 0 {
+        return;
+    }
+    for d in 0..256 {
+        let (start, end) = (bounds[d], bounds[d + 1]);
+        if end - start > 1 {
+            msd_pass(&mut arr[start..end], &mut buffer[start..end], shift - 8);
+        }
+    }
+}
+
+/// Counting sort: counts every value between the smallest and the largest,
+/// then writes them out in order. Takes memory for the whole range, which
+/// must stay below [`COUNTING_RANGE_LIMIT`].
+pub fn counting_sort(arr: &mut [i32]) {
+    let (Some(&min), Some(&max)) = (arr.iter().min(), arr.iter().max()) else {
+        return;
+    };
+    let range = (max as i64 - min as i64 + 1) as u64;
+    assert!(range <= COUNTING_RANGE_LIMIT, "counting sort over a range of {}", range);
+    let mut counts = vec![0u32; range as usize];
+    for &value in arr.iter() {
+        counts[(value as i64 - min as i64) as usize] += 1;
+    }
+    let mut out = arr.iter_mut();
+    for (offset, &count) in counts.iter().enumerate() {
+        let value = (min as i64 + offset as i64) as i32;
+        for slot in out.by_ref().take(count as usize) {
+            *slot = value;
+        }
+    }
+}
+
+/// A simplified timsort: finds the ascending and strictly descending runs
+/// already in the array, reverses the descending ones, extends short runs
+/// to [`MIN_RUN`] with insertion sort, and merges them on a stack that
+/// keeps timsort's invariants. There is no galloping.
+pub fn natural_merge_sort(arr: &mut [i32]) {
+    let n = arr.len();
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut buffer = Vec::with_capacity(n / 2);
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        if end < n && arr[end] < arr[start] {
+            while end < n && arr[end] < arr[end - 1] {
+                end += 1;
+            }
+            arr[start..end].reverse();
+        } else {
+            while end < n && arr[end] >= arr[end - 1] {
+                end += 1;
+            }
+        }
+        let forced = (start + MIN_RUN).min(n);
+        if end < forced {
+            insertion_sort_from(&mut arr[start..forced], end - start);
+            end = forced;
+        }
+        runs.push((start, end - start));
+        collapse_runs(arr, &mut runs, &mut buffer);
+        start = end;
+    }
+    while runs.len() > 1 {
+        let at = runs.len() - 2;
+        merge_runs_at(arr, &mut runs, at, &mut buffer);
+    }
+}
+
+/// Merges runs until, from the top of the stack down, every run is longer
+/// than the next two together and than the next one.
+fn collapse_runs(arr: &mut [i32], runs: &mut Vec<(usize, usize)>, buffer: &mut Vec<i32>) {
+    loop {
+        let k = runs.len();
+        let len = |i: usize| runs[i].1;
+        if (k >= 3 && len(k - 3) <= len(k - 2) + len(k - 1)) || (k >= 4 && len(k - 4) <= len(k - 3) + len(k - 2)) {
+            let at = if len(k - 3) < len(k - 1) { k - 3 } else { k - 2 };
+            merge_runs_at(arr, runs, at, buffer);
+        } else if k >= 2 && len(k - 2) <= len(k - 1) {
+            merge_runs_at(arr, runs, k - 2, buffer);
+        } else {
+            break;
+        }
+    }
+}
+
+/// Merges run `at` with the run after it, copying only the first one out.
+fn merge_runs_at(arr: &mut [i32], runs: &mut Vec<(usize, usize)>, at: usize, buffer: &mut Vec<i32>) {
+    let (start, left_len) = runs[at];
+    let right_len = runs[at + 1].1;
+    runs[at].1 += right_len;
+    runs.remove(at + 1);
+
+    let arr = &mut arr[start..start + left_len + right_len];
+    buffer.clear();
+    buffer.extend_from_slice(&arr[..left_len]);
+    let (mut i, mut j, mut k) = (0, left_len, 0);
+    while i < buffer.len() && j < arr.len() {
+        if arr[j] < buffer[i] {
+            arr[k] = arr[j];
+            j += 1;
+        } else {
+            arr[k] = buffer[i];
+            i += 1;
+        }
+        k += 1;
+    }
+    arr[k..k + buffer.len() - i].copy_from_slice(&buffer[i..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{check_sort, inputs};
+
+    #[test]
+    fn introsort_falls_back_to_heap_sort() {
+        for depth in 0..3 {
+            check_sort("introsort", &[17, 100, 1000], |arr| introsort_loop(arr, depth));
+        }
+    }
+
+    #[test]
+    fn median_of_3_partition_splits() {
+        for size in [3, 4, 17, 100] {
+            for (spec, mut arr) in inputs(size) {
+                let j = partition_median_of_3(&mut arr);
+                assert!(j < size - 1, "{spec}: nothing on the right of {j}");
+                let split = arr[..=j].iter().max().unwrap();
+                assert!(arr[j + 1..].iter().all(|v| v >= split), "{spec} of {size} split at {j}");
+            }
+        }
+    }
+
+    #[test]
+    fn heap_sort_by_orders_by_the_comparator() {
+        for (spec, mut arr) in inputs(200) {
+            heap_sort_by(&mut arr, &mut |a: &i32, b: &i32| b.cmp(a));
+            assert!(arr.windows(2).all(|w| w[0] >= w[1]), "{spec}");
+        }
+    }
+}
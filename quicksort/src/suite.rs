@@ -0,0 +1,294 @@
+//! The `sort` benchmark: every algorithm of [`crate::sorts`], the
+//! quicksort of this crate and the two slice sorts of the standard library
+//! on the same input, each with a C counterpart.
+
+use std::fmt;
+use std::process::exit;
+use std::str::FromStr;
+
+use harness::distribution::Distribution;
+use harness::{Args, Benchmark, Language};
+
+use crate::ffi::{self, CSort};
+use crate::quick_sort;
+use crate::sorts::{self, COUNTING_RANGE_LIMIT};
+
+/// `--algo`: which sort to run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    Insertion,
+    MergeTopDown,
+    MergeBottomUp,
+    Heap,
+    Introsort,
+    Shell,
+    RadixLsd,
+    RadixMsd,
+    Counting,
+    NaturalMerge,
+    /// [`quick_sort`], against the C `quickSort`.
+    #[default]
+    Quicksort,
+    /// `slice::sort`, against libc `qsort`.
+    StdStable,
+    /// `slice::sort_unstable`, against libc `qsort`.
+    StdUnstable,
+}
+
+const NAMES: [(&str, Algorithm); 13] = [
+    ("insertion", Algorithm::Insertion),
+    ("merge-top-down", Algorithm::MergeTopDown),
+    ("merge-bottom-up", Algorithm::MergeBottomUp),
+    ("heap", Algorithm::Heap),
+    ("introsort", Algorithm::Introsort),
+    ("shell", Algorithm::Shell),
+    ("radix-lsd", Algorithm::RadixLsd),
+    ("radix-msd", Algorithm::RadixMsd),
+    ("counting", Algorithm::Counting),
+    ("natural-merge", Algorithm::NaturalMerge),
+    ("quicksort", Algorithm::Quicksort),
+    ("std-stable", Algorithm::StdStable),
+    ("std-unstable", Algorithm::StdUnstable),
+];
+
+impl Algorithm {
+    fn rust(self) -> fn(&mut [i32]) {
+        match self {
+            Algorithm::Insertion => sorts::insertion_sort,
+            Algorithm::MergeTopDown => sorts::merge_sort_top_down,
+            Algorithm::MergeBottomUp => sorts::merge_sort_bottom_up,
+            Algorithm::Heap => sorts::heap_sort,
+            Algorithm::Introsort => sorts::introsort,
+            Algorithm::Shell => sorts::shell_sort,
+            Algorithm::RadixLsd => sorts::radix_sort_lsd,
+            Algorithm::RadixMsd => sorts::radix_sort_msd,
+            Algorithm::Counting => sorts::counting_sort,
+            Algorithm::NaturalMerge => sorts::natural_merge_sort,
+            Algorithm::Quicksort => |arr| {
+                if !arr.is_empty() {
+                    quick_sort(arr, 0, arr.len() - 1);
+                }
+            },
+            Algorithm::StdStable => <[i32]>::sort,
+            Algorithm::StdUnstable => <[i32]>::sort_unstable,
+        }
+    }
+
+    /// The C counterpart, except for quicksort, whose C version also
+    /// counts its calls.
+    fn c(self) -> Option<CSort> {
+        match self {
+            Algorithm::Insertion => Some(ffi::INSERTION_SORT),
+            Algorithm::MergeTopDown => Some(ffi::MERGE_SORT_TOP_DOWN),
+            Algorithm::MergeBottomUp => Some(ffi::MERGE_SORT_BOTTOM_UP),
+            Algorithm::Heap => Some(ffi::HEAP_SORT),
+            Algorithm::Introsort => Some(ffi::INTROSORT),
+            Algorithm::Shell => Some(ffi::SHELL_SORT),
+            Algorithm::RadixLsd => Some(ffi::RADIX_SORT_LSD),
+            Algorithm::RadixMsd => Some(ffi::RADIX_SORT_MSD),
+            Algorithm::Counting => Some(ffi::COUNTING_SORT),
+            Algorithm::NaturalMerge => Some(ffi::NATURAL_MERGE_SORT),
+            Algorithm::Quicksort => None,
+            Algorithm::StdStable | Algorithm::StdUnstable => Some(ffi::LIBC_QSORT),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, _) = NAMES.iter().find(|(_, algorithm)| algorithm == self).unwrap();
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match NAMES.iter().find(|(name, _)| *name == s) {
+            Some(&(_, algorithm)) => Ok(algorithm),
+            None => {
+                let names: Vec<&str> = NAMES.iter().map(|(name, _)| *name).collect();
+                Err(format!("unknown algorithm: {s} (expected one of {})", names.join(", ")))
+            }
+        }
+    }
+}
+
+/// The array to sort, a copy of it for resetting, and the same values
+/// sorted by `slice::sort_unstable` to check every run against.
+pub struct SuiteInput {
+    pub original: Vec<i32>,
+    pub arr: Vec<i32>,
+    pub expected: Vec<i32>,
+}
+
+#[derive(Default)]
+pub struct Sort {
+    algorithm: Algorithm,
+    distribution: Distribution,
+    language: Language,
+    seed: u64,
+}
+
+impl Benchmark for Sort {
+    type Input = SuiteInput;
+    type Output = ();
+
+    fn name(&self) -> &str {
+        "sort"
+    }
+
+    fn variant(&self) -> String {
+        format!("{}/{}", self.algorithm, self.distribution)
+    }
+
+    fn size_name(&self) -> &str {
+        "array_size"
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn input_arrays(&self, input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        Some(vec![input.original.clone()])
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Every algorithm has a C counterpart; both slice sorts share libc
+    /// `qsort`.
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--algo insertion|merge-top-down|merge-bottom-up|heap|introsort|shell|radix-lsd|radix-msd|counting|\
+         natural-merge|quicksort|std-stable|std-unstable] [--dist NAME[:param=value,...]]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.algorithm = args.parse("--algo")?.unwrap_or_default();
+        self.distribution = args.parse("--dist")?.unwrap_or_default();
+        Ok(())
+    }
+
+    /// Exits with an error if counting sort would need too many counters.
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let arr = self.distribution.generate(size, self.seed);
+        let mut expected = arr.clone();
+        expected.sort_unstable();
+        if let (Algorithm::Counting, Some(min), Some(max)) = (self.algorithm, expected.first(), expected.last()) {
+            let range = (*max as i64 - *min as i64 + 1) as u64;
+            if range > COUNTING_RANGE_LIMIT {
+                eprintln!(
+                    "Error: counting sort would need {} counters, try --dist uniform:max=10000",
+                    range
+                );
+                exit(1);
+            }
+        }
+        SuiteInput {
+            original: arr.clone(),
+            arr,
+            expected,
+        }
+    }
+
+    fn run(&mut self, input: &mut Self::Input) {
+        match (self.language, self.algorithm.c()) {
+            (Language::Rust, _) => self.algorithm.rust()(&mut input.arr),
+            (Language::C, Some(sort)) => ffi::sort(sort, &mut input.arr),
+            (Language::C, None) => {
+                ffi::quick_sort(&mut input.arr);
+            }
+        }
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.arr.copy_from_slice(&input.original);
+    }
+
+    fn verify(&self, input: &Self::Input, _output: &()) -> Result<(), String> {
+        match input.arr.iter().zip(&input.expected).position(|(a, b)| a != b) {
+            Some(i) => Err(format!(
+                "index {} holds {}, expected {}",
+                i, input.arr[i], input.expected[i]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to sort the array of size {} with {}", size, self.algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::check_sort;
+
+    /// Around the insertion-sort cutoff of 16, the minimum run of 32 and
+    /// the Shell gap of 701.
+    const LENGTHS: [usize; 15] = [0, 1, 2, 3, 15, 16, 17, 31, 32, 33, 64, 65, 300, 702, 2000];
+
+    #[test]
+    fn every_algorithm_sorts_in_both_languages() {
+        for &(name, algorithm) in &NAMES {
+            let rust = algorithm.rust();
+            check_sort(name, &LENGTHS, |arr| {
+                // Counting sort only takes a range it can count.
+                if algorithm != Algorithm::Counting || in_counting_range(arr) {
+                    rust(arr)
+                } else {
+                    arr.sort_unstable()
+                }
+            });
+            check_sort(&format!("C {name}"), &LENGTHS, |arr| match algorithm.c() {
+                Some(_) if algorithm == Algorithm::Counting && !in_counting_range(arr) => arr.sort_unstable(),
+                Some(sort) => ffi::sort(sort, arr),
+                None => {
+                    ffi::quick_sort(arr);
+                }
+            });
+        }
+    }
+
+    fn in_counting_range(arr: &[i32]) -> bool {
+        match (arr.iter().min(), arr.iter().max()) {
+            (Some(&min), Some(&max)) => (max as i64 - min as i64 + 1) as u64 <= COUNTING_RANGE_LIMIT,
+            _ => true,
+        }
+    }
+
+    #[test]
+    fn counting_sort_takes_negative_values() {
+        let arr = vec![3, -7, 0, -2, -7, 5, -1000, 2, -999];
+        let mut expected = arr.clone();
+        expected.sort_unstable();
+        let mut rust = arr.clone();
+        sorts::counting_sort(&mut rust);
+        assert_eq!(rust, expected);
+        let mut c = arr;
+        ffi::sort(ffi::COUNTING_SORT, &mut c);
+        assert_eq!(c, expected);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for &(name, algorithm) in &NAMES {
+            assert_eq!(name.parse::<Algorithm>().unwrap(), algorithm);
+            assert_eq!(algorithm.to_string(), name);
+        }
+        assert!("bogo".parse::<Algorithm>().is_err());
+    }
+}
@@ -0,0 +1,666 @@
+//! The `sort_types` benchmark: the generic sorts on other element types
+//! than `i32`, against C sorts that take a qsort-style comparator.
+//!
+//! Rust compiles a copy of each sort per element type and comparator, in
+//! which comparisons inline. The C sorts in `typed_sort_algo.c` are
+//! compiled once and call the comparator through a function pointer.
+//! `--compare dyn` makes the Rust side do the same with a `dyn FnMut`.
+
+use std::cmp::Ordering;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::os::raw::c_char;
+use std::str::FromStr;
+
+use harness::distribution::Distribution;
+use harness::{Args, Benchmark, Language};
+use selection_sort::{selection_sort, selection_sort_by};
+
+use crate::ffi::{self, CCompare, CTypedSort};
+use crate::sorts::{heap_sort, heap_sort_by};
+use crate::{quick_sort, quick_sort_by};
+
+/// An `f64` ordered by [`f64::total_cmp`].
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct TotalF64(pub f64);
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// A record of `8 + 8 * WORDS` bytes, compared by its key alone.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Record<const WORDS: usize> {
+    pub key: u64,
+    pub payload: [u64; WORDS],
+}
+
+impl<const WORDS: usize> Record<WORDS> {
+    /// Keyed by `value` in the same order, with `value` as payload.
+    fn new(value: i32) -> Self {
+        Record {
+            key: key(value),
+            payload: [value as u64; WORDS],
+        }
+    }
+}
+
+pub type Record16 = Record<1>;
+pub type Record64 = Record<7>;
+pub type Record256 = Record<31>;
+
+impl<const WORDS: usize> PartialEq for Record<WORDS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<const WORDS: usize> Eq for Record<WORDS> {}
+
+impl<const WORDS: usize> PartialOrd for Record<WORDS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const WORDS: usize> Ord for Record<WORDS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// `value` as a `u64` in the same order.
+fn key(value: i32) -> u64 {
+    (value as i64 - i32::MIN as i64) as u64
+}
+
+/// `value` as eight hex digits that sort like it.
+fn hex(value: i32) -> String {
+    format!("{:08x}", key(value))
+}
+
+/// An element type of the benchmark, made from the `i32`s of a
+/// [`Distribution`].
+pub trait Element: Ord + Clone {
+    /// The element as the C sorts see it.
+    type C: Copy;
+    /// Orders [`Self::C`] the way `Ord` orders `Self`.
+    const C_COMPARE: CCompare;
+
+    fn generate(values: &[i32]) -> Vec<Self>;
+
+    /// The C elements for `items`. `strings` owns whatever they point to.
+    fn to_c(items: &[Self], strings: &mut Vec<CString>) -> Vec<Self::C>;
+
+    /// Whether a C element equals a Rust one.
+    fn matches(c: &Self::C, expected: &Self) -> bool;
+}
+
+/// Types that C sorts as they are.
+macro_rules! plain_element {
+    ($t:ty, $compare:expr, |$value:ident| $make:expr) => {
+        impl Element for $t {
+            type C = $t;
+            const C_COMPARE: CCompare = $compare;
+
+            fn generate(values: &[i32]) -> Vec<$t> {
+                values.iter().map(|&$value| $make).collect()
+            }
+
+            fn to_c(items: &[$t], _strings: &mut Vec<CString>) -> Vec<$t> {
+                items.to_vec()
+            }
+
+            fn matches(c: &$t, expected: &$t) -> bool {
+                c == expected
+            }
+        }
+    };
+}
+
+plain_element!(i32, ffi::COMPARE_I32, |value| value);
+// The low byte, so most values repeat.
+plain_element!(u8, ffi::COMPARE_U8, |value| value as u8);
+plain_element!(u64, ffi::COMPARE_U64, |value| key(value));
+plain_element!(TotalF64, ffi::COMPARE_F64_TOTAL, |value| TotalF64(value as f64 / 8.0));
+plain_element!(Record16, ffi::COMPARE_KEY, |value| Record::new(value));
+plain_element!(Record64, ffi::COMPARE_KEY, |value| Record::new(value));
+plain_element!(Record256, ffi::COMPARE_KEY, |value| Record::new(value));
+
+/// C copies of the strings, as `char*`s.
+fn c_strings<'a>(items: impl Iterator<Item = &'a str>, strings: &mut Vec<CString>) -> Vec<*const c_char> {
+    strings.clear();
+    strings.extend(items.map(|s| CString::new(s).expect("strings have no NUL bytes")));
+    strings.iter().map(|s| s.as_ptr()).collect()
+}
+
+/// Whether the C string `c` holds `expected`.
+fn c_matches(c: *const c_char, expected: &str) -> bool {
+    // SAFETY: `c` points into one of the CStrings the input keeps.
+    unsafe { CStr::from_ptr(c) }.to_bytes() == expected.as_bytes()
+}
+
+impl Element for String {
+    type C = *const c_char;
+    const C_COMPARE: CCompare = ffi::COMPARE_STRING;
+
+    fn generate(values: &[i32]) -> Vec<String> {
+        values.iter().map(|&value| hex(value)).collect()
+    }
+
+    fn to_c(items: &[String], strings: &mut Vec<CString>) -> Vec<*const c_char> {
+        c_strings(items.iter().map(String::as_str), strings)
+    }
+
+    fn matches(c: &*const c_char, expected: &String) -> bool {
+        c_matches(*c, expected)
+    }
+}
+
+impl Element for &'static str {
+    type C = *const c_char;
+    const C_COMPARE: CCompare = ffi::COMPARE_STRING;
+
+    /// Slices of one string holding them all, which is leaked so the
+    /// slices can be `'static`. Setup runs once per process.
+    fn generate(values: &[i32]) -> Vec<&'static str> {
+        let all: &'static str = values.iter().map(|&value| hex(value)).collect::<String>().leak();
+        (0..values.len()).map(|i| &all[8 * i..8 * (i + 1)]).collect()
+    }
+
+    fn to_c(items: &[&'static str], strings: &mut Vec<CString>) -> Vec<*const c_char> {
+        c_strings(items.iter().copied(), strings)
+    }
+
+    fn matches(c: &*const c_char, expected: &&'static str) -> bool {
+        c_matches(*c, expected)
+    }
+}
+
+/// `--algo`: the generic sorts, with C versions over a comparator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// [`quick_sort`], against `quick_sort_typed`.
+    #[default]
+    Quicksort,
+    /// [`selection_sort`], against `selection_sort_typed`.
+    Selection,
+    /// [`heap_sort`], against `heap_sort_typed`.
+    Heap,
+    /// `slice::sort`, against libc `qsort`.
+    StdStable,
+    /// `slice::sort_unstable`, against libc `qsort`.
+    StdUnstable,
+}
+
+impl Algorithm {
+    fn c(self) -> CTypedSort {
+        match self {
+            Algorithm::Quicksort => ffi::QUICK_SORT_TYPED,
+            Algorithm::Selection => ffi::SELECTION_SORT_TYPED,
+            Algorithm::Heap => ffi::HEAP_SORT_TYPED,
+            Algorithm::StdStable | Algorithm::StdUnstable => ffi::QSORT_TYPED,
+        }
+    }
+
+    fn sort<T: Ord>(self, arr: &mut [T]) {
+        match self {
+            Algorithm::Quicksort => quick_sort(arr, 0, arr.len() - 1),
+            Algorithm::Selection => selection_sort(arr),
+            Algorithm::Heap => heap_sort(arr),
+            Algorithm::StdStable => arr.sort(),
+            Algorithm::StdUnstable => arr.sort_unstable(),
+        }
+    }
+
+    fn sort_by<T, F>(self, arr: &mut [T], compare: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering + ?Sized,
+    {
+        match self {
+            Algorithm::Quicksort => quick_sort_by(arr, 0, arr.len() - 1, compare),
+            Algorithm::Selection => selection_sort_by(arr, compare),
+            Algorithm::Heap => heap_sort_by(arr, compare),
+            Algorithm::StdStable => arr.sort_by(|a, b| compare(a, b)),
+            Algorithm::StdUnstable => arr.sort_unstable_by(|a, b| compare(a, b)),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::Quicksort => write!(f, "quicksort"),
+            Algorithm::Selection => write!(f, "selection"),
+            Algorithm::Heap => write!(f, "heap"),
+            Algorithm::StdStable => write!(f, "std-stable"),
+            Algorithm::StdUnstable => write!(f, "std-unstable"),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quicksort" => Ok(Algorithm::Quicksort),
+            "selection" => Ok(Algorithm::Selection),
+            "heap" => Ok(Algorithm::Heap),
+            "std-stable" => Ok(Algorithm::StdStable),
+            "std-unstable" => Ok(Algorithm::StdUnstable),
+            _ => Err(format!(
+                "unknown algorithm: {s} (expected quicksort, selection, heap, std-stable or std-unstable)"
+            )),
+        }
+    }
+}
+
+/// `--compare`: how the Rust sorts compare elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Comparison {
+    /// The element's `Ord`.
+    #[default]
+    Ord,
+    /// A closure calling `Ord::cmp`, which inlines just the same.
+    Closure,
+    /// The same closure as a `&mut dyn FnMut`, called through a vtable
+    /// like the C comparator.
+    Dyn,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Comparison::Ord => write!(f, "ord"),
+            Comparison::Closure => write!(f, "closure"),
+            Comparison::Dyn => write!(f, "dyn"),
+        }
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ord" => Ok(Comparison::Ord),
+            "closure" => Ok(Comparison::Closure),
+            "dyn" => Ok(Comparison::Dyn),
+            _ => Err(format!("unknown comparison: {s} (expected ord, closure or dyn)")),
+        }
+    }
+}
+
+/// The array of one element type, a copy for resetting and the elements
+/// sorted at setup, each for Rust and for C.
+pub struct TypedArray<T: Element> {
+    pub original: Vec<T>,
+    pub arr: Vec<T>,
+    pub expected: Vec<T>,
+    pub c_original: Vec<T::C>,
+    pub c_arr: Vec<T::C>,
+    /// What string elements point to on the C side.
+    _strings: Vec<CString>,
+}
+
+impl<T: Element> TypedArray<T> {
+    fn new(values: &[i32]) -> TypedArray<T> {
+        let original = T::generate(values);
+        let mut expected = original.clone();
+        expected.sort_unstable();
+        let mut strings = Vec::new();
+        let c_original = T::to_c(&original, &mut strings);
+        TypedArray {
+            arr: original.clone(),
+            c_arr: c_original.clone(),
+            original,
+            expected,
+            c_original,
+            _strings: strings,
+        }
+    }
+
+    fn run(&mut self, language: Language, algorithm: Algorithm, comparison: Comparison) {
+        let arr = &mut self.arr;
+        match (language, comparison) {
+            // SAFETY: `C_COMPARE` reads `T::C`s, and the CStrings the
+            // elements may point to live in `self._strings`.
+            (Language::C, _) => unsafe { ffi::sort_typed(algorithm.c(), &mut self.c_arr, T::C_COMPARE) },
+            (Language::Rust, Comparison::Ord) => algorithm.sort(arr),
+            (Language::Rust, Comparison::Closure) => algorithm.sort_by(arr, &mut |a: &T, b: &T| a.cmp(b)),
+            (Language::Rust, Comparison::Dyn) => {
+                let compare: &mut dyn FnMut(&T, &T) -> Ordering = &mut |a, b| a.cmp(b);
+                algorithm.sort_by(arr, compare)
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.arr.clone_from_slice(&self.original);
+        self.c_arr.copy_from_slice(&self.c_original);
+    }
+
+    fn verify(&self, language: Language) -> Result<(), String> {
+        let wrong = match language {
+            Language::Rust => self.arr.iter().zip(&self.expected).position(|(a, b)| a != b),
+            Language::C => self
+                .c_arr
+                .iter()
+                .zip(&self.expected)
+                .position(|(c, b)| !T::matches(c, b)),
+        };
+        match wrong {
+            Some(i) => Err(format!("index {} is out of order", i)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Evaluates `$body` with `$a` bound to the [`TypedArray`] inside an
+/// [`AnyArray`].
+macro_rules! with_array {
+    ($array:expr, $a:ident => $body:expr) => {
+        match $array {
+            AnyArray::I32($a) => $body,
+            AnyArray::U8($a) => $body,
+            AnyArray::U64($a) => $body,
+            AnyArray::F64($a) => $body,
+            AnyArray::Record16($a) => $body,
+            AnyArray::Record64($a) => $body,
+            AnyArray::Record256($a) => $body,
+            AnyArray::String($a) => $body,
+            AnyArray::Str($a) => $body,
+        }
+    };
+}
+
+/// A [`TypedArray`] of the type picked with `--type`.
+pub enum AnyArray {
+    I32(TypedArray<i32>),
+    U8(TypedArray<u8>),
+    U64(TypedArray<u64>),
+    F64(TypedArray<TotalF64>),
+    Record16(TypedArray<Record16>),
+    Record64(TypedArray<Record64>),
+    Record256(TypedArray<Record256>),
+    String(TypedArray<String>),
+    Str(TypedArray<&'static str>),
+}
+
+/// `--type`: the element type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ElementType {
+    #[default]
+    I32,
+    U8,
+    U64,
+    /// `f64` in total order.
+    F64,
+    Record16,
+    Record64,
+    Record256,
+    /// Eight hex digits, owned.
+    String,
+    /// Eight hex digits, borrowed from one big string.
+    Str,
+}
+
+const TYPE_NAMES: [(&str, ElementType); 9] = [
+    ("i32", ElementType::I32),
+    ("u8", ElementType::U8),
+    ("u64", ElementType::U64),
+    ("f64", ElementType::F64),
+    ("record16", ElementType::Record16),
+    ("record64", ElementType::Record64),
+    ("record256", ElementType::Record256),
+    ("string", ElementType::String),
+    ("str", ElementType::Str),
+];
+
+impl ElementType {
+    fn array(self, values: &[i32]) -> AnyArray {
+        match self {
+            ElementType::I32 => AnyArray::I32(TypedArray::new(values)),
+            ElementType::U8 => AnyArray::U8(TypedArray::new(values)),
+            ElementType::U64 => AnyArray::U64(TypedArray::new(values)),
+            ElementType::F64 => AnyArray::F64(TypedArray::new(values)),
+            ElementType::Record16 => AnyArray::Record16(TypedArray::new(values)),
+            ElementType::Record64 => AnyArray::Record64(TypedArray::new(values)),
+            ElementType::Record256 => AnyArray::Record256(TypedArray::new(values)),
+            ElementType::String => AnyArray::String(TypedArray::new(values)),
+            ElementType::Str => AnyArray::Str(TypedArray::new(values)),
+        }
+    }
+}
+
+impl fmt::Display for ElementType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, _) = TYPE_NAMES.iter().find(|(_, t)| t == self).unwrap();
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ElementType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match TYPE_NAMES.iter().find(|(name, _)| *name == s) {
+            Some(&(_, t)) => Ok(t),
+            None => {
+                let names: Vec<&str> = TYPE_NAMES.iter().map(|(name, _)| *name).collect();
+                Err(format!("unknown type: {s} (expected one of {})", names.join(", ")))
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TypedSort {
+    algorithm: Algorithm,
+    element: ElementType,
+    comparison: Comparison,
+    distribution: Distribution,
+    language: Language,
+    seed: u64,
+}
+
+impl Benchmark for TypedSort {
+    type Input = AnyArray;
+    type Output = ();
+
+    fn name(&self) -> &str {
+        "sort_types"
+    }
+
+    /// The comparison only matters for Rust, but is recorded for both.
+    fn variant(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.algorithm, self.element, self.comparison, self.distribution
+        )
+    }
+
+    fn size_name(&self) -> &str {
+        "array_size"
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--algo quicksort|selection|heap|std-stable|std-unstable] \
+         [--type i32|u8|u64|f64|record16|record64|record256|string|str] [--compare ord|closure|dyn] \
+         [--dist NAME[:param=value,...]]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.algorithm = args.parse("--algo")?.unwrap_or_default();
+        self.element = args.parse("--type")?.unwrap_or_default();
+        self.comparison = args.parse("--compare")?.unwrap_or_default();
+        self.distribution = args.parse("--dist")?.unwrap_or_default();
+        Ok(())
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        self.element.array(&self.distribution.generate(size, self.seed))
+    }
+
+    fn run(&mut self, input: &mut Self::Input) {
+        with_array!(input, a => a.run(self.language, self.algorithm, self.comparison))
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        with_array!(input, a => a.reset())
+    }
+
+    fn verify(&self, input: &Self::Input, _output: &()) -> Result<(), String> {
+        with_array!(input, a => a.verify(self.language))
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!(
+            "to sort the {} array of size {} with {}",
+            self.element, size, self.algorithm
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::inputs;
+
+    const ALGORITHMS: [Algorithm; 5] = [
+        Algorithm::Quicksort,
+        Algorithm::Selection,
+        Algorithm::Heap,
+        Algorithm::StdStable,
+        Algorithm::StdUnstable,
+    ];
+    const COMPARISONS: [Comparison; 3] = [Comparison::Ord, Comparison::Closure, Comparison::Dyn];
+
+    #[test]
+    fn every_type_sorts_with_every_algorithm_and_comparison() {
+        for size in [1, 2, 3, 16, 17, 300] {
+            for (spec, values) in inputs(size) {
+                for (name, element) in TYPE_NAMES {
+                    let mut array = element.array(&values);
+                    for algorithm in ALGORITHMS {
+                        let runs = COMPARISONS.map(|comparison| (Language::Rust, comparison));
+                        for (language, comparison) in runs.into_iter().chain([(Language::C, Comparison::Ord)]) {
+                            with_array!(&mut array, a => {
+                                a.reset();
+                                a.run(language, algorithm, comparison);
+                                if let Err(err) = a.verify(language) {
+                                    panic!("{language:?} {algorithm}/{comparison} on {size} {spec} {name}: {err}");
+                                }
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn c_orders_floats_like_total_cmp() {
+        let specials = [
+            f64::NAN,
+            -f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            0.0,
+            -0.0,
+            f64::MIN_POSITIVE,
+            -f64::MIN_POSITIVE,
+            5e-324,
+            -5e-324,
+            f64::MAX,
+            f64::MIN,
+            1.5,
+            -1.5,
+        ];
+        let mut expected: Vec<TotalF64> = specials.iter().map(|&x| TotalF64(x)).collect();
+        expected.sort_unstable();
+        for algorithm in ALGORITHMS {
+            let mut arr: Vec<TotalF64> = specials.iter().rev().map(|&x| TotalF64(x)).collect();
+            // SAFETY: `COMPARE_F64_TOTAL` reads `f64`s, which `TotalF64` wraps.
+            unsafe { ffi::sort_typed(algorithm.c(), &mut arr, ffi::COMPARE_F64_TOTAL) };
+            let bits = |arr: &[TotalF64]| arr.iter().map(|x| x.0.to_bits()).collect::<Vec<_>>();
+            assert_eq!(bits(&arr), bits(&expected), "{algorithm}");
+        }
+    }
+
+    #[test]
+    fn c_moves_whole_records() {
+        let values = &inputs(300)[0].1;
+        for algorithm in ALGORITHMS {
+            let mut array = TypedArray::<Record256>::new(values);
+            array.run(Language::C, algorithm, Comparison::Ord);
+            array.verify(Language::C).unwrap();
+            for record in &array.c_arr {
+                let value = (record.key as i64 + i32::MIN as i64) as i32;
+                assert_eq!(record.payload, [value as u64; 31], "{algorithm}");
+            }
+        }
+    }
+
+    #[test]
+    fn strings_sort_like_their_values() {
+        let mut values = [i32::MIN, -1, 0, 1, 255, 256, i32::MAX];
+        let mut strings: Vec<String> = values.iter().rev().map(|&value| hex(value)).collect();
+        strings.sort_unstable();
+        values.sort_unstable();
+        assert_eq!(strings, values.iter().map(|&value| hex(value)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for algorithm in ALGORITHMS {
+            assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+        }
+        for comparison in COMPARISONS {
+            assert_eq!(comparison.to_string().parse(), Ok(comparison));
+        }
+        for (name, element) in TYPE_NAMES {
+            assert_eq!(element.to_string(), name);
+            assert_eq!(name.parse(), Ok(element));
+        }
+        assert!("f32".parse::<ElementType>().is_err());
+        assert!("merge".parse::<Algorithm>().is_err());
+        assert!("vtable".parse::<Comparison>().is_err());
+    }
+}
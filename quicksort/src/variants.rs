@@ -0,0 +1,466 @@
+//! Quicksort with a choice of pivot, partition scheme and insertion-sort
+//! cutoff, to see which of these choices matter. The C twin is
+//! `quick_sort_variant` in `quicksort_variants_algo.c`.
+//!
+//! Like [`quick_sort`](crate::quick_sort), it recurses into the smaller
+//! side only and loops on the larger one, so inputs that make it quadratic
+//! take long but do not overflow the stack.
+
+use std::fmt;
+use std::str::FromStr;
+
+use harness::input::Rng;
+
+use crate::sorts::insertion_sort;
+
+/// Length of the blocks whose misplaced elements the block partition
+/// collects before swapping them.
+const BLOCK: usize = 64;
+
+/// Below this length the ninther falls back to the median of 3.
+const NINTHER_THRESHOLD: usize = 40;
+
+/// `--pivot`: how the pivot is chosen. The discriminants are the ones
+/// the C code uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Pivot {
+    /// The last element, as in [`quick_sort`](crate::quick_sort).
+    #[default]
+    Last = 0,
+    /// A uniformly random element.
+    Random = 1,
+    /// The median of the first, middle and last element.
+    MedianOf3 = 2,
+    /// Tukey's ninther: the median of three medians of 3, spread over the
+    /// slice.
+    Ninther = 3,
+}
+
+/// `--partition`: how the slice is split around the pivot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Partition {
+    /// Lomuto's single scan, as in [`quick_sort`](crate::quick_sort).
+    #[default]
+    Lomuto = 0,
+    /// Hoare's two scans from both ends.
+    Hoare = 1,
+    /// Dijkstra's Dutch national flag, which sets the elements equal to
+    /// the pivot aside.
+    ThreeWay = 2,
+    /// Branchless block partitioning as in BlockQuicksort and pdqsort:
+    /// the positions of misplaced elements are collected from a block at
+    /// each end without branches, then swapped in pairs.
+    Block = 3,
+}
+
+/// A quicksort variant, shown as `pivot=P,partition=S,cutoff=N`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Variant {
+    pub pivot: Pivot,
+    pub partition: Partition,
+    /// Slices of at most this length are left to insertion sort.
+    pub cutoff: usize,
+}
+
+impl fmt::Display for Pivot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pivot::Last => write!(f, "last"),
+            Pivot::Random => write!(f, "random"),
+            Pivot::MedianOf3 => write!(f, "median3"),
+            Pivot::Ninther => write!(f, "ninther"),
+        }
+    }
+}
+
+impl FromStr for Pivot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "last" => Ok(Pivot::Last),
+            "random" => Ok(Pivot::Random),
+            "median3" => Ok(Pivot::MedianOf3),
+            "ninther" => Ok(Pivot::Ninther),
+            _ => Err(format!(
+                "unknown pivot: {s} (expected last, random, median3 or ninther)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Partition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Partition::Lomuto => write!(f, "lomuto"),
+            Partition::Hoare => write!(f, "hoare"),
+            Partition::ThreeWay => write!(f, "three-way"),
+            Partition::Block => write!(f, "block"),
+        }
+    }
+}
+
+impl FromStr for Partition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lomuto" => Ok(Partition::Lomuto),
+            "hoare" => Ok(Partition::Hoare),
+            "three-way" => Ok(Partition::ThreeWay),
+            "block" => Ok(Partition::Block),
+            _ => Err(format!(
+                "unknown partition: {s} (expected lomuto, hoare, three-way or block)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "pivot={},partition={},cutoff={}",
+            self.pivot, self.partition, self.cutoff
+        )
+    }
+}
+
+/// Sorts `arr` with `variant`, drawing random pivots from a generator
+/// seeded with `seed`. Returns the number of partitions.
+pub fn quick_sort_variant(arr: &mut [i32], variant: &Variant, seed: u64) -> u64 {
+    let mut rng = Rng::new(seed);
+    let mut partitions = 0;
+    sort(arr, variant, &mut rng, &mut partitions);
+    partitions
+}
+
+fn sort(mut arr: &mut [i32], variant: &Variant, rng: &mut Rng, partitions: &mut u64) {
+    while arr.len() > variant.cutoff.max(1) {
+        *partitions += 1;
+        let n = arr.len();
+        let pivot = choose_pivot(arr, variant.pivot, rng);
+        // The slice splits into arr[..left_end] and arr[right_start..].
+        let (left_end, right_start) = match variant.partition {
+            Partition::Lomuto => {
+                arr.swap(pivot, n - 1);
+                let q = partition_lomuto(arr);
+                (q, q + 1)
+            }
+            Partition::Hoare => {
+                arr.swap(pivot, 0);
+                let j = partition_hoare(arr);
+                (j + 1, j + 1)
+            }
+            Partition::ThreeWay => partition_three_way(arr, arr[pivot]),
+            Partition::Block => {
+                arr.swap(pivot, 0);
+                let q = partition_block(arr);
+                (q, q + 1)
+            }
+        };
+        let (left, rest) = std::mem::take(&mut arr).split_at_mut(left_end);
+        let right = &mut rest[right_start - left_end..];
+        if left.len() < right.len() {
+            sort(left, variant, rng, partitions);
+            arr = right;
+        } else {
+            sort(right, variant, rng, partitions);
+            arr = left;
+        }
+    }
+    insertion_sort(arr);
+}
+
+/// Index of the median of `arr[a]`, `arr[b]` and `arr[c]`.
+#[inline]
+fn median_of_3(arr: &[i32], a: usize, b: usize, c: usize) -> usize {
+    if arr[a] < arr[b] {
+        if arr[b] < arr[c] {
+            b
+        } else if arr[a] < arr[c] {
+            c
+        } else {
+            a
+        }
+    } else if arr[a] < arr[c] {
+        a
+    } else if arr[b] < arr[c] {
+        c
+    } else {
+        b
+    }
+}
+
+fn choose_pivot(arr: &[i32], pivot: Pivot, rng: &mut Rng) -> usize {
+    let n = arr.len();
+    match pivot {
+        Pivot::Last => n - 1,
+        Pivot::Random => rng.below(n as u64) as usize,
+        Pivot::Ninther if n >= NINTHER_THRESHOLD => {
+            let (step, mid) = (n / 8, n / 2);
+            let a = median_of_3(arr, 0, step, 2 * step);
+            let b = median_of_3(arr, mid - step, mid, mid + step);
+            let c = median_of_3(arr, n - 1 - 2 * step, n - 1 - step, n - 1);
+            median_of_3(arr, a, b, c)
+        }
+        Pivot::MedianOf3 | Pivot::Ninther => median_of_3(arr, 0, n / 2, n - 1),
+    }
+}
+
+/// Lomuto partition around the last element. Returns where the pivot
+/// ends up.
+fn partition_lomuto(arr: &mut [i32]) -> usize {
+    let high = arr.len() - 1;
+    let pivot = arr[high];
+    let mut i = 0;
+    for j in 0..high {
+        if arr[j] <= pivot {
+            arr.swap(i, j);
+            i += 1;
+        }
+    }
+    arr.swap(i, high);
+    i
+}
+
+/// Hoare partition around the first element. Returns `j < len - 1` such
+/// that `arr[..=j]` holds no element above the pivot and `arr[j + 1..]`
+/// none below it.
+fn partition_hoare(arr: &mut [i32]) -> usize {
+    let pivot = arr[0];
+    let (mut i, mut j) = (0, arr.len() - 1);
+    loop {
+        while arr[i] < pivot {
+            i += 1;
+        }
+        while arr[j] > pivot {
+            j -= 1;
+        }
+        if i >= j {
+            return j;
+        }
+        arr.swap(i, j);
+        i += 1;
+        j -= 1;
+    }
+}
+
+/// Three-way partition around `pivot`. Returns `(lt, gt)` such that the
+/// elements below the pivot are `arr[..lt]`, those equal to it
+/// `arr[lt..gt]` and those above it `arr[gt..]`.
+fn partition_three_way(arr: &mut [i32], pivot: i32) -> (usize, usize) {
+    let (mut lt, mut i, mut gt) = (0, 0, arr.len());
+    while i < gt {
+        if arr[i] < pivot {
+            arr.swap(lt, i);
+            lt += 1;
+            i += 1;
+        } else if arr[i] > pivot {
+            gt -= 1;
+            arr.swap(i, gt);
+        } else {
+            i += 1;
+        }
+    }
+    (lt, gt)
+}
+
+/// Block partition around the first element. Returns where the pivot
+/// ends up, with the smaller elements before it and the others after.
+fn partition_block(arr: &mut [i32]) -> usize {
+    let pivot = arr[0];
+    // arr[1..l] is below the pivot and arr[r..] is not; arr[l..r] is left.
+    let (mut l, mut r) = (1, arr.len());
+    let mut offsets_l = [0u8; BLOCK];
+    let mut offsets_r = [0u8; BLOCK];
+    let (mut start_l, mut num_l, mut start_r, mut num_r) = (0, 0, 0, 0);
+
+    while r - l > 2 * BLOCK {
+        // Record every offset but only advance past the misplaced ones.
+        if num_l == 0 {
+            start_l = 0;
+            for i in 0..BLOCK {
+                offsets_l[num_l] = i as u8;
+                num_l += (arr[l + i] >= pivot) as usize;
+            }
+        }
+        if num_r == 0 {
+            start_r = 0;
+            for i in 0..BLOCK {
+                offsets_r[num_r] = i as u8;
+                num_r += (arr[r - 1 - i] < pivot) as usize;
+            }
+        }
+        let num = num_l.min(num_r);
+        for k in 0..num {
+            arr.swap(
+                l + offsets_l[start_l + k] as usize,
+                r - 1 - offsets_r[start_r + k] as usize,
+            );
+        }
+        (num_l, num_r) = (num_l - num, num_r - num);
+        (start_l, start_r) = (start_l + num, start_r + num);
+        if num_l == 0 {
+            l += BLOCK;
+        }
+        if num_r == 0 {
+            r -= BLOCK;
+        }
+    }
+
+    // A block with misplaced elements left still lies within arr[l..r],
+    // so the rest is partitioned element by element.
+    while l < r {
+        if arr[l] < pivot {
+            l += 1;
+        } else {
+            r -= 1;
+            arr.swap(l, r);
+        }
+    }
+    arr.swap(0, l - 1);
+    l - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi;
+    use crate::tests::inputs;
+
+    const PIVOTS: [Pivot; 4] = [Pivot::Last, Pivot::Random, Pivot::MedianOf3, Pivot::Ninther];
+    const PARTITIONS: [Partition; 4] = [Partition::Lomuto, Partition::Hoare, Partition::ThreeWay, Partition::Block];
+
+    /// Lengths around the ninther threshold and around one and two blocks
+    /// at each end of the block partition.
+    const LENGTHS: [usize; 18] = [0, 1, 2, 3, 16, 17, 39, 40, 41, 63, 64, 65, 128, 129, 130, 131, 200, 1000];
+
+    /// Every input of the lengths in [`LENGTHS`] at or above `min`.
+    fn cases(min: usize) -> impl Iterator<Item = (usize, &'static str, Vec<i32>)> {
+        LENGTHS
+            .into_iter()
+            .filter(move |&size| size >= min)
+            .flat_map(|size| inputs(size).into_iter().map(move |(spec, input)| (size, spec, input)))
+    }
+
+    fn sorted(arr: &[i32]) -> Vec<i32> {
+        let mut arr = arr.to_vec();
+        arr.sort_unstable();
+        arr
+    }
+
+    #[test]
+    fn every_variant_sorts_in_both_languages() {
+        for (size, spec, input) in cases(0) {
+            let expected = sorted(&input);
+            for pivot in PIVOTS {
+                for partition in PARTITIONS {
+                    for cutoff in [0, 1, 16] {
+                        let variant = Variant { pivot, partition, cutoff };
+                        let mut arr = input.clone();
+                        let partitions = quick_sort_variant(&mut arr, &variant, 7);
+                        assert!(arr == expected, "{variant} missorts {size} {spec} values");
+                        let mut c_arr = input.clone();
+                        let c_partitions = ffi::quick_sort_variant(&mut c_arr, &variant, 7);
+                        assert!(c_arr == expected, "C {variant} missorts {size} {spec} values");
+                        assert_eq!(partitions, c_partitions, "{variant} on {size} {spec} values");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lomuto_splits_around_the_last_element() {
+        for (size, spec, input) in cases(1) {
+            let mut arr = input.clone();
+            let pivot = arr[size - 1];
+            let q = partition_lomuto(&mut arr);
+            assert_eq!(arr[q], pivot, "{size} {spec}");
+            assert!(arr[..q].iter().all(|&x| x <= pivot), "{size} {spec}");
+            assert!(arr[q + 1..].iter().all(|&x| x > pivot), "{size} {spec}");
+            assert_eq!(sorted(&arr), sorted(&input), "{size} {spec}");
+        }
+    }
+
+    #[test]
+    fn hoare_splits_around_the_first_element() {
+        for (size, spec, input) in cases(2) {
+            let mut arr = input.clone();
+            let pivot = arr[0];
+            let j = partition_hoare(&mut arr);
+            assert!(j < size - 1, "{size} {spec}: split at {j}");
+            assert!(arr[..=j].iter().all(|&x| x <= pivot), "{size} {spec}");
+            assert!(arr[j + 1..].iter().all(|&x| x >= pivot), "{size} {spec}");
+            assert_eq!(sorted(&arr), sorted(&input), "{size} {spec}");
+        }
+    }
+
+    #[test]
+    fn three_way_sets_the_pivot_aside() {
+        for (size, spec, input) in cases(1) {
+            let mut arr = input.clone();
+            let pivot = arr[size / 2];
+            let (lt, gt) = partition_three_way(&mut arr, pivot);
+            assert!(lt < gt, "{size} {spec}: the pivot is missing");
+            assert!(arr[..lt].iter().all(|&x| x < pivot), "{size} {spec}");
+            assert!(arr[lt..gt].iter().all(|&x| x == pivot), "{size} {spec}");
+            assert!(arr[gt..].iter().all(|&x| x > pivot), "{size} {spec}");
+            assert_eq!(sorted(&arr), sorted(&input), "{size} {spec}");
+        }
+    }
+
+    #[test]
+    fn block_splits_around_the_first_element() {
+        // Every length up to past three blocks, so the block loop stops
+        // with either side's block half done.
+        let lengths = (1..=3 * BLOCK + 3).chain(LENGTHS.into_iter().filter(|&size| size > 3 * BLOCK + 3));
+        for size in lengths {
+            for (spec, input) in inputs(size) {
+                let mut arr = input.clone();
+                let pivot = arr[0];
+                let q = partition_block(&mut arr);
+                assert_eq!(arr[q], pivot, "{size} {spec}");
+                assert!(arr[..q].iter().all(|&x| x < pivot), "{size} {spec}");
+                assert!(arr[q + 1..].iter().all(|&x| x >= pivot), "{size} {spec}");
+                assert_eq!(sorted(&arr), sorted(&input), "{size} {spec}");
+            }
+        }
+    }
+
+    #[test]
+    fn ninther_needs_the_threshold() {
+        let mut rng = Rng::new(1);
+        for (size, spec, arr) in cases(1) {
+            let median = choose_pivot(&arr, Pivot::MedianOf3, &mut rng);
+            let ninther = choose_pivot(&arr, Pivot::Ninther, &mut rng);
+            if size < NINTHER_THRESHOLD {
+                assert_eq!(ninther, median, "{size} {spec}");
+            } else {
+                // The median of the medians of 3 of each third.
+                let (step, mid) = (size / 8, size / 2);
+                let medians = [
+                    arr[median_of_3(&arr, 0, step, 2 * step)],
+                    arr[median_of_3(&arr, mid - step, mid, mid + step)],
+                    arr[median_of_3(&arr, size - 1 - 2 * step, size - 1 - step, size - 1)],
+                ];
+                assert_eq!(arr[ninther], sorted(&medians)[1], "{size} {spec}");
+            }
+        }
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for pivot in PIVOTS {
+            assert_eq!(pivot.to_string().parse(), Ok(pivot));
+        }
+        for partition in PARTITIONS {
+            assert_eq!(partition.to_string().parse(), Ok(partition));
+        }
+        assert!("median5".parse::<Pivot>().is_err());
+        assert!("two-way".parse::<Partition>().is_err());
+        let variant = Variant { pivot: Pivot::Ninther, partition: Partition::Block, cutoff: 16 };
+        assert_eq!(variant.to_string(), "pivot=ninther,partition=block,cutoff=16");
+    }
+}
@@ -0,0 +1,138 @@
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+#include "typed_sort_algo.h"
+
+#define AT(i) ((char*)base + (size_t)(i) * size)
+
+// Elements are swapped through a stack buffer of this many bytes at a time
+#define SWAP_CHUNK 256
+
+// Swaps with memcpy rather than byte by byte, as glibc's qsort does, so
+// large records cost about what Rust's slice::swap does
+static void swapBytes(char* a, char* b, size_t size) {
+    if (a == b) {
+        return;
+    }
+    char tmp[SWAP_CHUNK];
+    while (size > 0) {
+        size_t n = size < SWAP_CHUNK ? size : SWAP_CHUNK;
+        memcpy(tmp, a, n);
+        memcpy(a, b, n);
+        memcpy(b, tmp, n);
+        a += n;
+        b += n;
+        size -= n;
+    }
+}
+
+static int partitionTyped(void* base, int low, int high, size_t size, Compare compare) {
+    int i = low;
+    for (int j = low; j < high; j++) {
+        if (compare(AT(j), AT(high)) <= 0) {
+            swapBytes(AT(i), AT(j), size);
+            i++;
+        }
+    }
+    swapBytes(AT(i), AT(high), size);
+    return i;
+}
+
+static void quickSortTyped(void* base, int low, int high, size_t size, Compare compare) {
+    // Smaller side first, larger one in the loop, like quickSort
+    while (low < high) {
+        int pi = partitionTyped(base, low, high, size, compare);
+        if (pi - low < high - pi) {
+            quickSortTyped(base, low, pi - 1, size, compare);
+            low = pi + 1;
+        } else {
+            quickSortTyped(base, pi + 1, high, size, compare);
+            high = pi - 1;
+        }
+    }
+}
+
+void quick_sort_typed(void* base, int n, size_t size, Compare compare) {
+    quickSortTyped(base, 0, n - 1, size, compare);
+}
+
+void selection_sort_typed(void* base, int n, size_t size, Compare compare) {
+    for (int i = 0; i < n - 1; i++) {
+        int minIdx = i;
+        for (int j = i + 1; j < n; j++) {
+            if (compare(AT(j), AT(minIdx)) < 0) {
+                minIdx = j;
+            }
+        }
+        swapBytes(AT(i), AT(minIdx), size);
+    }
+}
+
+static void siftDownTyped(void* base, int n, int root, size_t size, Compare compare) {
+    for (;;) {
+        int child = 2 * root + 1;
+        if (child >= n) {
+            break;
+        }
+        if (child + 1 < n && compare(AT(child + 1), AT(child)) > 0) {
+            child++;
+        }
+        if (compare(AT(root), AT(child)) >= 0) {
+            break;
+        }
+        swapBytes(AT(root), AT(child), size);
+        root = child;
+    }
+}
+
+void heap_sort_typed(void* base, int n, size_t size, Compare compare) {
+    for (int root = n / 2 - 1; root >= 0; root--) {
+        siftDownTyped(base, n, root, size, compare);
+    }
+    for (int end = n - 1; end > 0; end--) {
+        swapBytes(AT(0), AT(end), size);
+        siftDownTyped(base, end, 0, size, compare);
+    }
+}
+
+void qsort_typed(void* base, int n, size_t size, Compare compare) {
+    qsort(base, n, size, compare);
+}
+
+int compare_i32(const void* a, const void* b) {
+    int32_t x = *(const int32_t*)a;
+    int32_t y = *(const int32_t*)b;
+    return (x > y) - (x < y);
+}
+
+int compare_u8(const void* a, const void* b) {
+    return (int)*(const uint8_t*)a - (int)*(const uint8_t*)b;
+}
+
+int compare_u64(const void* a, const void* b) {
+    uint64_t x = *(const uint64_t*)a;
+    uint64_t y = *(const uint64_t*)b;
+    return (x > y) - (x < y);
+}
+
+// Flips all bits but the sign of negative numbers, so that the bits
+// compare as signed integers in the order of f64::total_cmp.
+static int64_t totalKey(const void* p) {
+    int64_t bits;
+    memcpy(&bits, p, sizeof(bits));
+    return bits ^ (int64_t)((uint64_t)(bits >> 63) >> 1);
+}
+
+int compare_f64_total(const void* a, const void* b) {
+    int64_t x = totalKey(a);
+    int64_t y = totalKey(b);
+    return (x > y) - (x < y);
+}
+
+int compare_key(const void* a, const void* b) {
+    return compare_u64(a, b);
+}
+
+int compare_string(const void* a, const void* b) {
+    return strcmp(*(const char* const*)a, *(const char* const*)b);
+}
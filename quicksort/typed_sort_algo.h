@@ -0,0 +1,30 @@
+#ifndef TYPED_SORT_ALGO_H
+#define TYPED_SORT_ALGO_H
+
+#include <stddef.h>
+
+// Sorts over any element type, taking the array like qsort: n elements of
+// size bytes each at base, ordered by compare. They follow quick_sort,
+// selection_sort and heap_sort on the Rust side, but every comparison is a
+// call through the function pointer and every swap a byte loop.
+
+typedef int (*Compare)(const void* a, const void* b);
+
+void quick_sort_typed(void* base, int n, size_t size, Compare compare);
+void selection_sort_typed(void* base, int n, size_t size, Compare compare);
+void heap_sort_typed(void* base, int n, size_t size, Compare compare);
+// libc qsort, with the same signature as the others.
+void qsort_typed(void* base, int n, size_t size, Compare compare);
+
+// Comparators for the element types of the typed benchmark.
+int compare_i32(const void* a, const void* b);
+int compare_u8(const void* a, const void* b);
+int compare_u64(const void* a, const void* b);
+// The total order of f64::total_cmp: -NaN < -inf < ... < -0 < +0 < ... < NaN.
+int compare_f64_total(const void* a, const void* b);
+// Records whose first member is an unsigned 64-bit key.
+int compare_key(const void* a, const void* b);
+// Elements are char pointers to NUL-terminated strings.
+int compare_string(const void* a, const void* b);
+
+#endif
@@ -1,15 +1,6 @@
-use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::process::{Command, exit};
-use std::thread::sleep;
-use std::time::{Duration, Instant};
-use nix::unistd::{fork, ForkResult, Pid};
-use nix::sys::signal::{kill, Signal};
-use nix::sys::wait::waitpid;
 use std::rc::Rc;
 use std::cell::RefCell;
-use libc::{clock_gettime, timespec, CLOCK_MONOTONIC};
+use harness::{Benchmark, Runner};
 
 struct TreeNode {
     value: i64,
@@ -79,161 +70,109 @@ impl TreeNodeRefCell {
     }
 
     fn sum(self_rc: &Rc<Self>) -> i64 {
-        let left_sum = self_rc.left.borrow().as_ref().map_or(0, |node| TreeNodeRefCell::sum(node));
-        let right_sum = self_rc.right.borrow().as_ref().map_or(0, |node| TreeNodeRefCell::sum(node));
+        let left_sum = self_rc.left.borrow().as_ref().map_or(0, TreeNodeRefCell::sum);
+        let right_sum = self_rc.right.borrow().as_ref().map_or(0, TreeNodeRefCell::sum);
         self_rc.value + left_sum + right_sum
     }
 }
 
-fn test_tree(iterations: usize) -> f64 {
-    let mut tree = TreeNode::new(0);
-    let start_time = Instant::now();
-
-    for i in 1..=iterations {
-        tree.insert(i as i64);
+fn check_sum(sum: i64, iterations: usize) -> Result<(), String> {
+    let n = iterations as i64;
+    let expected = n * (n + 1) / 2;
+    if sum != expected {
+        return Err(format!("final sum {} does not match expected {}", sum, expected));
     }
-
-    let sum = tree.sum();
-    let elapsed = start_time.elapsed().as_secs_f64();
-    println!("Tree: final sum = {}, time = {:.6} seconds", sum, elapsed);
-    elapsed
+    Ok(())
 }
 
-fn test_tree_refcell(iterations: usize) -> f64 {
-    let tree = TreeNodeRefCell::new(0);
-    let start_time = Instant::now();
+struct Tree;
 
-    for i in 1..=iterations {
-        TreeNodeRefCell::insert(&tree, i as i64);
-    }
+impl Benchmark for Tree {
+    /// Number of values to insert.
+    type Input = usize;
+    /// The tree is handed back so that dropping it is not timed.
+    type Output = (TreeNode, i64);
 
-    let sum = TreeNodeRefCell::sum(&tree);
-    let elapsed = start_time.elapsed().as_secs_f64();
-    println!("TreeRefCell: final sum = {}, time = {:.6} seconds", sum, elapsed);
-    elapsed
-}
+    fn name(&self) -> &str {
+        "MyStruct"
+    }
 
-fn run_perf_test(test_function: fn(usize) -> f64, iterations: usize, label: &str) {
-    let parent_pid = std::process::id();
+    fn size_name(&self) -> &str {
+        "iterations"
+    }
 
-    match unsafe { fork() } {
-        Ok(ForkResult::Child) => {
-            let perf_command = format!(
-                "perf stat -e cycles,instructions,cache-references,cache-misses -p {} > perf_output_normal.log 2>&1",
-                parent_pid
-            );
+    fn default_size(&self) -> Option<usize> {
+        Some(1_000_000)
+    }
 
-            Command::new("sh")
-                .arg("-c")
-                .arg(perf_command)
-                .spawn()
-                .expect("Failed to start perf stat");
+    fn setup(&mut self, iterations: usize) -> usize {
+        iterations
+    }
 
-            exit(0);
+    fn run(&mut self, iterations: &mut usize) -> Self::Output {
+        let mut tree = TreeNode::new(0);
+        for i in 1..=*iterations {
+            tree.insert(i as i64);
         }
-        Ok(ForkResult::Parent { child }) => {
-            let child_pid = child.as_raw();
-
-            sleep(Duration::from_millis(500)); 
-
-            println!("\nRunning {} test with {} iterations...\n", label, iterations);
-            let elapsed_time = test_function(iterations);
-
-            let _ = kill(Pid::from_raw(child_pid), Signal::SIGINT);
-            let _ = waitpid(Pid::from_raw(child_pid), None);
-
-            if let Ok(file) = File::open("perf_output_normal.log") {
-                let reader = BufReader::new(file);
-                println!("\n[ Perf Stat Output for {} ]", label);
-                for line in reader.lines() {
-                    if let Ok(l) = line {
-                        println!("{}", l);
-                    }
-                }
-            } else {
-                eprintln!("Failed to read perf stat log");
-            }
+        let sum = tree.sum();
+        (tree, sum)
+    }
 
-            println!(
-                "\nTime taken for {} with {} iterations: {:.6} seconds",
-                label,
-                iterations,
-                elapsed_time
-            );
-        }
-        Err(_) => {
-            eprintln!("Fork failed!");
-            exit(1);
-        }
+    fn reset(&mut self, _iterations: &mut usize, _size: usize) {}
+
+    fn verify(&self, iterations: &usize, output: &Self::Output) -> Result<(), String> {
+        check_sum(output.1, *iterations)
+    }
+
+    fn describe(&self, iterations: usize) -> String {
+        format!("for {} with {} iterations", self.name(), iterations)
     }
 }
 
-fn run_refcell_perf_test(test_function: fn(usize) -> f64, iterations: usize, label: &str) {
-    let parent_pid = std::process::id();
+struct TreeRefCell;
+
+impl Benchmark for TreeRefCell {
+    type Input = usize;
+    type Output = (Rc<TreeNodeRefCell>, i64);
 
-    match unsafe { fork() } {
-        Ok(ForkResult::Child) => {
-            let perf_command = format!(
-                "perf stat -e cycles,instructions,cache-references,cache-misses -p {} > perf_output_refcell.log 2>&1",
-                parent_pid
-            );
+    fn name(&self) -> &str {
+        "MyStructRefCell"
+    }
 
-            Command::new("sh")
-                .arg("-c")
-                .arg(perf_command)
-                .spawn()
-                .expect("Failed to start perf stat");
+    fn size_name(&self) -> &str {
+        "iterations"
+    }
 
-            exit(0);
-        }
-        Ok(ForkResult::Parent { child }) => {
-            let child_pid = child.as_raw();
-
-            sleep(Duration::from_millis(500));
-
-            println!("\nRunning {} test with {} iterations...\n", label, iterations);
-            let elapsed_time = test_function(iterations);
-
-            let _ = kill(Pid::from_raw(child_pid), Signal::SIGINT);
-            let _ = waitpid(Pid::from_raw(child_pid), None);
-
-            if let Ok(file) = File::open("perf_output_refcell.log") {
-                let reader = BufReader::new(file);
-                println!("\n[ Perf Stat Output for {} ]", label);
-                for line in reader.lines() {
-                    if let Ok(l) = line {
-                        println!("{}", l);
-                    }
-                }
-            } else {
-                eprintln!("Failed to read perf stat log");
-            }
+    fn default_size(&self) -> Option<usize> {
+        Some(1_000_000)
+    }
 
-            println!(
-                "\nTime taken for {} with {} iterations: {:.6} seconds",
-                label,
-                iterations,
-                elapsed_time
-            );
-        }
-        Err(_) => {
-            eprintln!("Fork failed!");
-            exit(1);
+    fn setup(&mut self, iterations: usize) -> usize {
+        iterations
+    }
+
+    fn run(&mut self, iterations: &mut usize) -> Self::Output {
+        let tree = TreeNodeRefCell::new(0);
+        for i in 1..=*iterations {
+            TreeNodeRefCell::insert(&tree, i as i64);
         }
+        let sum = TreeNodeRefCell::sum(&tree);
+        (tree, sum)
+    }
+
+    fn reset(&mut self, _iterations: &mut usize, _size: usize) {}
+
+    fn verify(&self, iterations: &usize, output: &Self::Output) -> Result<(), String> {
+        check_sum(output.1, *iterations)
+    }
+
+    fn describe(&self, iterations: usize) -> String {
+        format!("for {} with {} iterations", self.name(), iterations)
     }
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let iterations: usize = if args.len() == 2 {
-        args[1].parse().unwrap_or_else(|_| {
-            eprintln!("Invalid number of iterations, using default (1_000_000)");
-            1_000_000
-        })
-    } else {
-        1_000_000
-    };
-
-    run_perf_test(test_tree, iterations, "MyStruct");
-    run_refcell_perf_test(test_tree_refcell, iterations, "MyStructRefCell");
-}
\ No newline at end of file
+    let runner = Runner::new().counters(true);
+    runner.run(Tree);
+    runner.run(TreeRefCell);
+}
@@ -0,0 +1,16 @@
+// Compiles the C implementation shared with the Makefile so the benchmarks
+// can call it through FFI.
+fn main() {
+    println!("cargo:rerun-if-changed=selection_sort_algo.c");
+    println!("cargo:rerun-if-changed=selection_sort_algo.h");
+    println!("cargo:rerun-if-changed=../harness/c/ops.h");
+    let mut build = cc::Build::new();
+    if std::env::var_os("CARGO_FEATURE_INSTRUMENT").is_some() {
+        build.define("INSTRUMENT", None);
+    }
+    build
+        .include("../harness/c")
+        .file("selection_sort_algo.c")
+        .opt_level(3)
+        .compile("selection_sort_algo");
+}
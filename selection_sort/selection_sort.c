@@ -1,20 +1,9 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <time.h>
-
-void selection_sort(int arr[], int n) {
-    for (int i = 0; i < n - 1; i++) {
-        int min_idx = i;
-        for (int j = i + 1; j < n; j++) {
-            if (arr[j] < arr[min_idx]) {
-                min_idx = j;
-            }
-        }
-        int temp = arr[i];
-        arr[i] = arr[min_idx];
-        arr[min_idx] = temp;
-    }
-}
+#include "selection_sort_algo.h"
+#include "bench_input.h"
+#include "bench_rng.h"
 
 double diff_timespec(struct timespec *time1, struct timespec *time0) {
     return (time1->tv_sec - time0->tv_sec)
@@ -22,8 +11,12 @@ double diff_timespec(struct timespec *time1, struct timespec *time0) {
 }
 
 int main(int argc, char *argv[]) {
-    if (argc != 2) {
-        printf("Usage: %s <array_size>\n", argv[0]);
+    uint64_t seed = BENCH_DEFAULT_SEED;
+    if (bench_take_seed(&argc, argv, &seed) != 0) {
+        return 1;
+    }
+    if (argc != 2 && argc != 3) {
+        printf("Usage: %s <array_size> [input_file] [--seed N]\n", argv[0]);
         return 1;
     }
     
@@ -40,9 +33,18 @@ int main(int argc, char *argv[]) {
         return 1;
     }
 
-    srand(10000);
-    for (int i = 0; i < size; i++) {
-        arr[i] = rand(); 
+    if (argc == 3) {
+        free(arr);
+        arr = bench_input_load(argv[2], size, 0, size);
+        if (!arr) {
+            return 1;
+        }
+    } else {
+        // Like the Rust default, uniform:min=0,max=2147483647
+        BenchRng rng = {seed};
+        for (int i = 0; i < size; i++) {
+            arr[i] = bench_rng_range(&rng, 0, INT32_MAX);
+        }
     }
 
     clock_gettime(CLOCK_MONOTONIC, &start_time);
@@ -50,7 +52,12 @@ int main(int argc, char *argv[]) {
     clock_gettime(CLOCK_MONOTONIC, &end_time);
 
     double time_elapsed = diff_timespec(&end_time, &start_time);
+#ifdef INSTRUMENT
+    printf("Time taken to sort the array of size %d: %f seconds, with %llu comparisons, %llu swaps\n", size,
+           time_elapsed, (unsigned long long)selectionSortOps.comparisons, (unsigned long long)selectionSortOps.swaps);
+#else
     printf("Time taken to sort the array of size %d: %f seconds\n", size, time_elapsed);
+#endif
 
     free(arr);
     return 0;
@@ -0,0 +1,19 @@
+#include "selection_sort_algo.h"
+
+OpCounts selectionSortOps;
+
+void selection_sort(int arr[], int n) {
+    for (int i = 0; i < n - 1; i++) {
+        int min_idx = i;
+        for (int j = i + 1; j < n; j++) {
+            OPS_COMPARE(selectionSortOps);
+            if (arr[j] < arr[min_idx]) {
+                min_idx = j;
+            }
+        }
+        OPS_SWAP(selectionSortOps);
+        int temp = arr[i];
+        arr[i] = arr[min_idx];
+        arr[min_idx] = temp;
+    }
+}
@@ -0,0 +1,12 @@
+#ifndef SELECTION_SORT_ALGO_H
+#define SELECTION_SORT_ALGO_H
+
+#include "ops.h"
+
+// Operations of selection_sort since they were last reset to 0. Only
+// counted when compiled with -DINSTRUMENT.
+extern OpCounts selectionSortOps;
+
+void selection_sort(int arr[], int n);
+
+#endif
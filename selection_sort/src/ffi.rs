@@ -0,0 +1,28 @@
+//! Bindings to the C implementation in `selection_sort_algo.c`.
+
+use std::os::raw::c_int;
+
+use harness::ops::{self, OpCounts};
+
+extern "C" {
+    #[link_name = "selectionSortOps"]
+    static mut SELECTION_SORT_OPS: OpCounts;
+
+    #[link_name = "selection_sort"]
+    fn c_selection_sort(arr: *mut c_int, n: c_int);
+}
+
+/// Sorts `arr` with the C `selection_sort` and returns what it counted,
+/// which is only kept with the `instrument` feature.
+pub fn selection_sort(arr: &mut [i32]) -> Option<OpCounts> {
+    let n = c_int::try_from(arr.len()).expect("array too large for the C implementation");
+    // SAFETY: `arr` holds n ints. The counts are only touched from the
+    // benchmark thread.
+    let counts = unsafe {
+        let ops = std::ptr::addr_of_mut!(SELECTION_SORT_OPS);
+        ops.write(OpCounts::default());
+        c_selection_sort(arr.as_mut_ptr(), n);
+        ops.read()
+    };
+    ops::ENABLED.then_some(counts)
+}
@@ -0,0 +1,149 @@
+use std::cmp::Ordering;
+
+use harness::distribution::Distribution;
+use harness::input::DEFAULT_SEED;
+use harness::ops::{self, OpCounts};
+use harness::{Args, Benchmark, Language};
+
+pub mod ffi;
+
+pub fn selection_sort<T: Ord>(arr: &mut [T]) {
+    selection_sort_by(arr, &mut T::cmp);
+}
+
+/// [`selection_sort`] with a comparator, which may also be a `dyn FnMut`.
+pub fn selection_sort_by<T, F>(arr: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering + ?Sized,
+{
+    let n = arr.len();
+    for i in 0..n - 1 {
+        let mut min_idx = i;
+        for j in i + 1..n {
+            ops::compare();
+            if compare(&arr[j], &arr[min_idx]) == Ordering::Less {
+                min_idx = j;
+            }
+        }
+        ops::swap();
+        arr.swap(i, min_idx);
+    }
+}
+
+/// The array to sort and a copy of it, so every run and language can be
+/// handed exactly the same values, and those values sorted by
+/// `slice::sort_unstable` to check every run against.
+pub struct SortInput {
+    pub original: Vec<i32>,
+    pub arr: Vec<i32>,
+    pub expected: Vec<i32>,
+}
+
+pub struct SelectionSort {
+    /// Distribution of the array, unless `--dist` picks another.
+    pub distribution: Distribution,
+    language: Language,
+    seed: u64,
+}
+
+impl SelectionSort {
+    pub fn new(distribution: Distribution) -> Self {
+        SelectionSort {
+            distribution,
+            language: Language::Rust,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+impl Benchmark for SelectionSort {
+    type Input = SortInput;
+    /// What the sort counted, with the `instrument` feature.
+    type Output = Option<OpCounts>;
+
+    fn name(&self) -> &str {
+        "selection_sort"
+    }
+
+    fn variant(&self) -> String {
+        self.distribution.to_string()
+    }
+
+    fn size_name(&self) -> &str {
+        "array_size"
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn set_language(&mut self, language: Language) -> bool {
+        self.language = language;
+        true
+    }
+
+    fn options(&self) -> &str {
+        "[--dist NAME[:param=value,...]]"
+    }
+
+    fn configure(&mut self, args: &mut Args) -> Result<(), String> {
+        self.distribution = args.parse("--dist")?.unwrap_or(self.distribution);
+        Ok(())
+    }
+
+    fn setup(&mut self, size: usize) -> Self::Input {
+        let arr = self.distribution.generate(size, self.seed);
+        let mut expected = arr.clone();
+        expected.sort_unstable();
+        SortInput {
+            original: arr.clone(),
+            arr,
+            expected,
+        }
+    }
+
+    fn input_arrays(&self, input: &Self::Input) -> Option<Vec<Vec<i32>>> {
+        Some(vec![input.original.clone()])
+    }
+
+    fn reset(&mut self, input: &mut Self::Input, _size: usize) {
+        input.arr.copy_from_slice(&input.original);
+    }
+
+    fn run(&mut self, input: &mut Self::Input) -> Option<OpCounts> {
+        match self.language {
+            Language::Rust => {
+                ops::reset();
+                selection_sort(&mut input.arr);
+                ops::take()
+            }
+            Language::C => ffi::selection_sort(&mut input.arr),
+        }
+    }
+
+    fn verify(&self, input: &Self::Input, _counts: &Option<OpCounts>) -> Result<(), String> {
+        match input.arr.iter().zip(&input.expected).position(|(a, b)| a != b) {
+            Some(i) => Err(format!(
+                "index {} holds {}, expected {}",
+                i, input.arr[i], input.expected[i]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn describe(&self, size: usize) -> String {
+        format!("to sort the array of size {}", size)
+    }
+
+    fn summary(&self, counts: &Option<OpCounts>) -> Option<String> {
+        counts.map(|counts| format!("with {}", counts))
+    }
+}